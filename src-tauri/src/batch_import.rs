@@ -0,0 +1,105 @@
+//! Lists of series links pasted as text, copied from a spreadsheet or saved as CSV.
+//!
+//! Every line is scanned for URLs; CSV and TSV cells, quotes and surrounding notes are ignored.
+//! Blank lines and `#` comments are dropped. Resolving the links is left to the caller so it can
+//! rate-limit the requests.
+
+use crate::error::AppError;
+use crate::loaded_series::LoadedSeriesSummary;
+use regex::Regex;
+use serde::Serialize;
+
+/// One line of the list with the URLs found on it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportEntry {
+    /// 1-based line number
+    pub line: usize,
+    pub text: String,
+    pub urls: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ImportStatus {
+    Imported,
+    /// The same series appeared on an earlier line, `of`
+    Duplicate { of: usize },
+    /// No URL on the line, e.g. a CSV header
+    Skipped,
+    /// The URLs on the line don't point at a series on a supported site
+    Invalid,
+    /// The series couldn't be loaded
+    Failed,
+}
+
+/// Outcome for one line of the list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportLine {
+    pub line: usize,
+    pub text: String,
+    pub url: Option<String>,
+    #[serde(flatten)]
+    pub status: ImportStatus,
+    pub series: Option<LoadedSeriesSummary>,
+    pub error: Option<AppError>,
+}
+
+impl ImportLine {
+    pub fn new(entry: &ImportEntry, status: ImportStatus) -> Self {
+        Self {
+            line: entry.line,
+            text: entry.text.clone(),
+            url: entry.urls.first().cloned(),
+            status,
+            series: None,
+            error: None,
+        }
+    }
+}
+
+/// Lines worth reporting on, with the URLs on each
+pub fn read_entries(text: &str) -> Vec<ImportEntry> {
+    let url_re = Regex::new(r#"https?://[^\s,;|"'<>]+"#).unwrap();
+
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, text)| ImportEntry {
+            line,
+            text: text.to_string(),
+            urls: url_re
+                .find_iter(text)
+                .map(|m| m.as_str().trim_end_matches(['.', ')']).to_string())
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_entries() {
+        let text = "title,url,notes\n\
+            \"Love Story\",\"https://rongyok.com/watch/?series_id=1004\",\"ep 1-40\"\n\
+            \n\
+            # finished\n\
+            https://www.thongyok.com/series/7/title\thttps://img.example.com/7.jpg\n\
+            see https://rongyok.com/watch/?series_id=12.\n";
+
+        let entries = read_entries(text);
+        let summary: Vec<_> = entries.iter().map(|e| (e.line, e.urls.clone())).collect();
+        assert_eq!(summary, vec![
+            (1, vec![]),
+            (2, vec!["https://rongyok.com/watch/?series_id=1004".to_string()]),
+            (5, vec![
+                "https://www.thongyok.com/series/7/title".to_string(),
+                "https://img.example.com/7.jpg".to_string(),
+            ]),
+            (6, vec!["https://rongyok.com/watch/?series_id=12".to_string()]),
+        ]);
+    }
+}
@@ -0,0 +1,153 @@
+//! Series listed on search, genre and category pages, rendered as cards linking to
+//! series pages, plus the pagination links of those listings.
+
+use crate::metadata::{collapse_whitespace, join_url};
+use crate::sites::SiteExtractor;
+use regex::Regex;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// One series as listed in search results or catalog pages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesSummary {
+    pub series_id: i32,
+    pub site: String,
+    /// Series page URL, ready for `fetch_series`
+    pub url: String,
+    pub title: String,
+    pub poster_url: Option<String>,
+    pub total_episodes: Option<i32>,
+}
+
+/// One page of a genre, category or "latest updates" listing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesPage {
+    pub url: String,
+    /// 1-based page number
+    pub page: u32,
+    /// Highest page number linked from the pagination, if any
+    pub total_pages: Option<u32>,
+    pub next_page_url: Option<String>,
+    pub series: Vec<SeriesSummary>,
+}
+
+/// Series cards and pagination of a listing page
+pub fn extract_listing_page<S: SiteExtractor + ?Sized>(html: &str, page_url: &str, site: &S) -> SeriesPage {
+    let series = site.parse_series_list(html, page_url);
+
+    let document = Html::parse_document(html);
+    let base = Url::parse(page_url).ok();
+    let page = page_number(page_url).unwrap_or(1);
+
+    // <link rel="next">, <a rel="next"> or a.next, as rendered by most WordPress themes
+    let next_selector = Selector::parse("link[rel~='next'], a[rel~='next'], a.next, .next a, a.nextpostslink").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+
+    let pages: Vec<(u32, String)> = document
+        .select(&link_selector)
+        .filter_map(|a| {
+            let href = join_url(base.as_ref(), a.value().attr("href")?)?;
+            Some((page_number(&href)?, href))
+        })
+        .collect();
+
+    let next_page_url = document
+        .select(&next_selector)
+        .find_map(|el| join_url(base.as_ref(), el.value().attr("href")?))
+        .or_else(|| pages.iter().find(|(n, _)| *n == page + 1).map(|(_, href)| href.clone()))
+        .filter(|next| next != page_url);
+    let total_pages = pages.iter().map(|(n, _)| *n).chain(next_page_url.as_ref().map(|_| page + 1)).max();
+
+    SeriesPage {
+        url: page_url.to_string(),
+        page,
+        total_pages: total_pages.map(|n| n.max(page)),
+        next_page_url,
+        series,
+    }
+}
+
+/// Page number from `/page/3/`, `?page=3` or `?paged=3`
+fn page_number(url: &str) -> Option<u32> {
+    let re = Regex::new(r"(?:/page/|[?&](?:page|paged)=)(\d+)").unwrap();
+    re.captures(url).and_then(|c| c[1].parse().ok())
+}
+
+/// Series cards on a listing page: every link to a series of `site`, grouped per series,
+/// with title, poster and episode count taken from the card around the link
+pub fn extract_series_cards<S: SiteExtractor + ?Sized>(html: &str, page_url: &str, site: &S) -> Vec<SeriesSummary> {
+    let document = Html::parse_document(html);
+    let base = Url::parse(page_url).ok();
+
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let img_selector = Selector::parse("img").unwrap();
+    let heading_selector = Selector::parse("[class*='title'], h1, h2, h3, h4").unwrap();
+    let count_re = Regex::new(r"(?i)(\d+)\s*(?:ตอน|episodes?|eps?\b)|(?:ตอนที่|EP\.?)\s*(\d+)").unwrap();
+
+    let series_of = |link: &ElementRef| -> Option<(i32, String)> {
+        let href = join_url(base.as_ref(), link.value().attr("href")?)?;
+        if !site.matches(&href) {
+            return None;
+        }
+        Some((site.parse_series_id(&href)?, href))
+    };
+
+    let mut cards: Vec<SeriesSummary> = Vec::new();
+    for link in document.select(&link_selector) {
+        let Some((series_id, href)) = series_of(&link) else { continue };
+
+        // Widen to the enclosing card, stopping before it would include another series
+        let mut card = link;
+        for ancestor in link.ancestors().filter_map(ElementRef::wrap).take(4) {
+            let other = ancestor
+                .select(&link_selector)
+                .any(|a| series_of(&a).is_some_and(|(id, _)| id != series_id));
+            if other {
+                break;
+            }
+            card = ancestor;
+        }
+
+        let image = link.select(&img_selector).next().or_else(|| card.select(&img_selector).next());
+        let title = link
+            .value()
+            .attr("title")
+            .map(collapse_whitespace)
+            .or_else(|| card.select(&heading_selector).next().map(|el| collapse_whitespace(&el.text().collect::<String>())))
+            .or_else(|| image.and_then(|img| img.value().attr("alt")).map(collapse_whitespace))
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| collapse_whitespace(&link.text().collect::<String>()));
+        let poster_url = image
+            .and_then(|img| img.value().attr("data-src").or_else(|| img.value().attr("src")))
+            .and_then(|src| join_url(base.as_ref(), src));
+        let total_episodes = count_re
+            .captures(&card.text().collect::<String>())
+            .and_then(|c| c.get(1).or_else(|| c.get(2))?.as_str().parse().ok());
+
+        match cards.iter_mut().find(|c| c.series_id == series_id) {
+            Some(existing) => {
+                if existing.title.is_empty() {
+                    existing.title = title;
+                }
+                existing.poster_url = existing.poster_url.take().or(poster_url);
+                existing.total_episodes = existing.total_episodes.or(total_episodes);
+            }
+            None => cards.push(SeriesSummary {
+                series_id,
+                site: site.name().to_string(),
+                url: site.series_url(series_id, Some(&href)),
+                title,
+                poster_url,
+                total_episodes,
+            }),
+        }
+    }
+
+    for card in cards.iter_mut().filter(|c| c.title.is_empty()) {
+        card.title = format!("Series {}", card.series_id);
+    }
+    cards
+}
@@ -0,0 +1,460 @@
+use crate::downloader::{fetch_segments_into, fetch_text, SegmentProgress, SegmentRequest};
+use crate::error::DownloadError;
+use crate::quality::{QualityPreference, Rendition};
+use regex::Regex;
+use reqwest::{Client, Url};
+use roxmltree::{Document, Node};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static TEMPLATE_VAR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$(RepresentationID|Bandwidth|Number|Time)?(?:%0(\d+)d)?\$").unwrap());
+static DURATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^P(?:(\d+)D)?(?:T(?:(\d+)H)?(?:(\d+)M)?(?:([\d.]+)S)?)?$").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Other,
+}
+
+/// A single downloadable rendition with all of its segment URLs resolved
+#[derive(Debug, Clone, PartialEq)]
+pub struct Representation {
+    pub id: String,
+    pub kind: TrackKind,
+    pub bandwidth: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub initialization: Option<String>,
+    pub segments: Vec<String>,
+}
+
+impl Rendition for Representation {
+    fn height(&self) -> Option<u32> {
+        self.height
+    }
+
+    fn bitrate(&self) -> Option<u64> {
+        Some(self.bandwidth).filter(|b| *b > 0)
+    }
+}
+
+/// SegmentTemplate attributes; Representation-level values override AdaptationSet-level ones
+#[derive(Debug, Clone, Default)]
+struct Template {
+    media: Option<String>,
+    initialization: Option<String>,
+    start_number: Option<u64>,
+    timescale: Option<u64>,
+    duration: Option<u64>,
+    /// Media time at the start of the period, in timescale units
+    presentation_time_offset: Option<u64>,
+    // (t, d, r) entries of the SegmentTimeline
+    timeline: Vec<(Option<u64>, u64, i64)>,
+}
+
+impl Template {
+    fn from_node(node: Node) -> Self {
+        let timeline = child(node, "SegmentTimeline")
+            .map(|tl| {
+                tl.children()
+                    .filter(|n| n.has_tag_name("S"))
+                    .map(|s| {
+                        (
+                            attr_u64(s, "t"),
+                            attr_u64(s, "d").unwrap_or(0),
+                            s.attribute("r").and_then(|r| r.parse().ok()).unwrap_or(0),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            media: node.attribute("media").map(str::to_string),
+            initialization: node.attribute("initialization").map(str::to_string),
+            start_number: attr_u64(node, "startNumber"),
+            timescale: attr_u64(node, "timescale"),
+            duration: attr_u64(node, "duration"),
+            presentation_time_offset: attr_u64(node, "presentationTimeOffset"),
+            timeline,
+        }
+    }
+
+    fn merge(parent: &Template, child: Template) -> Template {
+        Template {
+            media: child.media.or_else(|| parent.media.clone()),
+            initialization: child.initialization.or_else(|| parent.initialization.clone()),
+            start_number: child.start_number.or(parent.start_number),
+            timescale: child.timescale.or(parent.timescale),
+            duration: child.duration.or(parent.duration),
+            presentation_time_offset: child.presentation_time_offset.or(parent.presentation_time_offset),
+            timeline: if child.timeline.is_empty() { parent.timeline.clone() } else { child.timeline },
+        }
+    }
+}
+
+/// Check if a URL points to a DASH manifest
+pub fn is_dash_url(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.to_lowercase().ends_with(".mpd")
+}
+
+/// Parse an MPD manifest (first Period only), resolving every URL against `manifest_url`
+pub fn parse_manifest(text: &str, manifest_url: &str) -> Result<Vec<Representation>, String> {
+    let doc = Document::parse(text).map_err(|e| format!("Invalid MPD manifest: {}", e))?;
+    let mpd = doc.root_element();
+    if !mpd.has_tag_name("MPD") {
+        return Err("Not an MPD manifest".to_string());
+    }
+
+    let base = Url::parse(manifest_url).map_err(|e| format!("Invalid manifest URL: {}", e))?;
+    let base = with_base_url(&base, mpd)?;
+    let period = child(mpd, "Period").ok_or("MPD has no Period")?;
+    let base = with_base_url(&base, period)?;
+
+    let period_duration = period
+        .attribute("duration")
+        .or_else(|| mpd.attribute("mediaPresentationDuration"))
+        .and_then(parse_duration);
+
+    let mut representations = Vec::new();
+
+    for set in period.children().filter(|n| n.has_tag_name("AdaptationSet")) {
+        let set_base = with_base_url(&base, set)?;
+        let set_template = child(set, "SegmentTemplate").map(Template::from_node);
+        let set_kind = content_kind(set);
+
+        for rep in set.children().filter(|n| n.has_tag_name("Representation")) {
+            let rep_base = with_base_url(&set_base, rep)?;
+            let id = rep.attribute("id").unwrap_or_default().to_string();
+            let bandwidth = attr_u64(rep, "bandwidth").unwrap_or(0);
+
+            let template = match (&set_template, child(rep, "SegmentTemplate").map(Template::from_node)) {
+                (Some(parent), Some(own)) => Some(Template::merge(parent, own)),
+                (Some(parent), None) => Some(parent.clone()),
+                (None, own) => own,
+            };
+
+            let (initialization, segments) = if let Some(template) = template {
+                expand_template(&template, &rep_base, &id, bandwidth, period_duration)?
+            } else if let Some(list) = child(rep, "SegmentList").or_else(|| child(set, "SegmentList")) {
+                let init = child(list, "Initialization")
+                    .and_then(|n| n.attribute("sourceURL"))
+                    .map(|u| resolve_uri(&rep_base, u))
+                    .transpose()?;
+                let segments = list
+                    .children()
+                    .filter(|n| n.has_tag_name("SegmentURL"))
+                    .filter_map(|n| n.attribute("media"))
+                    .map(|u| resolve_uri(&rep_base, u))
+                    .collect::<Result<Vec<_>, _>>()?;
+                (init, segments)
+            } else if [rep, set, period, mpd].into_iter().any(|n| child(n, "BaseURL").is_some()) {
+                // SegmentBase / plain BaseURL: the representation is a single file
+                (None, vec![rep_base.to_string()])
+            } else {
+                // Nothing to fetch but the manifest itself (e.g. a thumbnail track); the other
+                // representations are still usable
+                continue;
+            };
+
+            let kind = match content_kind(rep) {
+                TrackKind::Other => set_kind,
+                kind => kind,
+            };
+
+            representations.push(Representation {
+                id,
+                kind,
+                bandwidth,
+                width: attr_u64(rep, "width").map(|w| w as u32),
+                height: attr_u64(rep, "height").map(|h| h as u32),
+                initialization,
+                segments,
+            });
+        }
+    }
+
+    if representations.is_empty() {
+        return Err("MPD manifest has no representations".to_string());
+    }
+
+    Ok(representations)
+}
+
+/// Pick video and audio representations by preference (audio has no height, so bitrate decides)
+pub fn select_representations(
+    representations: &[Representation],
+    quality: QualityPreference,
+) -> (Option<&Representation>, Option<&Representation>) {
+    let video = quality.select(representations.iter().filter(|r| r.kind == TrackKind::Video));
+    let audio = quality.select(representations.iter().filter(|r| r.kind == TrackKind::Audio));
+    (video, audio)
+}
+
+/// Download the selected video and audio tracks of a DASH manifest into separate files
+/// next to `output`. Returns the track files written, video first.
+pub async fn download_stream(
+    client: &Client,
+    manifest_url: &str,
+    output: &Path,
+    quality: QualityPreference,
+    progress: &mut SegmentProgress<'_>,
+) -> Result<Vec<PathBuf>, DownloadError> {
+    let text = fetch_text(client, manifest_url).await?;
+    let representations = parse_manifest(&text, manifest_url).map_err(DownloadError::InvalidStream)?;
+
+    let (video, audio) = select_representations(&representations, quality);
+    let tracks: Vec<(&Representation, PathBuf)> = [
+        video.map(|r| (r, output.with_extension("video.part"))),
+        audio.map(|r| (r, output.with_extension("audio.part"))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if tracks.is_empty() {
+        return Err(DownloadError::InvalidStream("MPD manifest has no video or audio track".to_string()));
+    }
+
+    for (rep, _) in &tracks {
+        progress.add_segments(rep.segments.len() + rep.initialization.iter().count());
+    }
+
+    let mut files = Vec::new();
+    for (rep, path) in tracks {
+        let mut file = File::create(&path).map_err(|e| DownloadError::io(&path, e))?;
+        let requests = rep.initialization.iter().chain(rep.segments.iter()).cloned().map(SegmentRequest::from).collect();
+        fetch_segments_into(client, requests, &mut file, &path, progress, |_, data| Ok(data)).await?;
+        files.push(path);
+    }
+
+    Ok(files)
+}
+
+/// Expand a SegmentTemplate into the initialization URL and the list of media URLs
+fn expand_template(
+    template: &Template,
+    base: &Url,
+    rep_id: &str,
+    bandwidth: u64,
+    period_duration: Option<f64>,
+) -> Result<(Option<String>, Vec<String>), String> {
+    let fill = |pattern: &str, number: u64, time: u64| -> Result<String, String> {
+        resolve_uri(base, &substitute(pattern, rep_id, bandwidth, number, time))
+    };
+
+    let initialization = template
+        .initialization
+        .as_deref()
+        .map(|init| fill(init, 0, 0))
+        .transpose()?;
+
+    let media = match template.media.as_deref() {
+        Some(media) => media,
+        None => return Ok((initialization, Vec::new())),
+    };
+
+    let start_number = template.start_number.unwrap_or(1);
+    let timescale = template.timescale.unwrap_or(1).max(1);
+    let offset = template.presentation_time_offset.unwrap_or(0);
+    let mut segments = Vec::new();
+
+    if !template.timeline.is_empty() {
+        let period_end = period_duration.map(|d| offset + (d * timescale as f64) as u64);
+        let mut number = start_number;
+        let mut time = offset;
+
+        for &(t, d, r) in &template.timeline {
+            if let Some(t) = t {
+                time = t;
+            }
+            // r = -1 repeats until the end of the period
+            let repeats = if r < 0 {
+                match period_end {
+                    Some(end) if d > 0 => end.saturating_sub(time).div_ceil(d).saturating_sub(1),
+                    _ => 0,
+                }
+            } else {
+                r as u64
+            };
+            for _ in 0..=repeats {
+                segments.push(fill(media, number, time)?);
+                number += 1;
+                time += d;
+            }
+        }
+    } else if let (Some(duration), Some(total)) = (template.duration, period_duration) {
+        let segment_seconds = duration as f64 / timescale as f64;
+        let count = (total / segment_seconds).ceil() as u64;
+        for i in 0..count {
+            segments.push(fill(media, start_number + i, offset + i * duration)?);
+        }
+    } else {
+        return Err("SegmentTemplate without timeline or duration".to_string());
+    }
+
+    Ok((initialization, segments))
+}
+
+/// Replace $RepresentationID$, $Bandwidth$, $Number$ and $Time$ (with optional %0Nd width)
+fn substitute(pattern: &str, rep_id: &str, bandwidth: u64, number: u64, time: u64) -> String {
+    TEMPLATE_VAR_RE.replace_all(pattern, |caps: &regex::Captures| {
+        let width = caps.get(2).and_then(|w| w.as_str().parse().ok()).unwrap_or(0);
+        match caps.get(1).map(|m| m.as_str()) {
+            Some("RepresentationID") => rep_id.to_string(),
+            Some("Bandwidth") => format!("{:0width$}", bandwidth, width = width),
+            Some("Number") => format!("{:0width$}", number, width = width),
+            Some("Time") => format!("{:0width$}", time, width = width),
+            _ => "$".to_string(), // "$$" escape
+        }
+    })
+    .to_string()
+}
+
+/// Parse an ISO 8601 duration such as PT1H2M3.5S into seconds
+pub(crate) fn parse_duration(value: &str) -> Option<f64> {
+    let caps = DURATION_RE.captures(value.trim())?;
+    let part = |i: usize| caps.get(i).and_then(|m| m.as_str().parse::<f64>().ok()).unwrap_or(0.0);
+    Some(part(1) * 86400.0 + part(2) * 3600.0 + part(3) * 60.0 + part(4))
+}
+
+fn content_kind(node: Node) -> TrackKind {
+    let kind = node
+        .attribute("contentType")
+        .or_else(|| node.attribute("mimeType"))
+        .unwrap_or_default();
+    if kind.starts_with("video") {
+        TrackKind::Video
+    } else if kind.starts_with("audio") {
+        TrackKind::Audio
+    } else {
+        TrackKind::Other
+    }
+}
+
+fn with_base_url(base: &Url, node: Node) -> Result<Url, String> {
+    match child(node, "BaseURL").and_then(|n| n.text()) {
+        Some(text) => base
+            .join(text.trim())
+            .map_err(|e| format!("Invalid BaseURL {}: {}", text, e)),
+        None => Ok(base.clone()),
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn attr_u64(node: Node, name: &str) -> Option<u64> {
+    node.attribute(name).and_then(|v| v.parse().ok())
+}
+
+fn resolve_uri(base: &Url, uri: &str) -> Result<String, String> {
+    base.join(uri)
+        .map(|u| u.to_string())
+        .map_err(|e| format!("Invalid URI {}: {}", uri, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_segment_template() {
+        let mpd = r#"<?xml version="1.0"?>
+            <MPD xmlns="urn:mpeg:dash:schema:mpd:2011" mediaPresentationDuration="PT10S">
+              <Period>
+                <AdaptationSet contentType="video">
+                  <SegmentTemplate timescale="1000" duration="4000" startNumber="1"
+                      initialization="$RepresentationID$/init.mp4" media="$RepresentationID$/seg-$Number%03d$.m4s"/>
+                  <Representation id="360p" bandwidth="800000" width="640" height="360"/>
+                  <Representation id="720p" bandwidth="2400000" width="1280" height="720"/>
+                </AdaptationSet>
+                <AdaptationSet mimeType="audio/mp4">
+                  <Representation id="aud" bandwidth="128000">
+                    <SegmentTemplate timescale="48000" initialization="a/init.mp4" media="a/$Time$.m4s">
+                      <SegmentTimeline><S t="0" d="96000" r="1"/><S d="48000"/></SegmentTimeline>
+                    </SegmentTemplate>
+                  </Representation>
+                </AdaptationSet>
+              </Period>
+            </MPD>"#;
+
+        let reps = parse_manifest(mpd, "https://example.com/ep1/manifest.mpd").unwrap();
+        let (video, audio) = select_representations(&reps, QualityPreference::Highest);
+
+        let video = video.unwrap();
+        assert_eq!(video.id, "720p");
+        assert_eq!(video.initialization.as_deref(), Some("https://example.com/ep1/720p/init.mp4"));
+        assert_eq!(video.segments.len(), 3); // ceil(10s / 4s)
+        assert_eq!(video.segments[2], "https://example.com/ep1/720p/seg-003.m4s");
+
+        let audio = audio.unwrap();
+        assert_eq!(audio.segments, vec![
+            "https://example.com/ep1/a/0.m4s",
+            "https://example.com/ep1/a/96000.m4s",
+            "https://example.com/ep1/a/192000.m4s",
+        ]);
+    }
+
+    #[test]
+    fn test_parse_segment_list() {
+        let mpd = r#"<MPD><Period><AdaptationSet mimeType="video/mp4">
+              <Representation id="v" bandwidth="1000">
+                <BaseURL>https://cdn.example.com/v/</BaseURL>
+                <SegmentList><Initialization sourceURL="init.mp4"/>
+                  <SegmentURL media="1.m4s"/><SegmentURL media="2.m4s"/></SegmentList>
+              </Representation></AdaptationSet></Period></MPD>"#;
+
+        let reps = parse_manifest(mpd, "https://example.com/manifest.mpd").unwrap();
+        assert_eq!(reps[0].kind, TrackKind::Video);
+        assert_eq!(reps[0].initialization.as_deref(), Some("https://cdn.example.com/v/init.mp4"));
+        assert_eq!(reps[0].segments, vec!["https://cdn.example.com/v/1.m4s", "https://cdn.example.com/v/2.m4s"]);
+
+        // Without segments or a BaseURL there is nothing to download but the manifest itself,
+        // so that representation is skipped; an MPD-level BaseURL names a file to fetch
+        let mixed = r#"<MPD><Period>
+              <AdaptationSet contentType="image"><Representation id="thumbs" bandwidth="1"/></AdaptationSet>
+              <AdaptationSet mimeType="video/mp4"><Representation id="v" bandwidth="1000">
+                <BaseURL>v.mp4</BaseURL></Representation></AdaptationSet></Period></MPD>"#;
+        let reps = parse_manifest(mixed, "https://example.com/manifest.mpd").unwrap();
+        assert_eq!(reps.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), vec!["v"]);
+
+        let mpd_base = r#"<MPD><BaseURL>https://cdn.example.com/ep1.mp4</BaseURL><Period>
+              <AdaptationSet mimeType="video/mp4"><Representation id="v" bandwidth="1000"/></AdaptationSet>
+              </Period></MPD>"#;
+        let reps = parse_manifest(mpd_base, "https://example.com/manifest.mpd").unwrap();
+        assert_eq!(reps[0].segments, vec!["https://cdn.example.com/ep1.mp4"]);
+
+        let bare = r#"<MPD><Period><AdaptationSet mimeType="video/mp4">
+              <Representation id="v" bandwidth="1000"/></AdaptationSet></Period></MPD>"#;
+        assert!(parse_manifest(bare, "https://example.com/manifest.mpd").is_err());
+    }
+
+    #[test]
+    fn test_presentation_time_offset() {
+        let mpd = r#"<MPD mediaPresentationDuration="PT8S"><Period><AdaptationSet mimeType="audio/mp4">
+              <SegmentTemplate timescale="1000" presentationTimeOffset="90000" media="a/$Time$.m4s">
+                <SegmentTimeline><S d="4000" r="-1"/></SegmentTimeline></SegmentTemplate>
+              <Representation id="a" bandwidth="1000"/>
+              <Representation id="b" bandwidth="2000">
+                <SegmentTemplate duration="4000" media="b/$Time$.m4s"/></Representation>
+              </AdaptationSet></Period></MPD>"#;
+
+        let reps = parse_manifest(mpd, "https://example.com/manifest.mpd").unwrap();
+        assert_eq!(reps[0].segments, vec!["https://example.com/a/90000.m4s", "https://example.com/a/94000.m4s"]);
+        assert_eq!(reps[1].segments, vec!["https://example.com/b/90000.m4s", "https://example.com/b/94000.m4s"]);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H2M3.5S"), Some(3723.5));
+        assert_eq!(parse_duration("PT45S"), Some(45.0));
+        assert_eq!(parse_duration("bogus"), None);
+    }
+}
@@ -1,15 +1,25 @@
-use futures_util::StreamExt;
+use crate::dash;
+use crate::error::{DownloadError, MergeError};
+use crate::hls;
+use crate::network::{build_client, ProxySettings};
+use crate::quality::QualityPreference;
+use crate::session::Session;
+use crate::subtitles::{combine_srt, sidecar_subtitles, subtitle_path, to_srt, SubtitleTrack};
+use futures_util::{stream, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use tauri::{AppHandle, Emitter};
 use tokio::time::{sleep, Duration};
 
+/// Number of media segments (HLS/DASH) fetched in parallel
+const SEGMENT_CONCURRENCY: usize = 4;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadProgress {
@@ -28,13 +38,20 @@ pub struct MergeProgress {
     pub total_duration: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadResult {
     pub episode: i32,
     pub success: bool,
     pub file_path: Option<String>,
-    pub error: Option<String>,
+    pub error: Option<DownloadError>,
+}
+
+impl DownloadResult {
+    /// The signed video URL was rejected and needs re-resolving
+    pub fn url_expired(&self) -> bool {
+        matches!(self.error, Some(DownloadError::UrlExpired { .. }))
+    }
 }
 
 #[derive(Clone)]
@@ -42,6 +59,10 @@ pub struct DownloadConfig {
     pub speed_limit_kbps: i32,  // 0 = unlimited
     pub file_naming: String,    // "ep_001", "episode_1", "title_ep1"
     pub series_title: String,
+    pub quality: QualityPreference,
+    /// Cookie jar shared with the parser
+    pub session: Session,
+    pub proxy: ProxySettings,
 }
 
 impl Default for DownloadConfig {
@@ -50,6 +71,9 @@ impl Default for DownloadConfig {
             speed_limit_kbps: 0,
             file_naming: "ep_001".to_string(),
             series_title: "".to_string(),
+            quality: QualityPreference::default(),
+            session: Session::default(),
+            proxy: ProxySettings::default(),
         }
     }
 }
@@ -77,10 +101,7 @@ pub struct VideoDownloader {
 
 impl VideoDownloader {
     pub fn with_config(output_dir: &str, config: DownloadConfig) -> Self {
-        let client = Client::builder()
-            .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36")
-            .build()
-            .expect("Failed to create HTTP client");
+        let client = build_client(&config.session, &config.proxy).expect("Failed to create HTTP client");
 
         // Expand ~ to home directory
         let expanded_dir = if output_dir.starts_with("~/") {
@@ -118,6 +139,15 @@ impl VideoDownloader {
         self.output_dir.join(filename)
     }
 
+    /// Speed limit in bytes per second (0 = unlimited)
+    fn speed_limit_bytes(&self) -> u64 {
+        if self.config.speed_limit_kbps > 0 {
+            (self.config.speed_limit_kbps as u64) * 1024
+        } else {
+            0
+        }
+    }
+
     pub async fn download_episode(
         &self,
         episode: i32,
@@ -125,6 +155,13 @@ impl VideoDownloader {
         app_handle: &AppHandle,
         download_state: Option<Arc<DownloadState>>,
     ) -> DownloadResult {
+        if hls::is_hls_url(video_url) {
+            return self.download_hls_episode(episode, video_url, app_handle, download_state).await;
+        }
+        if dash::is_dash_url(video_url) {
+            return self.download_dash_episode(episode, video_url, app_handle, download_state).await;
+        }
+
         let file_path = self.get_episode_filename(episode);
 
         // Check for existing partial download
@@ -148,21 +185,11 @@ impl VideoDownloader {
                     episode,
                     success: false,
                     file_path: None,
-                    error: Some(format!("Request failed: {}", e)),
+                    error: Some(DownloadError::Network(e.to_string())),
                 };
             }
         };
 
-        // Get content length
-        let total_size = if start_byte > 0 {
-            response
-                .content_length()
-                .map(|cl| cl + start_byte)
-                .unwrap_or(0)
-        } else {
-            response.content_length().unwrap_or(0)
-        };
-
         // Check if server returned 416 (Range Not Satisfiable) - file is complete
         if response.status().as_u16() == 416 {
             return DownloadResult {
@@ -173,6 +200,42 @@ impl VideoDownloader {
             };
         }
 
+        // Signed CDN URLs are rejected once they expire. Keep the partial file so the
+        // download can continue from the same offset with a re-resolved URL.
+        let status = response.status().as_u16();
+        if status == 403 || status == 410 {
+            return DownloadResult {
+                episode,
+                success: false,
+                file_path: None,
+                error: Some(DownloadError::UrlExpired { status }),
+            };
+        }
+
+        if !response.status().is_success() {
+            return DownloadResult {
+                episode,
+                success: false,
+                file_path: None,
+                error: Some(DownloadError::HttpStatus { status, url: video_url.to_string() }),
+            };
+        }
+
+        // Server ignored the Range header - start over instead of appending a second copy
+        if start_byte > 0 && status == 200 {
+            start_byte = 0;
+        }
+
+        // Get content length
+        let total_size = if start_byte > 0 {
+            response
+                .content_length()
+                .map(|cl| cl + start_byte)
+                .unwrap_or(0)
+        } else {
+            response.content_length().unwrap_or(0)
+        };
+
         // Open file for writing
         let mut file = match if start_byte > 0 {
             fs::OpenOptions::new().append(true).open(&file_path)
@@ -185,7 +248,7 @@ impl VideoDownloader {
                     episode,
                     success: false,
                     file_path: None,
-                    error: Some(format!("Failed to create file: {}", e)),
+                    error: Some(DownloadError::io(&file_path, e)),
                 };
             }
         };
@@ -196,11 +259,7 @@ impl VideoDownloader {
         let mut last_emit = std::time::Instant::now();
 
         // Speed limiting variables
-        let speed_limit_bytes = if self.config.speed_limit_kbps > 0 {
-            (self.config.speed_limit_kbps as u64) * 1024
-        } else {
-            0
-        };
+        let speed_limit_bytes = self.speed_limit_bytes();
         let mut interval_downloaded: u64 = 0;
         let mut interval_start = std::time::Instant::now();
 
@@ -214,7 +273,7 @@ impl VideoDownloader {
                         episode,
                         success: false,
                         file_path: None,
-                        error: Some("Download cancelled".to_string()),
+                        error: Some(DownloadError::Cancelled),
                     };
                 }
 
@@ -226,7 +285,7 @@ impl VideoDownloader {
                             episode,
                             success: false,
                             file_path: None,
-                            error: Some("Download cancelled".to_string()),
+                            error: Some(DownloadError::Cancelled),
                         };
                     }
                     sleep(Duration::from_millis(100)).await;
@@ -240,7 +299,7 @@ impl VideoDownloader {
                             episode,
                             success: false,
                             file_path: None,
-                            error: Some(format!("Write failed: {}", e)),
+                            error: Some(DownloadError::io(&file_path, e)),
                         };
                     }
 
@@ -300,7 +359,7 @@ impl VideoDownloader {
                         episode,
                         success: false,
                         file_path: Some(file_path.to_string_lossy().to_string()),
-                        error: Some(format!("Download stream error: {}", e)),
+                        error: Some(DownloadError::Network(e.to_string())),
                     };
                 }
             }
@@ -313,6 +372,316 @@ impl VideoDownloader {
             error: None,
         }
     }
+
+    /// Download an HLS stream segment by segment, then remux it (and any separate audio) to MP4
+    async fn download_hls_episode(
+        &self,
+        episode: i32,
+        playlist_url: &str,
+        app_handle: &AppHandle,
+        download_state: Option<Arc<DownloadState>>,
+    ) -> DownloadResult {
+        let file_path = self.get_episode_filename(episode);
+        let ts_path = file_path.with_extension("ts.part");
+        let mut progress = SegmentProgress::new(episode, app_handle, download_state, self.speed_limit_bytes());
+
+        let result = hls::download_stream(&self.client, playlist_url, &ts_path, self.config.quality, &mut progress)
+            .await
+            .and_then(|tracks| {
+                let inputs: Vec<String> = tracks.iter().map(|t| t.to_string_lossy().to_string()).collect();
+                remux_to_mp4(&inputs, &file_path.to_string_lossy()).map_err(DownloadError::from)
+            });
+
+        // The stream intermediates (MPEG-TS or fMP4, plus separate audio) are never kept
+        let _ = fs::remove_file(&ts_path);
+        let _ = fs::remove_file(ts_path.with_extension("audio.part"));
+
+        segmented_result(episode, &file_path, result)
+    }
+
+    /// Download the preferred video and audio tracks of a DASH manifest, then mux them to MP4
+    async fn download_dash_episode(
+        &self,
+        episode: i32,
+        manifest_url: &str,
+        app_handle: &AppHandle,
+        download_state: Option<Arc<DownloadState>>,
+    ) -> DownloadResult {
+        let file_path = self.get_episode_filename(episode);
+        let mut progress = SegmentProgress::new(episode, app_handle, download_state, self.speed_limit_bytes());
+
+        let result = dash::download_stream(&self.client, manifest_url, &file_path, self.config.quality, &mut progress)
+            .await
+            .and_then(|tracks| {
+                let inputs: Vec<String> = tracks.iter().map(|t| t.to_string_lossy().to_string()).collect();
+                remux_to_mp4(&inputs, &file_path.to_string_lossy()).map_err(DownloadError::from)
+            });
+
+        // Per-track intermediates are never kept
+        let _ = fs::remove_file(file_path.with_extension("video.part"));
+        let _ = fs::remove_file(file_path.with_extension("audio.part"));
+
+        segmented_result(episode, &file_path, result)
+    }
+
+    /// Download subtitle tracks next to the episode file (named after it), converted to SRT
+    pub async fn download_subtitles(&self, episode: i32, tracks: &[SubtitleTrack]) -> Vec<Result<PathBuf, DownloadError>> {
+        let video = self.get_episode_filename(episode);
+        let mut written: Vec<PathBuf> = Vec::new();
+        let mut results = Vec::new();
+
+        for track in tracks {
+            let path = subtitle_path(&video, track.language.as_deref(), &written);
+            let result = match fetch_text(&self.client, &track.url).await {
+                Ok(text) => fs::write(&path, to_srt(&text))
+                    .map(|_| path.clone())
+                    .map_err(|e| DownloadError::io(&path, e)),
+                Err(e) => Err(e),
+            };
+            written.push(path);
+            results.push(result);
+        }
+
+        results
+    }
+}
+
+/// Build the DownloadResult for a segmented (HLS/DASH) download
+fn segmented_result(episode: i32, file_path: &std::path::Path, result: Result<(), DownloadError>) -> DownloadResult {
+    match result {
+        Ok(_) => DownloadResult {
+            episode,
+            success: true,
+            file_path: Some(file_path.to_string_lossy().to_string()),
+            error: None,
+        },
+        Err(e) => DownloadResult {
+            episode,
+            success: false,
+            file_path: None,
+            error: Some(e),
+        },
+    }
+}
+
+/// Progress, speed limit and pause/cancel bookkeeping for segmented (HLS/DASH) downloads.
+/// Segment sizes are unknown up front, so the total is extrapolated from the average so far.
+pub struct SegmentProgress<'a> {
+    episode: i32,
+    app_handle: &'a AppHandle,
+    download_state: Option<Arc<DownloadState>>,
+    speed_limit_bytes: u64,
+    total_segments: usize,
+    completed: usize,
+    downloaded: u64,
+    start_time: std::time::Instant,
+    last_emit: std::time::Instant,
+}
+
+impl<'a> SegmentProgress<'a> {
+    pub fn new(
+        episode: i32,
+        app_handle: &'a AppHandle,
+        download_state: Option<Arc<DownloadState>>,
+        speed_limit_bytes: u64,
+    ) -> Self {
+        Self {
+            episode,
+            app_handle,
+            download_state,
+            speed_limit_bytes,
+            total_segments: 0,
+            completed: 0,
+            downloaded: 0,
+            start_time: std::time::Instant::now(),
+            last_emit: std::time::Instant::now(),
+        }
+    }
+
+    /// Register more segments to download (e.g. a second track)
+    pub fn add_segments(&mut self, count: usize) {
+        self.total_segments += count;
+    }
+
+    /// Wait while paused; fails if the download was cancelled
+    async fn checkpoint(&self) -> Result<(), DownloadError> {
+        if let Some(ref state) = self.download_state {
+            if state.is_cancelled.load(Ordering::SeqCst) {
+                return Err(DownloadError::Cancelled);
+            }
+            while state.is_paused.load(Ordering::SeqCst) {
+                if state.is_cancelled.load(Ordering::SeqCst) {
+                    return Err(DownloadError::Cancelled);
+                }
+                sleep(Duration::from_millis(100)).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Record a finished segment, apply the speed limit and emit progress
+    async fn record(&mut self, bytes: u64) {
+        self.downloaded += bytes;
+        self.completed += 1;
+
+        let elapsed = self.start_time.elapsed().as_secs_f64();
+
+        // Speed limiting - keep the average rate under the limit
+        if self.speed_limit_bytes > 0 {
+            let target_time = self.downloaded as f64 / self.speed_limit_bytes as f64;
+            if target_time > elapsed {
+                sleep(Duration::from_secs_f64(target_time - elapsed)).await;
+            }
+        }
+
+        // Emit progress every 100ms (and always for the last segment)
+        if self.last_emit.elapsed().as_millis() >= 100 || self.completed == self.total_segments {
+            let speed = if elapsed > 0.0 { self.downloaded as f64 / elapsed } else { 0.0 };
+            let total = self.downloaded / self.completed as u64 * self.total_segments as u64;
+
+            let progress = DownloadProgress {
+                episode: self.episode,
+                downloaded: self.downloaded,
+                total,
+                speed,
+                percentage: self.completed as f64 / self.total_segments.max(1) as f64 * 100.0,
+            };
+            let _ = self.app_handle.emit("download-progress", progress);
+            self.last_emit = std::time::Instant::now();
+        }
+    }
+}
+
+/// `length` bytes of a resource starting at `offset`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ByteRange {
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// A segment to fetch: a whole resource, or a byte range of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentRequest {
+    pub url: String,
+    pub range: Option<ByteRange>,
+}
+
+impl From<String> for SegmentRequest {
+    fn from(url: String) -> Self {
+        Self { url, range: None }
+    }
+}
+
+/// Fetch segments in parallel and append them to `file` in order.
+/// `transform` receives the segment index and its bytes (e.g. for decryption).
+pub async fn fetch_segments_into<F>(
+    client: &Client,
+    requests: Vec<SegmentRequest>,
+    file: &mut File,
+    path: &Path,
+    progress: &mut SegmentProgress<'_>,
+    transform: F,
+) -> Result<(), DownloadError>
+where
+    F: Fn(usize, Vec<u8>) -> Result<Vec<u8>, DownloadError>,
+{
+    let mut results = stream::iter(requests.into_iter().map(|request| async move {
+        match request.range {
+            Some(range) => fetch_byte_range(client, &request.url, range).await,
+            None => fetch_bytes(client, &request.url).await,
+        }
+    }))
+    .buffered(SEGMENT_CONCURRENCY)
+    .enumerate();
+
+    while let Some((index, result)) = results.next().await {
+        progress.checkpoint().await?;
+
+        let data = transform(index, result?)?;
+        file.write_all(&data)
+            .map_err(|e| DownloadError::io(path, e))?;
+
+        progress.record(data.len() as u64).await;
+    }
+
+    Ok(())
+}
+
+/// Fetch a playlist or manifest as text
+pub async fn fetch_text(client: &Client, url: &str) -> Result<String, DownloadError> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| DownloadError::from_reqwest(e, url))?;
+    response
+        .text()
+        .await
+        .map_err(|e| DownloadError::Network(e.to_string()))
+}
+
+/// Fetch a whole (small) resource such as a media segment or key
+pub async fn fetch_bytes(client: &Client, url: &str) -> Result<Vec<u8>, DownloadError> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| DownloadError::from_reqwest(e, url))?;
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| DownloadError::Network(e.to_string()))
+}
+
+/// Fetch part of a resource. Servers that ignore the Range header send the whole body,
+/// which is then cut down to the range.
+pub async fn fetch_byte_range(client: &Client, url: &str, range: ByteRange) -> Result<Vec<u8>, DownloadError> {
+    let last = range.offset + range.length.max(1) - 1;
+    let response = client
+        .get(url)
+        .header(reqwest::header::RANGE, format!("bytes={}-{}", range.offset, last))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| DownloadError::from_reqwest(e, url))?;
+    let partial = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| DownloadError::Network(e.to_string()))?;
+
+    let (start, end) = if partial { (0, range.length) } else { (range.offset, range.offset + range.length) };
+    bytes
+        .get(start as usize..end as usize)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| DownloadError::InvalidStream(format!("Byte range {}@{} is past the end of {}", range.length, range.offset, url)))
+}
+
+/// Expiry (unix seconds) of a signed CDN URL, read from the hex `ex` query parameter
+/// used by Discord attachment links (`?ex=...&is=...&hm=...`)
+pub fn signed_url_expiry(url: &str) -> Option<u64> {
+    let query = url.split_once('?')?.1.split('#').next()?;
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        if key == "ex" {
+            u64::from_str_radix(value, 16).ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Check if a signed URL has expired or will within `margin_secs`.
+/// URLs without an expiry never count as expired.
+pub fn url_expires_soon(url: &str, margin_secs: u64) -> bool {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    signed_url_expiry(url).is_some_and(|expiry| now + margin_secs >= expiry)
 }
 
 /// Get FFmpeg command - tries bundled sidecar first, then Resources folder, then system
@@ -458,9 +827,51 @@ fn validate_video_file(path: &str) -> bool {
     }
 }
 
+/// Remux one or more input streams into an MP4 container without re-encoding
+pub fn remux_to_mp4(inputs: &[String], output_path: &str) -> Result<(), MergeError> {
+    let mut cmd = get_ffmpeg_command();
+    cmd.arg("-y");
+    for input in inputs {
+        cmd.args(["-i", input]);
+    }
+    for i in 0..inputs.len() {
+        cmd.args(["-map", &i.to_string()]);
+    }
+    cmd.args(["-c", "copy"]);
+    if inputs.iter().any(|input| has_adts_audio(Path::new(input))) {
+        cmd.args(["-bsf:a", "aac_adtstoasc"]); // ADTS (MPEG-TS) audio -> MP4 audio
+    }
+    cmd.arg(output_path);
+
+    cmd.stdout(Stdio::null());
+    cmd.stderr(Stdio::piped());
+
+    let output = cmd.output()
+        .map_err(MergeError::spawn)?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().last().unwrap_or("unknown error");
+        Err(MergeError::Ffmpeg { reason: format!("remux failed: {}", reason), files: inputs.to_vec() })
+    }
+}
+
+/// MPEG-TS files and raw ADTS streams carry AAC in ADTS framing, which MP4 can't hold as is.
+/// fMP4 and other containers (and non-AAC audio like AC-3 or Opus) must not get the filter.
+fn has_adts_audio(path: &Path) -> bool {
+    let mut head = [0u8; 189];
+    let read = File::open(path).and_then(|mut f| f.read(&mut head)).unwrap_or(0);
+    let head = &head[..read];
+    let mpeg_ts = head.first() == Some(&0x47) && head.get(188).is_none_or(|b| *b == 0x47);
+    let adts = head.len() >= 2 && head[0] == 0xFF && head[1] & 0xF6 == 0xF0;
+    mpeg_ts || adts
+}
+
 /// Merge videos using FFmpeg
-pub fn merge_videos(video_files: Vec<String>, output_path: &str) -> Result<(), String> {
-    merge_videos_with_progress(video_files, output_path, None)
+pub fn merge_videos(video_files: Vec<String>, output_path: &str) -> Result<(), MergeError> {
+    merge_videos_with_progress(video_files, output_path, None, false)
 }
 
 /// Get video duration using ffprobe
@@ -498,10 +909,51 @@ fn parse_ffmpeg_time(line: &str) -> Option<f64> {
     None
 }
 
-/// Merge videos using FFmpeg with progress reporting
-pub fn merge_videos_with_progress(video_files: Vec<String>, output_path: &str, app_handle: Option<&AppHandle>) -> Result<(), String> {
+/// Join the episodes' sidecar subtitles into one SRT per language next to the merged output,
+/// shifting each episode's cues by the duration of the episodes before it
+fn merge_sidecar_subtitles(video_files: &[&String], durations: &[f64], output_path: &str) -> Vec<(Option<String>, PathBuf)> {
+    let sidecars: Vec<Vec<(Option<String>, PathBuf)>> = video_files
+        .iter()
+        .map(|f| sidecar_subtitles(Path::new(f.as_str())))
+        .collect();
+
+    let mut languages: Vec<Option<String>> = sidecars.iter().flatten().map(|(lang, _)| lang.clone()).collect();
+    languages.sort();
+    languages.dedup();
+
+    let output = Path::new(output_path);
+    let mut merged = Vec::new();
+    for language in languages {
+        let mut offset = 0.0;
+        let mut parts = Vec::new();
+        for (episode_sidecars, duration) in sidecars.iter().zip(durations) {
+            if let Some((_, path)) = episode_sidecars.iter().find(|(lang, _)| *lang == language) {
+                if let Ok(text) = fs::read_to_string(path) {
+                    parts.push((text, offset));
+                }
+            }
+            offset += duration;
+        }
+
+        let path = subtitle_path(output, language.as_deref(), &[]);
+        if fs::write(&path, combine_srt(&parts)).is_ok() {
+            merged.push((language, path));
+        }
+    }
+
+    merged
+}
+
+/// Merge videos using FFmpeg with progress reporting. Episode sidecar subtitles are joined
+/// next to the output, and muxed into it as soft subtitles when `embed_subtitles` is set.
+pub fn merge_videos_with_progress(
+    video_files: Vec<String>,
+    output_path: &str,
+    app_handle: Option<&AppHandle>,
+    embed_subtitles: bool,
+) -> Result<(), MergeError> {
     if video_files.is_empty() {
-        return Err("No videos to merge".to_string());
+        return Err(MergeError::NoInput);
     }
 
     // Filter out invalid files
@@ -510,7 +962,7 @@ pub fn merge_videos_with_progress(video_files: Vec<String>, output_path: &str, a
         .collect();
 
     if valid_files.is_empty() {
-        return Err("No valid video files to merge - all files appear incomplete or corrupted".to_string());
+        return Err(MergeError::NoValidInput);
     }
 
     if valid_files.len() != video_files.len() {
@@ -520,8 +972,8 @@ pub fn merge_videos_with_progress(video_files: Vec<String>, output_path: &str, a
         }
     }
 
-    // For single valid file, just copy it
-    if valid_files.len() == 1 {
+    // For single valid file, just copy it (unless subtitles need muxing in)
+    if valid_files.len() == 1 && !embed_subtitles {
         if let Some(app) = app_handle {
             let _ = app.emit("merge-progress", MergeProgress {
                 percentage: 100.0,
@@ -532,14 +984,18 @@ pub fn merge_videos_with_progress(video_files: Vec<String>, output_path: &str, a
         let source = std::path::Path::new(valid_files[0]);
         let dest = std::path::Path::new(output_path);
         std::fs::copy(source, dest)
-            .map_err(|e| format!("Failed to copy file: {}", e))?;
+            .map_err(|e| MergeError::io(dest, e))?;
         return Ok(());
     }
 
     // Calculate total duration for progress calculation
-    let total_duration: f64 = valid_files.iter()
-        .filter_map(|f| get_video_duration(f))
-        .sum();
+    let durations: Vec<f64> = valid_files.iter()
+        .map(|f| get_video_duration(f).unwrap_or(0.0))
+        .collect();
+    let total_duration: f64 = durations.iter().sum();
+
+    let subtitles = merge_sidecar_subtitles(&valid_files, &durations, output_path);
+    let embedded: &[(Option<String>, PathBuf)] = if embed_subtitles { &subtitles } else { &[] };
 
     if let Some(app) = app_handle {
         let _ = app.emit("log-info", format!("Total video duration: {:.1}s ({} files)", total_duration, valid_files.len()));
@@ -550,13 +1006,13 @@ pub fn merge_videos_with_progress(video_files: Vec<String>, output_path: &str, a
     let mut list_content = String::new();
     for file in valid_files.iter() {
         let abs_path = std::fs::canonicalize(file)
-            .map_err(|e| format!("Cannot find file {}: {}", file, e))?;
+            .map_err(|e| MergeError::io(Path::new(file.as_str()), e))?;
         // Escape single quotes for FFmpeg concat list format
         let escaped_path = abs_path.to_string_lossy().replace("'", "'\\''");
         list_content.push_str(&format!("file '{}'\n", escaped_path));
     }
     std::fs::write(&list_path, &list_content)
-        .map_err(|e| format!("Failed to create concat list: {}", e))?;
+        .map_err(|e| MergeError::io(&list_path, e))?;
 
     // Build command using concat demuxer (stream copy - no re-encoding = FAST)
     let mut cmd = get_ffmpeg_command();
@@ -565,19 +1021,34 @@ pub fn merge_videos_with_progress(video_files: Vec<String>, output_path: &str, a
         "-f", "concat",
         "-safe", "0",
         "-i", list_path.to_str().unwrap_or(""),
-        "-c", "copy",  // Stream copy - no re-encoding!
-        output_path,
     ]);
+    for (_, path) in embedded {
+        cmd.arg("-i").arg(path);
+    }
+    if !embedded.is_empty() {
+        cmd.args(["-map", "0:v", "-map", "0:a?"]);
+        for (i, (language, _)) in embedded.iter().enumerate() {
+            cmd.args(["-map", &format!("{}:0", i + 1)]);
+            if let Some(language) = language {
+                cmd.args([&format!("-metadata:s:s:{}", i), &format!("language={}", language)]);
+            }
+        }
+    }
+    cmd.args(["-c", "copy"]);  // Stream copy - no re-encoding!
+    if !embedded.is_empty() {
+        cmd.args(["-c:s", "mov_text"]);  // MP4 soft subtitles
+    }
+    cmd.arg(output_path);
 
     // Spawn process and read stderr for progress
     cmd.stderr(Stdio::piped());
     cmd.stdout(Stdio::null());
 
     let mut child = cmd.spawn()
-        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+        .map_err(MergeError::spawn)?;
 
     let stderr = child.stderr.take()
-        .ok_or_else(|| "Failed to capture FFmpeg stderr".to_string())?;
+        .ok_or_else(|| MergeError::Ffmpeg { reason: "failed to capture FFmpeg stderr".to_string(), files: Vec::new() })?;
 
     let reader = BufReader::new(stderr);
     let mut last_emit = std::time::Instant::now();
@@ -610,9 +1081,14 @@ pub fn merge_videos_with_progress(video_files: Vec<String>, output_path: &str, a
     std::fs::remove_file(&list_path).ok();
 
     let status = child.wait()
-        .map_err(|e| format!("FFmpeg process error: {}", e))?;
+        .map_err(|e| MergeError::Ffmpeg { reason: format!("process error: {}", e), files: Vec::new() })?;
 
     if status.success() {
+        // Subtitles muxed into the output don't need separate files
+        for (_, path) in embedded {
+            std::fs::remove_file(path).ok();
+        }
+
         // Emit 100% completion
         if let Some(app) = app_handle {
             let _ = app.emit("merge-progress", MergeProgress {
@@ -627,18 +1103,24 @@ pub fn merge_videos_with_progress(video_files: Vec<String>, output_path: &str, a
         if let Some(app) = app_handle {
             let _ = app.emit("log-info", "Stream copy failed, trying re-encode method...".to_string());
         }
-        merge_videos_reencode(video_files, output_path, app_handle)
+        merge_videos_reencode(video_files, output_path, app_handle, embedded)
     }
 }
 
-/// Fallback merge using re-encoding (slower but handles incompatible formats)
-fn merge_videos_reencode(video_files: Vec<String>, output_path: &str, app_handle: Option<&AppHandle>) -> Result<(), String> {
+/// Fallback merge using re-encoding (slower but handles incompatible formats); `subtitles`
+/// are muxed in as soft subtitles like in the stream copy merge
+fn merge_videos_reencode(
+    video_files: Vec<String>,
+    output_path: &str,
+    app_handle: Option<&AppHandle>,
+    subtitles: &[(Option<String>, PathBuf)],
+) -> Result<(), MergeError> {
     let valid_files: Vec<&String> = video_files.iter()
         .filter(|f| validate_video_file(f))
         .collect();
 
     if valid_files.is_empty() {
-        return Err("No valid video files to merge".to_string());
+        return Err(MergeError::NoValidInput);
     }
 
     let total_duration: f64 = valid_files.iter()
@@ -648,9 +1130,12 @@ fn merge_videos_reencode(video_files: Vec<String>, output_path: &str, app_handle
     let mut inputs = Vec::new();
     for file in valid_files.iter() {
         let abs_path = std::fs::canonicalize(file)
-            .map_err(|e| format!("Cannot find file {}: {}", file, e))?;
+            .map_err(|e| MergeError::io(Path::new(file.as_str()), e))?;
         inputs.extend(["-i".to_string(), abs_path.to_string_lossy().to_string()]);
     }
+    for (_, path) in subtitles {
+        inputs.extend(["-i".to_string(), path.to_string_lossy().to_string()]);
+    }
 
     // Build concat filter
     let filter_parts: Vec<String> = (0..valid_files.len())
@@ -674,17 +1159,26 @@ fn merge_videos_reencode(video_files: Vec<String>, output_path: &str, app_handle
         "-crf", "23",
         "-c:a", "aac",
         "-b:a", "128k",
-        output_path,
     ]);
+    for (i, (language, _)) in subtitles.iter().enumerate() {
+        cmd.args(["-map", &format!("{}:0", valid_files.len() + i)]);
+        if let Some(language) = language {
+            cmd.args([&format!("-metadata:s:s:{}", i), &format!("language={}", language)]);
+        }
+    }
+    if !subtitles.is_empty() {
+        cmd.args(["-c:s", "mov_text"]);
+    }
+    cmd.arg(output_path);
 
     cmd.stderr(Stdio::piped());
     cmd.stdout(Stdio::null());
 
     let mut child = cmd.spawn()
-        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+        .map_err(MergeError::spawn)?;
 
     let stderr = child.stderr.take()
-        .ok_or_else(|| "Failed to capture FFmpeg stderr".to_string())?;
+        .ok_or_else(|| MergeError::Ffmpeg { reason: "failed to capture FFmpeg stderr".to_string(), files: Vec::new() })?;
 
     let reader = BufReader::new(stderr);
     let mut last_emit = std::time::Instant::now();
@@ -713,9 +1207,12 @@ fn merge_videos_reencode(video_files: Vec<String>, output_path: &str, app_handle
     }
 
     let status = child.wait()
-        .map_err(|e| format!("FFmpeg process error: {}", e))?;
+        .map_err(|e| MergeError::Ffmpeg { reason: format!("process error: {}", e), files: Vec::new() })?;
 
     if status.success() {
+        for (_, path) in subtitles {
+            std::fs::remove_file(path).ok();
+        }
         if let Some(app) = app_handle {
             let _ = app.emit("merge-progress", MergeProgress {
                 percentage: 100.0,
@@ -725,17 +1222,19 @@ fn merge_videos_reencode(video_files: Vec<String>, output_path: &str, app_handle
         }
         Ok(())
     } else {
-        Err(format!(
-            "FFmpeg merge failed. The following files may be corrupted/incomplete: {:?}",
-            valid_files.iter().map(|s| std::path::Path::new(s).file_name().and_then(|n| n.to_str()).unwrap_or("")).collect::<Vec<_>>()
-        ))
+        Err(MergeError::Ffmpeg {
+            reason: "merge failed, some files may be corrupted or incomplete".to_string(),
+            files: valid_files.iter().map(|s| std::path::Path::new(s).file_name().and_then(|n| n.to_str()).unwrap_or("").to_string()).collect(),
+        })
     }
 }
 
+/// Characters that aren't allowed in file names on Windows
+static FILENAME_RESERVED_RE: LazyLock<regex::Regex> = LazyLock::new(|| regex::Regex::new(r#"[<>:"/\\|?*]"#).unwrap());
+
 /// Sanitize filename - handle UTF-8 properly
 pub fn sanitize_filename(name: &str) -> String {
-    let re = regex::Regex::new(r#"[<>:"/\\|?*]"#).unwrap();
-    let clean = re.replace_all(name, "");
+    let clean = FILENAME_RESERVED_RE.replace_all(name, "");
     let clean = clean.trim();
 
     // Use chars() to properly handle UTF-8 instead of byte slicing
@@ -746,3 +1245,39 @@ pub fn sanitize_filename(name: &str) -> String {
         clean.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_url_expiry() {
+        let url = "https://cdn.discordapp.com/attachments/1/2/1.mp4?ex=6791a2b0&is=67905130&hm=abc123&";
+        assert_eq!(signed_url_expiry(url), Some(0x6791a2b0));
+        assert!(url_expires_soon(url, 0));
+
+        assert_eq!(signed_url_expiry("https://example.com/1.mp4"), None);
+        assert!(!url_expires_soon("https://example.com/1.mp4", 3600));
+    }
+
+    #[test]
+    fn test_has_adts_audio() {
+        let dir = std::env::temp_dir().join(format!("adts-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, bytes: &[u8]| {
+            let path = dir.join(name);
+            fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        let mut ts = vec![0u8; 376];
+        ts[0] = 0x47;
+        ts[188] = 0x47;
+        assert!(has_adts_audio(&write("seg.ts", &ts)));
+        assert!(has_adts_audio(&write("audio.aac", &[0xFF, 0xF1, 0x50, 0x80])));
+        assert!(!has_adts_audio(&write("track.mp4", b"\0\0\0\x18ftypiso6")));
+        assert!(!has_adts_audio(&dir.join("missing.ts")));
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,449 @@
+//! Structured errors for the parser, the downloader, merging and the app's own files and settings.
+//!
+//! Every error serializes as `{ "code": "...", "message": "...", "details": {...} }`. Codes are
+//! stable identifiers the frontend can localise and pick a retry action from; `message` is an
+//! English fallback and `details` carries the variant's fields (or null).
+
+use crate::parser::Availability;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors while fetching and parsing site pages
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParserError {
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Server returned HTTP {status} for {url}")]
+    HttpStatus { status: u16, url: String },
+    #[error("Unsupported site: {0}")]
+    UnsupportedSite(String),
+    #[error("Invalid series URL: {0}")]
+    InvalidUrl(String),
+    #[error("Search query is empty")]
+    EmptyQuery,
+    #[error("Search failed on every site: {}", .0.join("; "))]
+    SearchFailed(Vec<String>),
+}
+
+/// Errors while downloading an episode (direct file, HLS or DASH)
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum DownloadError {
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Server returned HTTP {status} for {url}")]
+    HttpStatus { status: u16, url: String },
+    /// The signed video URL was rejected (HTTP 403/410) and needs re-resolving
+    #[error("Video URL expired (HTTP {status})")]
+    UrlExpired { status: u16 },
+    #[error("No video URL for episode {episode}")]
+    NoSource { episode: i32 },
+    #[error("Invalid stream: {0}")]
+    InvalidStream(String),
+    #[error("Disk full while writing {path}")]
+    DiskFull { path: String },
+    #[error("Failed to write {path}: {message}")]
+    Io { path: String, message: String },
+    #[error("Download cancelled")]
+    Cancelled,
+    /// The download task itself failed (panicked or was aborted)
+    #[error("Download task failed: {0}")]
+    Task(String),
+    /// Remuxing an HLS/DASH download to MP4 failed
+    #[error(transparent)]
+    Remux(#[from] MergeError),
+}
+
+/// Errors while merging or remuxing with FFmpeg
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum MergeError {
+    #[error("FFmpeg not found - cannot merge videos")]
+    FfmpegNotFound,
+    #[error("No videos to merge")]
+    NoInput,
+    #[error("No valid video files to merge - all files appear incomplete or corrupted")]
+    NoValidInput,
+    #[error("FFmpeg failed: {reason}")]
+    Ffmpeg { reason: String, files: Vec<String> },
+    #[error("Disk full while writing {path}")]
+    DiskFull { path: String },
+    #[error("Failed to write {path}: {message}")]
+    Io { path: String, message: String },
+}
+
+/// Errors reading or writing files: the app's saved settings, caches and lists, or files the user picked
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum StorageError {
+    #[error("Failed to read {path}: {message}")]
+    Read { path: String, message: String },
+    #[error("Disk full while writing {path}")]
+    DiskFull { path: String },
+    #[error("Failed to write {path}: {message}")]
+    Write { path: String, message: String },
+    /// A saved file that couldn't be parsed; it was moved to `backup` rather than overwritten
+    #[error("{path} is corrupt ({message}); it was moved to {backup}")]
+    Corrupt { path: String, backup: String, message: String },
+}
+
+/// Proxy settings that can't be turned into an HTTP client
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ProxyError {
+    #[error("Invalid proxy URL: {0}")]
+    InvalidUrl(String),
+    #[error("Unsupported proxy scheme: {0}")]
+    UnsupportedScheme(String),
+    #[error("Proxy URL has no host")]
+    NoHost,
+    #[error("Proxy URL cannot carry credentials")]
+    Credentials,
+    #[error("Failed to create HTTP client: {0}")]
+    Client(String),
+}
+
+/// Errors importing or saving cookies
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CookieError {
+    #[error("Invalid cookies.txt line {line}: {reason}")]
+    InvalidLine { line: usize, reason: String },
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+/// Errors returned by Tauri commands
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum AppError {
+    #[error(transparent)]
+    Parser(#[from] ParserError),
+    #[error(transparent)]
+    Download(#[from] DownloadError),
+    #[error(transparent)]
+    Merge(#[from] MergeError),
+    #[error("Series {series_id} is not loaded")]
+    NoSeriesLoaded { series_id: i32 },
+    /// Selected episodes that can't be downloaded, found before the batch starts
+    #[error("Episodes not available: {}", episode_list(.episodes))]
+    EpisodesUnavailable { episodes: BTreeMap<i32, Availability> },
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Proxy(#[from] ProxyError),
+    #[error(transparent)]
+    Cookies(#[from] CookieError),
+    #[error("No active download for episode {episode}")]
+    NoActiveDownload { episode: i32 },
+    #[error("Nothing to import")]
+    NothingToImport,
+    /// A folder or file couldn't be handed to the system's file manager or player
+    #[error("Failed to open {path}: {message}")]
+    OpenFailed { path: String, message: String },
+}
+
+impl ParserError {
+    /// Classify a failed page request
+    pub fn from_reqwest(error: reqwest::Error, url: &str) -> Self {
+        match error.status() {
+            Some(status) => ParserError::HttpStatus { status: status.as_u16(), url: url.to_string() },
+            None => ParserError::Network(error.to_string()),
+        }
+    }
+
+    /// Worth retrying: network errors and server-side (5xx) failures
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ParserError::Network(_) => true,
+            ParserError::HttpStatus { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserError::Network(_) => "network",
+            ParserError::HttpStatus { .. } => "http_status",
+            ParserError::UnsupportedSite(_) => "unsupported_site",
+            ParserError::InvalidUrl(_) => "invalid_url",
+            ParserError::EmptyQuery => "empty_query",
+            ParserError::SearchFailed(_) => "search_failed",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            ParserError::HttpStatus { status, url } => json!({ "status": status, "url": url }),
+            ParserError::UnsupportedSite(url) | ParserError::InvalidUrl(url) => json!({ "url": url }),
+            ParserError::SearchFailed(errors) => json!({ "errors": errors }),
+            ParserError::Network(_) | ParserError::EmptyQuery => Value::Null,
+        }
+    }
+}
+
+impl DownloadError {
+    /// Classify a failed media request; 403/410 mean the signed URL expired
+    pub fn from_reqwest(error: reqwest::Error, url: &str) -> Self {
+        match error.status().map(|s| s.as_u16()) {
+            Some(status @ (403 | 410)) => DownloadError::UrlExpired { status },
+            Some(status) => DownloadError::HttpStatus { status, url: url.to_string() },
+            None => DownloadError::Network(error.to_string()),
+        }
+    }
+
+    /// Classify a failed file write, telling a full disk apart from other I/O errors
+    pub fn io(path: &Path, error: io::Error) -> Self {
+        let path = path.to_string_lossy().to_string();
+        if is_disk_full(&error) {
+            DownloadError::DiskFull { path }
+        } else {
+            DownloadError::Io { path, message: error.to_string() }
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            DownloadError::Network(_) => "network",
+            DownloadError::HttpStatus { .. } => "http_status",
+            DownloadError::UrlExpired { .. } => "url_expired",
+            DownloadError::NoSource { .. } => "no_source",
+            DownloadError::InvalidStream(_) => "invalid_stream",
+            DownloadError::DiskFull { .. } => "disk_full",
+            DownloadError::Io { .. } => "io",
+            DownloadError::Cancelled => "cancelled",
+            DownloadError::Task(_) => "task_failed",
+            DownloadError::Remux(e) => e.code(),
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            DownloadError::HttpStatus { status, url } => json!({ "status": status, "url": url }),
+            DownloadError::UrlExpired { status } => json!({ "status": status }),
+            DownloadError::NoSource { episode } => json!({ "episode": episode }),
+            DownloadError::DiskFull { path } => json!({ "path": path }),
+            DownloadError::Io { path, message } => json!({ "path": path, "message": message }),
+            DownloadError::Remux(e) => e.details(),
+            DownloadError::Network(_)
+            | DownloadError::InvalidStream(_)
+            | DownloadError::Cancelled
+            | DownloadError::Task(_) => Value::Null,
+        }
+    }
+}
+
+impl MergeError {
+    pub fn io(path: &Path, error: io::Error) -> Self {
+        let path = path.to_string_lossy().to_string();
+        if is_disk_full(&error) {
+            MergeError::DiskFull { path }
+        } else {
+            MergeError::Io { path, message: error.to_string() }
+        }
+    }
+
+    /// FFmpeg could not be started at all
+    pub fn spawn(error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::NotFound {
+            MergeError::FfmpegNotFound
+        } else {
+            MergeError::Ffmpeg { reason: format!("Failed to run FFmpeg: {}", error), files: Vec::new() }
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            MergeError::FfmpegNotFound => "ffmpeg_not_found",
+            MergeError::NoInput => "no_input",
+            MergeError::NoValidInput => "no_valid_input",
+            MergeError::Ffmpeg { .. } => "ffmpeg_failed",
+            MergeError::DiskFull { .. } => "disk_full",
+            MergeError::Io { .. } => "io",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            MergeError::Ffmpeg { reason, files } => json!({ "reason": reason, "files": files }),
+            MergeError::DiskFull { path } => json!({ "path": path }),
+            MergeError::Io { path, message } => json!({ "path": path, "message": message }),
+            MergeError::FfmpegNotFound | MergeError::NoInput | MergeError::NoValidInput => Value::Null,
+        }
+    }
+}
+
+impl StorageError {
+    pub fn read(path: &Path, error: impl std::fmt::Display) -> Self {
+        StorageError::Read { path: path.to_string_lossy().to_string(), message: error.to_string() }
+    }
+
+    /// Classify a failed write, telling a full disk apart from other I/O errors
+    pub fn write(path: &Path, error: io::Error) -> Self {
+        let path = path.to_string_lossy().to_string();
+        if is_disk_full(&error) {
+            StorageError::DiskFull { path }
+        } else {
+            StorageError::Write { path, message: error.to_string() }
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            StorageError::Read { .. } => "read_failed",
+            StorageError::DiskFull { .. } => "disk_full",
+            StorageError::Write { .. } => "io",
+            StorageError::Corrupt { .. } => "corrupt_file",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            StorageError::Read { path, message } | StorageError::Write { path, message } => {
+                json!({ "path": path, "message": message })
+            }
+            StorageError::DiskFull { path } => json!({ "path": path }),
+            StorageError::Corrupt { path, backup, message } => json!({ "path": path, "backup": backup, "message": message }),
+        }
+    }
+}
+
+impl ProxyError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProxyError::InvalidUrl(_) => "invalid_proxy_url",
+            ProxyError::UnsupportedScheme(_) => "unsupported_proxy_scheme",
+            ProxyError::NoHost => "proxy_without_host",
+            ProxyError::Credentials => "proxy_credentials",
+            ProxyError::Client(_) => "client_failed",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            ProxyError::UnsupportedScheme(scheme) => json!({ "scheme": scheme }),
+            ProxyError::InvalidUrl(_) | ProxyError::NoHost | ProxyError::Credentials | ProxyError::Client(_) => Value::Null,
+        }
+    }
+}
+
+impl CookieError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CookieError::InvalidLine { .. } => "invalid_cookie_line",
+            CookieError::Storage(e) => e.code(),
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            CookieError::InvalidLine { line, reason } => json!({ "line": line, "reason": reason }),
+            CookieError::Storage(e) => e.details(),
+        }
+    }
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Parser(e) => e.code(),
+            AppError::Download(e) => e.code(),
+            AppError::Merge(e) => e.code(),
+            AppError::NoSeriesLoaded { .. } => "no_series_loaded",
+            AppError::EpisodesUnavailable { .. } => "episodes_unavailable",
+            AppError::Storage(e) => e.code(),
+            AppError::Proxy(e) => e.code(),
+            AppError::Cookies(e) => e.code(),
+            AppError::NoActiveDownload { .. } => "no_active_download",
+            AppError::NothingToImport => "nothing_to_import",
+            AppError::OpenFailed { .. } => "open_failed",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            AppError::Parser(e) => e.details(),
+            AppError::Download(e) => e.details(),
+            AppError::Merge(e) => e.details(),
+            AppError::NoSeriesLoaded { series_id } => json!({ "seriesId": series_id }),
+            AppError::EpisodesUnavailable { episodes } => {
+                json!({ "episodes": episode_list(episodes), "availability": episodes })
+            }
+            AppError::Storage(e) => e.details(),
+            AppError::Proxy(e) => e.details(),
+            AppError::Cookies(e) => e.details(),
+            AppError::NoActiveDownload { episode } => json!({ "episode": episode }),
+            AppError::OpenFailed { path, message } => json!({ "path": path, "message": message }),
+            AppError::NothingToImport => Value::Null,
+        }
+    }
+}
+
+/// "17, 23" for the translated message
+fn episode_list(episodes: &BTreeMap<i32, Availability>) -> String {
+    episodes.keys().map(|ep| ep.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn is_disk_full(error: &io::Error) -> bool {
+    // ENOSPC on Unix, ERROR_DISK_FULL / ERROR_HANDLE_DISK_FULL on Windows
+    error.kind() == io::ErrorKind::StorageFull
+}
+
+fn serialize_error<S: Serializer>(serializer: S, code: &str, message: String, details: Value) -> Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct("Error", 3)?;
+    state.serialize_field("code", code)?;
+    state.serialize_field("message", &message)?;
+    state.serialize_field("details", &details)?;
+    state.end()
+}
+
+impl Serialize for ParserError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(serializer, self.code(), self.to_string(), self.details())
+    }
+}
+
+impl Serialize for DownloadError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(serializer, self.code(), self.to_string(), self.details())
+    }
+}
+
+impl Serialize for MergeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(serializer, self.code(), self.to_string(), self.details())
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_error(serializer, self.code(), self.to_string(), self.details())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_wire_format() {
+        let error = AppError::from(DownloadError::UrlExpired { status: 403 });
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "code": "url_expired", "message": "Video URL expired (HTTP 403)", "details": { "status": 403 } })
+        );
+
+        // Remux failures inside a download keep the merge error's code
+        let error = DownloadError::from(MergeError::FfmpegNotFound);
+        assert_eq!(serde_json::to_value(&error).unwrap()["code"], "ffmpeg_not_found");
+
+        let full = io::Error::from(io::ErrorKind::StorageFull);
+        assert_eq!(DownloadError::io(Path::new("/tmp/ep_001.mp4"), full).code(), "disk_full");
+        assert_eq!(serde_json::to_value(ParserError::EmptyQuery).unwrap()["details"], Value::Null);
+
+        // Settings and cookie failures have their own codes too
+        let error = AppError::from(CookieError::InvalidLine { line: 3, reason: "expected 7 tab-separated fields".to_string() });
+        assert_eq!(serde_json::to_value(&error).unwrap()["details"]["line"], 3);
+        let error = AppError::from(CookieError::from(StorageError::write(Path::new("/tmp/cookies.json"), io::Error::from(io::ErrorKind::StorageFull))));
+        assert_eq!(error.code(), "disk_full");
+        assert_eq!(AppError::from(ProxyError::NoHost).code(), "proxy_without_host");
+    }
+}
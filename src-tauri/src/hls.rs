@@ -0,0 +1,488 @@
+use crate::downloader::{fetch_bytes, fetch_segments_into, fetch_text, ByteRange, SegmentProgress, SegmentRequest};
+use crate::error::DownloadError;
+use crate::quality::{QualityPreference, Rendition};
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use reqwest::{Client, Url};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+/// One rendition listed in a master playlist
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub uri: String,
+    pub bandwidth: u64,
+    pub resolution: Option<(u32, u32)>,
+    /// Playlist of the variant's audio when it comes as a separate EXT-X-MEDIA rendition
+    pub audio: Option<String>,
+}
+
+/// Encryption key that applies to a run of segments
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentKey {
+    pub uri: String,
+    pub iv: Option<[u8; 16]>,
+}
+
+/// Media initialization section (EXT-X-MAP) that fMP4 segments need in front of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitSection {
+    pub uri: String,
+    pub range: Option<ByteRange>,
+    /// Key in effect at the EXT-X-MAP tag; always has an IV
+    pub key: Option<SegmentKey>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub uri: String,
+    /// Part of `uri` holding the segment (EXT-X-BYTERANGE)
+    pub range: Option<ByteRange>,
+    pub duration: f64,
+    pub sequence: u64,
+    pub key: Option<SegmentKey>,
+    pub map: Option<InitSection>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Playlist {
+    Master(Vec<Variant>),
+    Media(Vec<Segment>),
+}
+
+impl Rendition for Variant {
+    fn height(&self) -> Option<u32> {
+        self.resolution.map(|(_, h)| h)
+    }
+
+    fn bitrate(&self) -> Option<u64> {
+        Some(self.bandwidth).filter(|b| *b > 0)
+    }
+}
+
+/// Check if a URL points to an HLS playlist
+pub fn is_hls_url(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.to_lowercase().ends_with(".m3u8")
+}
+
+/// Parse an M3U8 playlist, resolving every URI against `base_url`
+pub fn parse_playlist(text: &str, base_url: &str) -> Result<Playlist, String> {
+    let base = Url::parse(base_url).map_err(|e| format!("Invalid playlist URL: {}", e))?;
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    if lines.next() != Some("#EXTM3U") {
+        return Err("Not an M3U8 playlist".to_string());
+    }
+
+    let mut variants: Vec<(Variant, Option<String>)> = Vec::new();
+    let mut segments = Vec::new();
+    // Variant (URI still to come) and its audio group
+    let mut pending_variant: Option<(Variant, Option<String>)> = None;
+    let mut pending_duration: Option<f64> = None;
+    let mut pending_range: Option<(u64, Option<u64>)> = None;
+    let mut current_key: Option<SegmentKey> = None;
+    let mut current_map: Option<InitSection> = None;
+    // Where the previous sub-range ended, for byte ranges without an offset
+    let mut range_end: Option<(String, u64)> = None;
+    // Audio playlist by group id; `None` when the group's default audio is muxed into the variant
+    let mut audio_groups: HashMap<String, Option<String>> = HashMap::new();
+    let mut sequence: u64 = 0;
+
+    for line in lines {
+        if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            let attrs = parse_attributes(attrs);
+            let bandwidth = attrs
+                .get("BANDWIDTH")
+                .and_then(|b| b.parse().ok())
+                .unwrap_or(0);
+            let resolution = attrs.get("RESOLUTION").and_then(|r| {
+                let (w, h) = r.split_once('x')?;
+                Some((w.parse().ok()?, h.parse().ok()?))
+            });
+            let variant = Variant { uri: String::new(), bandwidth, resolution, audio: None };
+            pending_variant = Some((variant, attrs.get("AUDIO").cloned()));
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MEDIA:") {
+            let attrs = parse_attributes(attrs);
+            if attrs.get("TYPE").map(String::as_str) != Some("AUDIO") {
+                continue;
+            }
+            let Some(group) = attrs.get("GROUP-ID") else { continue };
+            let uri = attrs.get("URI").map(|uri| resolve_uri(&base, uri)).transpose()?;
+            let is_default = attrs.get("DEFAULT").map(String::as_str) == Some("YES");
+            if is_default || !audio_groups.contains_key(group) {
+                audio_groups.insert(group.clone(), uri);
+            }
+        } else if let Some(value) = line.strip_prefix("#EXT-X-BYTERANGE:") {
+            pending_range = Some(parse_byte_range(value)?);
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MAP:") {
+            let attrs = parse_attributes(attrs);
+            let uri = attrs.get("URI").ok_or("EXT-X-MAP without URI")?;
+            let range = match attrs.get("BYTERANGE") {
+                Some(value) => {
+                    let (length, offset) = parse_byte_range(value)?;
+                    Some(ByteRange { offset: offset.unwrap_or(0), length })
+                }
+                None => None,
+            };
+            if current_key.as_ref().is_some_and(|key| key.iv.is_none()) {
+                return Err("Encrypted EXT-X-MAP without an IV".to_string());
+            }
+            current_map = Some(InitSection {
+                uri: resolve_uri(&base, uri)?,
+                range,
+                key: current_key.clone(),
+            });
+        } else if let Some(value) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            sequence = value.parse().unwrap_or(0);
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-KEY:") {
+            let attrs = parse_attributes(attrs);
+            current_key = match attrs.get("METHOD").map(String::as_str) {
+                Some("NONE") | None => None,
+                Some("AES-128") => {
+                    let uri = attrs.get("URI").ok_or("AES-128 key without URI")?;
+                    let iv = match attrs.get("IV") {
+                        Some(iv) => Some(parse_iv(iv)?),
+                        None => None,
+                    };
+                    Some(SegmentKey {
+                        uri: resolve_uri(&base, uri)?,
+                        iv,
+                    })
+                }
+                Some(method) => return Err(format!("Unsupported HLS encryption: {}", method)),
+            };
+        } else if let Some(value) = line.strip_prefix("#EXTINF:") {
+            let duration = value.split(',').next().unwrap_or("0");
+            pending_duration = Some(duration.trim().parse().unwrap_or(0.0));
+        } else if line.starts_with('#') {
+            continue;
+        } else if let Some((variant, audio_group)) = pending_variant.take() {
+            variants.push((Variant { uri: resolve_uri(&base, line)?, ..variant }, audio_group));
+        } else {
+            let uri = resolve_uri(&base, line)?;
+            let range = match pending_range.take() {
+                Some((length, offset)) => {
+                    let offset = match (offset, &range_end) {
+                        (Some(offset), _) => offset,
+                        (None, Some((previous, end))) if *previous == uri => *end,
+                        (None, _) => return Err(format!("EXT-X-BYTERANGE without an offset for {}", uri)),
+                    };
+                    range_end = Some((uri.clone(), offset + length));
+                    Some(ByteRange { offset, length })
+                }
+                None => None,
+            };
+            segments.push(Segment {
+                uri,
+                range,
+                duration: pending_duration.take().unwrap_or(0.0),
+                sequence,
+                key: current_key.clone(),
+                map: current_map.clone(),
+            });
+            sequence += 1;
+        }
+    }
+
+    if !variants.is_empty() {
+        // EXT-X-MEDIA tags may come after the variants that refer to them
+        let variants = variants
+            .into_iter()
+            .map(|(variant, group)| Variant {
+                audio: group.and_then(|g| audio_groups.get(&g).cloned().flatten()),
+                ..variant
+            })
+            .collect();
+        Ok(Playlist::Master(variants))
+    } else if !segments.is_empty() {
+        Ok(Playlist::Media(segments))
+    } else {
+        Err("Playlist contains no variants or segments".to_string())
+    }
+}
+
+/// Pick a variant according to the quality preference
+pub fn select_variant(variants: &[Variant], quality: QualityPreference) -> Option<&Variant> {
+    quality.select(variants)
+}
+
+/// Download an HLS stream into `output`. A separate audio rendition goes next to it with an
+/// `audio.part` extension. Returns the files written, video first.
+pub async fn download_stream(
+    client: &Client,
+    playlist_url: &str,
+    output: &Path,
+    quality: QualityPreference,
+    progress: &mut SegmentProgress<'_>,
+) -> Result<Vec<PathBuf>, DownloadError> {
+    let tracks = fetch_tracks(client, playlist_url, quality).await?;
+    let plans: Vec<Vec<PlannedFetch>> = tracks.iter().map(|segments| plan_fetches(segments)).collect();
+    for plan in &plans {
+        progress.add_segments(plan.len());
+    }
+
+    let paths = [output.to_path_buf(), output.with_extension("audio.part")];
+    let mut files = Vec::new();
+    for (plan, path) in plans.into_iter().zip(paths) {
+        // Fetch each distinct key once
+        let mut keys: HashMap<String, Vec<u8>> = HashMap::new();
+        for (key_uri, _) in plan.iter().filter_map(|p| p.decrypt.as_ref()) {
+            if !keys.contains_key(key_uri) {
+                let bytes = fetch_bytes(client, key_uri).await?;
+                if bytes.len() != 16 {
+                    return Err(DownloadError::InvalidStream(format!("Invalid AES-128 key length: {}", bytes.len())));
+                }
+                keys.insert(key_uri.clone(), bytes);
+            }
+        }
+
+        let (requests, decrypt): (Vec<_>, Vec<_>) = plan.into_iter().map(|p| (p.request, p.decrypt)).unzip();
+        let mut file = File::create(&path).map_err(|e| DownloadError::io(&path, e))?;
+        fetch_segments_into(client, requests, &mut file, &path, progress, |index, data| match decrypt[index] {
+            Some((ref key_uri, iv)) => decrypt_segment(&keys[key_uri], &iv, data).map_err(DownloadError::InvalidStream),
+            None => Ok(data),
+        })
+        .await?;
+        files.push(path);
+    }
+
+    Ok(files)
+}
+
+/// One fetch of a media track: a segment or an init section, with the key and IV to decrypt it
+#[derive(Debug, Clone, PartialEq)]
+struct PlannedFetch {
+    request: SegmentRequest,
+    decrypt: Option<(String, [u8; 16])>,
+}
+
+/// Fetches for a media playlist in file order; an init section goes in front of the first
+/// segment using it
+fn plan_fetches(segments: &[Segment]) -> Vec<PlannedFetch> {
+    let mut plan = Vec::new();
+    let mut last_map: Option<&InitSection> = None;
+
+    for segment in segments {
+        if let Some(map) = segment.map.as_ref().filter(|map| last_map != Some(*map)) {
+            plan.push(PlannedFetch {
+                request: SegmentRequest { url: map.uri.clone(), range: map.range },
+                decrypt: map.key.as_ref().and_then(|key| Some((key.uri.clone(), key.iv?))),
+            });
+            last_map = Some(map);
+        }
+        plan.push(PlannedFetch {
+            request: SegmentRequest { url: segment.uri.clone(), range: segment.range },
+            decrypt: segment
+                .key
+                .as_ref()
+                .map(|key| (key.uri.clone(), key.iv.unwrap_or_else(|| sequence_iv(segment.sequence)))),
+        });
+    }
+
+    plan
+}
+
+/// Media segments of the stream: the preferred variant, then its separate audio rendition if any
+async fn fetch_tracks(client: &Client, playlist_url: &str, quality: QualityPreference) -> Result<Vec<Vec<Segment>>, DownloadError> {
+    let text = fetch_text(client, playlist_url).await?;
+    match parse_playlist(&text, playlist_url).map_err(DownloadError::InvalidStream)? {
+        Playlist::Media(segments) => Ok(vec![segments]),
+        Playlist::Master(variants) => {
+            let variant = select_variant(&variants, quality)
+                .ok_or_else(|| DownloadError::InvalidStream("Master playlist has no variants".to_string()))?;
+            let mut tracks = vec![fetch_media_segments(client, &variant.uri).await?];
+            if let Some(ref audio) = variant.audio {
+                tracks.push(fetch_media_segments(client, audio).await?);
+            }
+            Ok(tracks)
+        }
+    }
+}
+
+async fn fetch_media_segments(client: &Client, url: &str) -> Result<Vec<Segment>, DownloadError> {
+    let text = fetch_text(client, url).await?;
+    match parse_playlist(&text, url).map_err(DownloadError::InvalidStream)? {
+        Playlist::Media(segments) => Ok(segments),
+        Playlist::Master(_) => Err(DownloadError::InvalidStream("Nested master playlists are not supported".to_string())),
+    }
+}
+
+/// Decrypt an AES-128-CBC segment (PKCS7 padded)
+fn decrypt_segment(key: &[u8], iv: &[u8; 16], mut data: Vec<u8>) -> Result<Vec<u8>, String> {
+    let len = Aes128CbcDec::new(key.into(), iv.into())
+        .decrypt_padded_mut::<Pkcs7>(&mut data)
+        .map_err(|_| "Failed to decrypt segment".to_string())?
+        .len();
+    data.truncate(len);
+    Ok(data)
+}
+
+/// Default IV when the key tag has none: the media sequence number, big-endian
+fn sequence_iv(sequence: u64) -> [u8; 16] {
+    let mut iv = [0u8; 16];
+    iv[8..].copy_from_slice(&sequence.to_be_bytes());
+    iv
+}
+
+fn parse_iv(value: &str) -> Result<[u8; 16], String> {
+    let hex = value.trim_start_matches("0x").trim_start_matches("0X");
+    if hex.len() != 32 {
+        return Err(format!("Invalid IV: {}", value));
+    }
+    let mut iv = [0u8; 16];
+    for (i, byte) in iv.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| format!("Invalid IV: {}", value))?;
+    }
+    Ok(iv)
+}
+
+/// Parse a byte range `length[@offset]`
+fn parse_byte_range(value: &str) -> Result<(u64, Option<u64>), String> {
+    let invalid = || format!("Invalid byte range: {}", value);
+    let (length, offset) = match value.trim().split_once('@') {
+        Some((length, offset)) => (length, Some(offset.parse().map_err(|_| invalid())?)),
+        None => (value.trim(), None),
+    };
+    Ok((length.parse().map_err(|_| invalid())?, offset))
+}
+
+/// Parse an attribute list like `BANDWIDTH=800000,CODECS="avc1,mp4a"`
+fn parse_attributes(input: &str) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    let mut rest = input;
+
+    while let Some((name, after)) = rest.split_once('=') {
+        let (value, remainder) = if let Some(quoted) = after.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            (&quoted[..end], quoted.get(end + 1..).unwrap_or(""))
+        } else {
+            after.split_once(',').map_or((after, ""), |(v, r)| (v, r))
+        };
+        attrs.insert(name.trim().to_uppercase(), value.to_string());
+        rest = remainder.trim_start_matches(',');
+    }
+
+    attrs
+}
+
+fn resolve_uri(base: &Url, uri: &str) -> Result<String, String> {
+    base.join(uri)
+        .map(|u| u.to_string())
+        .map_err(|e| format!("Invalid URI {}: {}", uri, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::downloader::fetch_byte_range;
+    use crate::test_support::{fixture, FixtureServer};
+
+    #[test]
+    fn test_parse_master_playlist() {
+        let text = "#EXTM3U\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360,CODECS=\"avc1.4d401e,mp4a.40.2\"\n\
+            360p/index.m3u8\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=2400000,RESOLUTION=1280x720\n\
+            https://cdn.example.com/720p/index.m3u8\n";
+
+        let playlist = parse_playlist(text, "https://example.com/ep1/master.m3u8").unwrap();
+        let Playlist::Master(variants) = playlist else { panic!("expected master playlist") };
+
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[0].uri, "https://example.com/ep1/360p/index.m3u8");
+        assert_eq!(variants[0].resolution, Some((640, 360)));
+        assert_eq!(select_variant(&variants, QualityPreference::Highest).unwrap().uri, "https://cdn.example.com/720p/index.m3u8");
+        assert_eq!(select_variant(&variants, QualityPreference::Closest { height: 480 }).unwrap().resolution, Some((640, 360)));
+    }
+
+    #[test]
+    fn test_parse_media_playlist_with_key() {
+        let text = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:10\n\
+            #EXT-X-MEDIA-SEQUENCE:5\n\
+            #EXTINF:9.5,\n\
+            seg5.ts\n\
+            #EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\",IV=0x000102030405060708090a0b0c0d0e0f\n\
+            #EXTINF:10.0,\n\
+            seg6.ts\n\
+            #EXT-X-ENDLIST\n";
+
+        let playlist = parse_playlist(text, "https://example.com/hls/index.m3u8").unwrap();
+        let Playlist::Media(segments) = playlist else { panic!("expected media playlist") };
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].sequence, 5);
+        assert_eq!(segments[0].duration, 9.5);
+        assert!(segments[0].key.is_none());
+
+        let key = segments[1].key.as_ref().unwrap();
+        assert_eq!(key.uri, "https://example.com/hls/key.bin");
+        assert_eq!(key.iv.unwrap()[15], 0x0f);
+        assert_eq!(sequence_iv(6)[15], 6);
+    }
+
+    #[test]
+    fn test_fmp4_playlist_fetches_init_sections() {
+        let playlist = parse_playlist(&fixture("hls_fmp4.m3u8"), "https://example.com/ep1/index.m3u8").unwrap();
+        let Playlist::Media(segments) = playlist else { panic!("expected media playlist") };
+
+        let urls: Vec<_> = plan_fetches(&segments).into_iter().map(|p| p.request.url).collect();
+        assert_eq!(urls, vec![
+            "https://example.com/ep1/init-a.mp4",
+            "https://example.com/ep1/seg0.m4s",
+            "https://example.com/ep1/seg1.m4s",
+            "https://example.com/ep1/init-b.mp4",
+            "https://example.com/ep1/seg2.m4s",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_byte_range_playlist() {
+        let playlist = parse_playlist(&fixture("hls_byterange.m3u8"), "https://example.com/ep1/index.m3u8").unwrap();
+        let Playlist::Media(segments) = playlist else { panic!("expected media playlist") };
+
+        let ranges: Vec<_> = plan_fetches(&segments)
+            .into_iter()
+            .map(|p| p.request.range.map(|r| (r.offset, r.length)).unwrap())
+            .collect();
+        assert_eq!(ranges, vec![(0, 720), (720, 1000), (1720, 1200), (2920, 500)]);
+        assert!(parse_playlist("#EXTM3U\n#EXT-X-BYTERANGE:100\nseg.ts\n", "https://example.com/").is_err());
+
+        // Only the requested slice is kept, whether or not the server honours Range
+        let body: Vec<u8> = (0..=255).collect();
+        let base_url = FixtureServer::bind()
+            .bytes("/ranged.mp4", "video/mp4", &body)
+            .with_ranges("/ranged.mp4")
+            .bytes("/whole.mp4", "video/mp4", &body)
+            .start();
+        let client = Client::new();
+        let range = ByteRange { offset: 16, length: 4 };
+        for path in ["ranged.mp4", "whole.mp4"] {
+            let bytes = fetch_byte_range(&client, &format!("{}{}", base_url, path), range).await.unwrap();
+            assert_eq!(bytes, vec![16, 17, 18, 19]);
+        }
+    }
+
+    #[test]
+    fn test_master_playlist_alternate_audio() {
+        let playlist = parse_playlist(&fixture("hls_master_audio.m3u8"), "https://example.com/ep1/master.m3u8").unwrap();
+        let Playlist::Master(variants) = playlist else { panic!("expected master playlist") };
+
+        let best = select_variant(&variants, QualityPreference::Highest).unwrap();
+        assert_eq!(best.uri, "https://example.com/ep1/720p/index.m3u8");
+        // The default rendition of the group, even though it's listed after the variants
+        assert_eq!(best.audio.as_deref(), Some("https://example.com/ep1/audio/th/index.m3u8"));
+        // Audio muxed into the variant needs no second track
+        assert_eq!(variants[2].audio, None);
+    }
+
+    #[test]
+    fn test_is_hls_url() {
+        assert!(is_hls_url("https://example.com/ep1/index.m3u8?token=abc"));
+        assert!(!is_hls_url("https://cdn.discordapp.com/attachments/1/2/1.mp4?ex=abc"));
+    }
+}
@@ -1,14 +1,48 @@
+mod batch_import;
+mod catalog;
+mod dash;
 mod downloader;
+mod error;
+mod hls;
+mod loaded_series;
+mod metadata;
+mod network;
 mod parser;
+mod poster_cache;
+mod probe;
+mod quality;
+mod rules;
+mod session;
+mod series_cache;
+mod sites;
+mod structured;
+mod subscriptions;
+mod subtitles;
+#[cfg(test)]
+mod test_support;
 
-use downloader::{check_ffmpeg, merge_videos_with_progress, sanitize_filename, DownloadConfig, DownloadResult, DownloadState, VideoDownloader};
+use batch_import::{read_entries, ImportLine, ImportStatus};
+use catalog::{SeriesPage, SeriesSummary};
+use downloader::{check_ffmpeg, merge_videos_with_progress, sanitize_filename, url_expires_soon, DownloadConfig, DownloadResult, DownloadState, VideoDownloader};
+use error::{AppError, DownloadError, MergeError, ParserError, StorageError};
+use loaded_series::{LoadedSeries, LoadedSeriesSummary};
+use network::{build_client, ProxySettings, ProxyTestResult};
 use parser::{RongyokParser, SeriesInfo};
+use poster_cache::{PosterCache, POSTER_SCHEME};
+use probe::{probe_episodes, sample_throughput, SeriesProbe, StreamKind};
+use quality::QualityPreference;
+use rules::{RulesReport, SiteRulesFile};
+use series_cache::{CacheEntry, CachedSeriesInfo, CachedSeriesSummary, SeriesCache, DEFAULT_MAX_AGE};
+use session::{CookieInfo, Session};
+use subscriptions::{NewEpisodes, Subscription, SubscriptionDownload, Subscriptions, MIN_INTERVAL_MINS};
+use subtitles::{sidecar_subtitles, subtitle_path};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, State};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 // Helper function to expand ~ to home directory
 fn expand_path(path: &str) -> PathBuf {
@@ -20,41 +54,439 @@ fn expand_path(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// Signed video URLs expiring within this window are re-resolved before downloading
+const URL_EXPIRY_MARGIN_SECS: u64 = 5 * 60;
+/// Default pause between series fetched by a batch import
+const IMPORT_INTERVAL: Duration = Duration::from_secs(1);
+/// How often subscriptions are looked at for ones due a check
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Interval for new subscriptions when none is given
+const DEFAULT_SUBSCRIPTION_INTERVAL_MINS: u64 = 6 * 60;
+/// How often the site rules file is checked for edits
+const RULES_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 // App state
 struct AppState {
     parser: RongyokParser,
+    site_rules: SiteRulesFile,
+    posters: Arc<PosterCache>,
+    series_cache: SeriesCache,
+    subscriptions: Subscriptions,
+    session: Session,
+    proxy: Mutex<ProxySettings>,
     downloader: Mutex<Option<VideoDownloader>>,
-    current_series: Mutex<Option<SeriesInfo>>,
-    download_states: Mutex<HashMap<i32, Arc<DownloadState>>>,
+    loaded_series: LoadedSeries,
+    /// Active downloads by site, series id and episode
+    download_states: Mutex<HashMap<(String, i32, i32), Arc<DownloadState>>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct DownloadRequest {
     series_id: i32,
+    /// Site of the series; needed only when two loaded series share an id
+    #[serde(default)]
+    site: Option<String>,
     episodes: Vec<i32>,
+    series_title: String,
+    #[serde(flatten)]
+    options: DownloadOptions,
+}
+
+/// How a batch is downloaded; the settings (or a preset) the user picked, or a subscription's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadOptions {
     output_dir: String,
     auto_merge: bool,
     concurrent_downloads: i32,
     speed_limit: i32,  // KB/s, 0 = unlimited
     file_naming: String, // "ep_001", "episode_1", "title_ep1"
-    series_title: String,
+    /// Which rendition to pick when an episode has several sources
+    #[serde(default)]
+    quality: QualityPreference,
+    /// Save subtitle tracks next to each episode as SRT
+    #[serde(default)]
+    download_subtitles: bool,
+    /// Mux subtitles into the merged file as soft subtitles
+    #[serde(default)]
+    embed_subtitles: bool,
+}
+
+impl From<DownloadOptions> for SubscriptionDownload {
+    fn from(options: DownloadOptions) -> Self {
+        Self {
+            output_dir: options.output_dir,
+            auto_merge: options.auto_merge,
+            concurrent_downloads: options.concurrent_downloads,
+            speed_limit: options.speed_limit,
+            file_naming: options.file_naming,
+            quality: options.quality,
+            download_subtitles: options.download_subtitles,
+            embed_subtitles: options.embed_subtitles,
+        }
+    }
+}
+
+impl From<SubscriptionDownload> for DownloadOptions {
+    fn from(download: SubscriptionDownload) -> Self {
+        Self {
+            output_dir: download.output_dir,
+            auto_merge: download.auto_merge,
+            concurrent_downloads: download.concurrent_downloads,
+            speed_limit: download.speed_limit,
+            file_naming: download.file_naming,
+            quality: download.quality,
+            download_subtitles: download.download_subtitles,
+            embed_subtitles: download.embed_subtitles,
+        }
+    }
+}
+
+/// A list of series links to load, and optionally queue for download
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportRequest {
+    /// Pasted text, CSV or clipboard contents
+    #[serde(default)]
+    text: Option<String>,
+    /// A text or CSV file to read when no text is given
+    #[serde(default)]
+    path: Option<String>,
+    /// Pause between series fetched from the sites, in milliseconds
+    #[serde(default)]
+    interval_ms: Option<u64>,
+    /// Queue every available episode of each imported series with these options
+    #[serde(default)]
+    enqueue: Option<DownloadOptions>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportReport {
+    lines: Vec<ImportLine>,
+    /// One download per imported series, in list order, when `enqueue` was given
+    downloads: Vec<DownloadRequest>,
+}
+
+/// Episodes to probe before queuing a download
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProbeRequest {
+    series_id: i32,
+    #[serde(default)]
+    site: Option<String>,
+    /// Empty means every episode of the series
+    #[serde(default)]
+    episodes: Vec<i32>,
+    #[serde(default)]
+    quality: QualityPreference,
+    /// KB/s per download, 0 = unlimited
+    #[serde(default)]
+    speed_limit: i32,
+    #[serde(default)]
+    concurrent_downloads: i32,
+}
+
+/// Download a poster into the on-disk cache and return its poster:// URL
+async fn cache_poster(state: &AppState, poster_url: Option<String>) -> Option<String> {
+    let key = state.posters.fetch(&poster_url?).await.ok()?;
+    Some(PosterCache::scheme_url(&key))
+}
+
+/// Point each summary's poster at the on-disk cache
+async fn cache_summary_posters(state: &AppState, summaries: &mut [SeriesSummary]) {
+    let posters = futures_util::future::join_all(
+        summaries.iter_mut().map(|s| cache_poster(state, s.poster_url.take())),
+    )
+    .await;
+    for (summary, poster) in summaries.iter_mut().zip(posters) {
+        summary.poster_url = poster;
+    }
+}
+
+/// Series info from the cache while it is younger than `max_age`, otherwise revalidated with the
+/// site and only re-scraped if the series page changed. An expired copy is served (marked offline)
+/// when the site can't be reached. `force` always re-scrapes, e.g. for fresh signed video URLs.
+async fn load_series(app_handle: &AppHandle, series_id: i32, url: &str, max_age: Duration, force: bool) -> Result<CachedSeriesInfo, ParserError> {
+    let state = app_handle.state::<AppState>();
+    let site = state.parser.site_for(Some(url))?.name().to_string();
+    let cached = state.series_cache.get(&site, series_id).filter(|_| !force);
+
+    if let Some(entry) = cached.clone().filter(|e| e.is_fresh(max_age)) {
+        return Ok(logged(app_handle, state.series_cache.touch(entry, false)).into_info(false));
+    }
+
+    let validators = cached.as_ref().map(|e| e.validators.clone()).unwrap_or_default();
+    match (state.parser.get_series_info_if_modified(series_id, Some(url), &validators).await, cached) {
+        (Ok(Some((mut series, validators))), _) => {
+            series.poster_url = cache_poster(&state, series.poster_url.take()).await;
+            // Keep cookies the site set while loading (age gates, logins)
+            let _ = state.session.save();
+            Ok(logged(app_handle, state.series_cache.store(series, validators)).into_info(false))
+        }
+        (Ok(None), Some(entry)) => Ok(logged(app_handle, state.series_cache.touch(entry, true)).into_info(false)),
+        (Err(_), Some(entry)) => Ok(logged(app_handle, state.series_cache.touch(entry, false)).into_info(true)),
+        // A 304 without validators; only a misbehaving server sends one
+        (Ok(None), None) => Err(ParserError::HttpStatus { status: 304, url: url.to_string() }),
+        (Err(e), None) => Err(e),
+    }
+}
+
+/// A failed cache write only costs the next lookup, so it is logged rather than failing the load
+fn logged(app_handle: &AppHandle, (entry, written): (CacheEntry, Result<(), StorageError>)) -> CacheEntry {
+    if let Err(e) = written {
+        let _ = app_handle.emit("log-info", format!("Failed to cache series {}: {}", entry.series.series_id, e));
+    }
+    entry
+}
+
+/// Re-fetch a series to get fresh signed video URLs
+async fn refresh_series(app_handle: &AppHandle, series: &mut SeriesInfo) -> Result<(), ParserError> {
+    let fresh = load_series(app_handle, series.series_id, &series.source_url, Duration::ZERO, true).await?.series;
+    app_handle.state::<AppState>().loaded_series.insert(fresh.clone());
+    *series = fresh;
+    Ok(())
+}
+
+/// Check the rest of a selection again after a refresh; episodes the fresh page no longer has
+/// are reported now and fail individually when their turn comes
+fn recheck_selection(app_handle: &AppHandle, series: &SeriesInfo, episodes: &[i32]) {
+    if let Err(unavailable) = series.check_selection(episodes) {
+        let list: Vec<String> = unavailable.keys().map(|ep| ep.to_string()).collect();
+        let _ = app_handle.emit(
+            "log-info",
+            format!("Episodes {} are no longer available after refreshing the series", list.join(", ")),
+        );
+    }
+}
+
+/// Remember where downloaded episodes were saved; a failed write is logged like other cache writes
+fn record_downloads(app_handle: &AppHandle, series: &SeriesInfo, files: &[(i32, String)]) {
+    let state = app_handle.state::<AppState>();
+    if let Err(e) = state.series_cache.record_downloads(&series.site, series.series_id, files) {
+        let _ = app_handle.emit("log-info", format!("Failed to cache series {}: {}", series.series_id, e));
+    }
+}
+
+/// Point the cache's download records for these episodes at the merged file
+fn record_merged(app_handle: &AppHandle, series: &SeriesInfo, downloaded: &[(i32, String)], output_path: &str) {
+    let merged: Vec<(i32, String)> = downloaded.iter().map(|(ep, _)| (*ep, output_path.to_string())).collect();
+    record_downloads(app_handle, series, &merged);
+}
+
+/// Re-read the site rules file and swap its sites into the parser
+fn reload_site_rules_into(state: &AppState) -> RulesReport {
+    let (sites, report) = state.site_rules.load();
+    state.parser.set_rule_sites(sites);
+    report
+}
+
+/// Wait until `interval` has passed since the previous site request
+async fn pace(last_request: &mut Option<Instant>, interval: Duration) {
+    if let Some(remaining) = last_request.and_then(|t| interval.checked_sub(t.elapsed())) {
+        tokio::time::sleep(remaining).await;
+    }
+    *last_request = Some(Instant::now());
+}
+
+/// Re-check a subscribed series. New episodes are announced with `new-episodes` and, when the
+/// subscription has download settings, downloaded in the background.
+async fn check_subscription(handle: &AppHandle, mut subscription: Subscription) -> Result<NewEpisodes, AppError> {
+    let state = handle.state::<AppState>();
+    subscription.last_checked = Some(series_cache::now());
+
+    let loaded = load_series(handle, subscription.series_id, &subscription.source_url, Duration::ZERO, false).await;
+    let info = match loaded {
+        Ok(info) => info,
+        Err(e) => {
+            // A failed check still waits for the next interval
+            let _ = state.subscriptions.update(&subscription);
+            return Err(e.into());
+        }
+    };
+
+    // An offline copy can't have new episodes
+    let episodes = if info.offline {
+        Vec::new()
+    } else {
+        subscription.take_new_episodes(&info.series.available_episodes(), &info.downloaded_episodes)
+    };
+    subscription.title = info.series.title.clone();
+    state.subscriptions.update(&subscription)?;
+
+    let series = info.series;
+    state.loaded_series.insert(series.clone());
+
+    let download = subscription.download.clone().filter(|_| !episodes.is_empty());
+    let enqueued = download.is_some();
+    if let Some(options) = download {
+        let request = DownloadRequest {
+            series_id: series.series_id,
+            site: Some(series.site.clone()),
+            episodes: episodes.clone(),
+            series_title: series.title.clone(),
+            options: options.into(),
+        };
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let title = request.series_title.clone();
+            if let Err(e) = start_download(request, handle.clone(), handle.state::<AppState>()).await {
+                let _ = handle.emit("log-info", format!("Subscription download of {} failed: {}", title, e));
+            }
+        });
+    }
+
+    let new_episodes = NewEpisodes {
+        site: series.site,
+        series_id: series.series_id,
+        title: series.title,
+        episodes,
+        enqueued,
+    };
+    if !new_episodes.episodes.is_empty() {
+        let _ = handle.emit("new-episodes", &new_episodes);
+    }
+    Ok(new_episodes)
+}
+
+fn max_age(secs: Option<u64>) -> Duration {
+    secs.map(Duration::from_secs).unwrap_or(DEFAULT_MAX_AGE)
 }
 
 // Commands
 
+/// Load a series, from the cache if it was fetched within `max_age_secs` (default one day).
+/// Episode, mobile and short links are accepted; an episode link sets `targetEpisode`.
 #[tauri::command]
-async fn fetch_series(url: String, state: State<'_, AppState>) -> Result<SeriesInfo, String> {
-    let series_id = RongyokParser::parse_series_url(&url).ok_or("Invalid URL format")?;
+async fn fetch_series(url: String, max_age_secs: Option<u64>, app_handle: AppHandle, state: State<'_, AppState>) -> Result<CachedSeriesInfo, AppError> {
+    let target = state.parser.resolve_target(&url).await?;
+
+    let mut series_info = load_series(&app_handle, target.series_id, &target.series_url, max_age(max_age_secs), false).await?;
+    series_info.target_episode = target.episode.filter(|ep| (1..=series_info.series.total_episodes).contains(ep));
 
-    let series_info = state.parser.get_series_info(series_id, Some(&url)).await?;
+    state.loaded_series.insert(series_info.series.clone());
+    Ok(series_info)
+}
 
-    // Store in state
-    *state.current_series.lock().unwrap() = Some(series_info.clone());
+/// Re-scrape a series now, ignoring the cache
+#[tauri::command]
+async fn refresh_cached_series(url: String, app_handle: AppHandle, state: State<'_, AppState>) -> Result<CachedSeriesInfo, AppError> {
+    let target = state.parser.resolve_target(&url).await?;
 
+    let series_info = load_series(&app_handle, target.series_id, &target.series_url, Duration::ZERO, true).await?;
+    state.loaded_series.insert(series_info.series.clone());
     Ok(series_info)
 }
 
+/// Load every series linked in a text/CSV list (or file), one line at a time with a pause
+/// between site requests. Emits `import-progress` per line. With `enqueue`, the report also
+/// holds a download request for each imported series.
+#[tauri::command]
+async fn import_series_list(request: ImportRequest, app_handle: AppHandle, state: State<'_, AppState>) -> Result<ImportReport, AppError> {
+    let text = match (request.text, request.path) {
+        (Some(text), _) => text,
+        (None, Some(path)) => {
+            let path = expand_path(&path);
+            std::fs::read_to_string(&path).map_err(|e| StorageError::read(&path, e))?
+        }
+        (None, None) => return Err(AppError::NothingToImport),
+    };
+    let interval = request.interval_ms.map(Duration::from_millis).unwrap_or(IMPORT_INTERVAL);
+
+    let mut report = ImportReport { lines: Vec::new(), downloads: Vec::new() };
+    let mut seen: HashMap<(String, i32), usize> = HashMap::new();
+    let mut last_request = None;
+
+    for entry in read_entries(&text) {
+        let mut result = ImportLine::new(&entry, ImportStatus::Skipped);
+
+        if !entry.urls.is_empty() {
+            // Links to known sites parse offline; otherwise the first URL may be a short link
+            let target = match entry.urls.iter().find_map(|url| state.parser.parse_target(url)) {
+                Some(target) => Ok(target),
+                None => {
+                    pace(&mut last_request, interval).await;
+                    state.parser.resolve_target(&entry.urls[0]).await
+                }
+            };
+
+            match target {
+                Err(e) => {
+                    result.status = ImportStatus::Invalid;
+                    result.error = Some(e.into());
+                }
+                Ok(target) => match seen.get(&(target.site.clone(), target.series_id)) {
+                    Some(first) => result.status = ImportStatus::Duplicate { of: *first },
+                    None => {
+                        seen.insert((target.site.clone(), target.series_id), entry.line);
+                        let cached = state.series_cache.get(&target.site, target.series_id);
+                        if !cached.is_some_and(|e| e.is_fresh(DEFAULT_MAX_AGE)) {
+                            pace(&mut last_request, interval).await;
+                        }
+
+                        match load_series(&app_handle, target.series_id, &target.series_url, DEFAULT_MAX_AGE, false).await {
+                            Ok(info) => {
+                                let series = info.series;
+                                result.status = ImportStatus::Imported;
+                                result.series = Some(LoadedSeriesSummary::from(&series));
+                                let episodes = series.available_episodes();
+                                if let Some(options) = request.enqueue.clone().filter(|_| !episodes.is_empty()) {
+                                    report.downloads.push(DownloadRequest {
+                                        series_id: series.series_id,
+                                        site: Some(series.site.clone()),
+                                        episodes,
+                                        series_title: series.title.clone(),
+                                        options,
+                                    });
+                                }
+                                state.loaded_series.insert(series);
+                            }
+                            Err(e) => {
+                                result.status = ImportStatus::Failed;
+                                result.error = Some(e.into());
+                            }
+                        }
+                    }
+                },
+            }
+        }
+
+        let _ = app_handle.emit("import-progress", &result);
+        report.lines.push(result);
+    }
+
+    Ok(report)
+}
+
+/// Recently viewed series, most recent first; `expired` is judged against `max_age_secs`
+#[tauri::command]
+fn list_cached_series(max_age_secs: Option<u64>, state: State<'_, AppState>) -> Vec<CachedSeriesSummary> {
+    state.series_cache.list(max_age(max_age_secs))
+}
+
+/// Forget a cached series; returns whether it was cached
+#[tauri::command]
+fn evict_cached_series(site: String, series_id: i32, state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.series_cache.evict(&site, series_id)?)
+}
+
+/// Search the catalog by keyword; each result's `url` can be passed to `fetch_series`
+#[tauri::command]
+async fn search_series(query: String, site: Option<String>, state: State<'_, AppState>) -> Result<Vec<SeriesSummary>, AppError> {
+    let mut results = state.parser.search_series(&query, site.as_deref()).await?;
+    cache_summary_posters(&state, &mut results).await;
+    Ok(results)
+}
+
+/// Read a genre, category or "latest updates" listing, following up to `max_pages` pages (default 1)
+#[tauri::command]
+async fn fetch_listing(url: String, max_pages: Option<u32>, state: State<'_, AppState>) -> Result<SeriesPage, AppError> {
+    let mut listing = state.parser.crawl_listing(&url, max_pages.unwrap_or(1).max(1)).await?;
+    cache_summary_posters(&state, &mut listing.series).await;
+    Ok(listing)
+}
+
 #[tauri::command]
 fn check_ffmpeg_available() -> bool {
     check_ffmpeg()
@@ -65,105 +497,189 @@ async fn start_download(
     request: DownloadRequest,
     app_handle: AppHandle,
     state: State<'_, AppState>,
-) -> Result<Vec<DownloadResult>, String> {
-    let series = state
-        .current_series
-        .lock()
-        .unwrap()
-        .clone()
-        .ok_or("No series loaded")?;
+) -> Result<Vec<DownloadResult>, AppError> {
+    let mut series = state
+        .loaded_series
+        .get(request.site.as_deref(), request.series_id)
+        .ok_or(AppError::NoSeriesLoaded { series_id: request.series_id })?;
+    // Refuse the whole selection up front rather than stopping at the first gap
+    series
+        .check_selection(&request.episodes)
+        .map_err(|episodes| AppError::EpisodesUnavailable { episodes })?;
 
     // Create downloader with config
     let config = DownloadConfig {
-        speed_limit_kbps: request.speed_limit,
-        file_naming: request.file_naming.clone(),
+        speed_limit_kbps: request.options.speed_limit,
+        file_naming: request.options.file_naming.clone(),
         series_title: request.series_title.clone(),
+        quality: request.options.quality,
+        session: state.session.clone(),
+        proxy: state.proxy.lock().unwrap().clone(),
     };
-    let _downloader = VideoDownloader::with_config(&request.output_dir, config.clone());
-    *state.downloader.lock().unwrap() = Some(VideoDownloader::with_config(&request.output_dir, config));
+    let _downloader = VideoDownloader::with_config(&request.options.output_dir, config.clone());
+    *state.downloader.lock().unwrap() = Some(VideoDownloader::with_config(&request.options.output_dir, config));
 
     let mut results = Vec::new();
     let mut successful_files = Vec::new();
+    let mut downloaded: Vec<(i32, String)> = Vec::new();
 
     // Concurrent downloads using chunks
-    let concurrent = request.concurrent_downloads.max(1) as usize;
-
-    for chunk in request.episodes.chunks(concurrent) {
-        let mut handles = Vec::new();
-
-        for episode in chunk {
-            let video_url = series
-                .episode_urls
-                .get(episode)
-                .ok_or(format!("No URL for episode {}", episode))?
-                .clone();
-
-            let app = app_handle.clone();
-            let dl = VideoDownloader::with_config(
-                &request.output_dir,
-                DownloadConfig {
-                    speed_limit_kbps: request.speed_limit,
-                    file_naming: request.file_naming.clone(),
-                    series_title: request.series_title.clone(),
+    let concurrent = request.options.concurrent_downloads.max(1) as usize;
+
+    for (i, chunk) in request.episodes.chunks(concurrent).enumerate() {
+        // Signed CDN URLs expire - re-resolve before starting if any in this chunk are stale
+        let expiring = chunk.iter().any(|ep| {
+            series
+                .video_url_for(*ep, request.options.quality)
+                .is_some_and(|url| url_expires_soon(&url, URL_EXPIRY_MARGIN_SECS))
+        });
+        if expiring {
+            let _ = app_handle.emit("log-info", "Video URLs are about to expire, refreshing series...".to_string());
+            match refresh_series(&app_handle, &mut series).await {
+                Ok(()) => recheck_selection(&app_handle, &series, &request.episodes[i * concurrent..]),
+                Err(e) => {
+                    let _ = app_handle.emit("log-info", format!("Series refresh failed: {}", e));
                 }
-            );
-            let ep = *episode;
-
-            // Create download state for this episode
-            let download_state = Arc::new(DownloadState::new());
-            {
-                let mut states = state.download_states.lock().unwrap();
-                states.insert(ep, download_state.clone());
             }
-
-            let handle = tokio::spawn(async move {
-                dl.download_episode(ep, &video_url, &app, Some(download_state)).await
-            });
-            handles.push((ep, handle));
         }
 
-        // Wait for all in this chunk to complete
-        for (ep, handle) in handles {
-            match handle.await {
-                Ok(result) => {
-                    // Remove from download states when done
-                    {
-                        let mut states = state.download_states.lock().unwrap();
-                        states.remove(&ep);
+        let mut pending: Vec<i32> = chunk.to_vec();
+        let mut refreshed = false;
+
+        while !pending.is_empty() {
+            let mut handles = Vec::new();
+
+            for episode in &pending {
+                // The refreshed page may have dropped the episode; fail it and carry on with the rest
+                let Some(video_url) = series.video_url_for(*episode, request.options.quality) else {
+                    let result = DownloadResult {
+                        episode: *episode,
+                        success: false,
+                        file_path: None,
+                        error: Some(DownloadError::NoSource { episode: *episode }),
+                    };
+                    let _ = app_handle.emit("download-result", &result);
+                    results.push(result);
+                    continue;
+                };
+
+                let app = app_handle.clone();
+                let dl = VideoDownloader::with_config(
+                    &request.options.output_dir,
+                    DownloadConfig {
+                        speed_limit_kbps: request.options.speed_limit,
+                        file_naming: request.options.file_naming.clone(),
+                        series_title: request.series_title.clone(),
+                        quality: request.options.quality,
+                        session: state.session.clone(),
+                        proxy: state.proxy.lock().unwrap().clone(),
                     }
+                );
+                let ep = *episode;
+                let subtitle_tracks = if request.options.download_subtitles {
+                    series
+                        .episodes
+                        .iter()
+                        .find(|e| e.episode_number == ep)
+                        .map(|e| e.subtitles.clone())
+                        .unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+
+                // Create download state for this episode
+                let download_state = Arc::new(DownloadState::new());
+                {
+                    let mut states = state.download_states.lock().unwrap();
+                    states.insert((series.site.clone(), series.series_id, ep), download_state.clone());
+                }
 
+                let handle = tokio::spawn(async move {
+                    let result = dl.download_episode(ep, &video_url, &app, Some(download_state)).await;
                     if result.success {
-                        if let Some(ref path) = result.file_path {
-                            successful_files.push(path.clone());
+                        for outcome in dl.download_subtitles(ep, &subtitle_tracks).await {
+                            if let Err(e) = outcome {
+                                let _ = app.emit("log-info", format!("EP{} subtitle failed: {}", ep, e));
+                            }
                         }
                     }
-                    let _ = app_handle.emit("download-result", &result);
-                    results.push(result);
+                    result
+                });
+                handles.push((ep, handle));
+            }
+
+            // Wait for all in this chunk to complete
+            let mut stale = Vec::new();
+            for (ep, handle) in handles {
+                match handle.await {
+                    Ok(result) => {
+                        // Remove from download states when done
+                        {
+                            let mut states = state.download_states.lock().unwrap();
+                            states.remove(&(series.site.clone(), series.series_id, ep));
+                        }
+
+                        // Expired URL: retry once with fresh URLs, resuming from the partial file
+                        if result.url_expired() && !refreshed {
+                            stale.push(result);
+                            continue;
+                        }
+
+                        if result.success {
+                            if let Some(ref path) = result.file_path {
+                                successful_files.push(path.clone());
+                                downloaded.push((result.episode, path.clone()));
+                            }
+                        }
+                        let _ = app_handle.emit("download-result", &result);
+                        results.push(result);
+                    }
+                    Err(e) => {
+                        // Remove from download states on error
+                        {
+                            let mut states = state.download_states.lock().unwrap();
+                            states.remove(&(series.site.clone(), series.series_id, ep));
+                        }
+                        let result = DownloadResult {
+                            episode: ep,
+                            success: false,
+                            file_path: None,
+                            error: Some(DownloadError::Task(e.to_string())),
+                        };
+                        results.push(result);
+                    }
                 }
+            }
+
+            pending = stale.iter().map(|r| r.episode).collect();
+            if pending.is_empty() {
+                break;
+            }
+
+            let _ = app_handle.emit("log-info", format!("Video URLs expired for episodes {:?}, refreshing series...", pending));
+            refreshed = true;
+            match refresh_series(&app_handle, &mut series).await {
+                Ok(()) => recheck_selection(&app_handle, &series, &pending),
                 Err(e) => {
-                    // Remove from download states on error
-                    {
-                        let mut states = state.download_states.lock().unwrap();
-                        states.remove(&ep);
+                    let _ = app_handle.emit("log-info", format!("Series refresh failed: {}", e));
+                    for result in stale {
+                        let _ = app_handle.emit("download-result", &result);
+                        results.push(result);
                     }
-                    let result = DownloadResult {
-                        episode: ep,
-                        success: false,
-                        file_path: None,
-                        error: Some(format!("Task failed: {}", e)),
-                    };
-                    results.push(result);
+                    pending.clear();
                 }
             }
         }
     }
 
+    record_downloads(&app_handle, &series, &downloaded);
+
     // Debug: emit info about what we're about to do
     let files_count = successful_files.len();
     let ffmpeg_available = check_ffmpeg();
     let merge_info = format!(
         "Merge check: auto_merge={}, files={}, ffmpeg={}",
-        request.auto_merge,
+        request.options.auto_merge,
         files_count,
         ffmpeg_available
     );
@@ -175,14 +691,14 @@ async fn start_download(
     }
 
     // Merge if requested
-    let should_merge = request.auto_merge && files_count > 0 && ffmpeg_available;
+    let should_merge = request.options.auto_merge && files_count > 0 && ffmpeg_available;
     let _ = app_handle.emit("log-info", format!("Should merge: {}", should_merge));
 
     if should_merge {
         let _ = app_handle.emit("log-info", format!("Series title: {}", series.title));
         let output_filename = sanitize_filename(&series.title);
         let _ = app_handle.emit("log-info", format!("Output filename: {}", output_filename));
-        let expanded_output_dir = expand_path(&request.output_dir);
+        let expanded_output_dir = expand_path(&request.options.output_dir);
         let _ = app_handle.emit("log-info", format!("Expanded dir: {:?}", expanded_output_dir));
         let output_path = expanded_output_dir.join(format!("{}.mp4", output_filename));
         let output_path_str = output_path.to_string_lossy().to_string();
@@ -190,7 +706,7 @@ async fn start_download(
         let _ = app_handle.emit("log-info", format!("Starting merge to: {}", output_path_str));
         let _ = app_handle.emit("merge-started", ());
 
-        if successful_files.len() == 1 {
+        if successful_files.len() == 1 && !request.options.embed_subtitles {
             // Just rename/copy the single file
             let _ = app_handle.emit("log-info", "Single file - renaming...".to_string());
 
@@ -199,8 +715,14 @@ async fn start_download(
                 .map_err(|e| format!("Cannot find source file: {}", e))
                 .unwrap_or(std::path::PathBuf::from(&successful_files[0]));
 
+            // Subtitle sidecars follow the video's new name
+            for (language, path) in sidecar_subtitles(&source) {
+                std::fs::rename(&path, subtitle_path(&output_path, language.as_deref(), &[])).ok();
+            }
+
             match std::fs::rename(&source, &output_path) {
                 Ok(_) => {
+                    record_merged(&app_handle, &series, &downloaded, &output_path_str);
                     let _ = app_handle.emit("merge-complete", output_path_str);
                 }
                 Err(e) => {
@@ -209,10 +731,11 @@ async fn start_download(
                     match std::fs::copy(&source, &output_path) {
                         Ok(_) => {
                             std::fs::remove_file(&source).ok();
+                            record_merged(&app_handle, &series, &downloaded, &output_path_str);
                             let _ = app_handle.emit("merge-complete", output_path_str.clone());
                         }
                         Err(e) => {
-                            let _ = app_handle.emit("merge-error", format!("Failed to rename: {}", e));
+                            let _ = app_handle.emit("merge-error", MergeError::io(&output_path, e));
                         }
                     }
                 }
@@ -225,13 +748,17 @@ async fn start_download(
             let mut sorted_files = successful_files.clone();
             sorted_files.sort();
 
-            match merge_videos_with_progress(sorted_files.clone(), &output_path_str, Some(&app_handle)) {
+            match merge_videos_with_progress(sorted_files.clone(), &output_path_str, Some(&app_handle), request.options.embed_subtitles) {
                 Ok(_) => {
                     let _ = app_handle.emit("log-info", "Merge complete, deleting individual files...".to_string());
-                    // Delete individual files after successful merge
+                    // Delete individual files (and their subtitle sidecars) after successful merge
                     for file in &sorted_files {
+                        for (_, subtitle) in sidecar_subtitles(std::path::Path::new(file)) {
+                            std::fs::remove_file(subtitle).ok();
+                        }
                         std::fs::remove_file(file).ok();
                     }
+                    record_merged(&app_handle, &series, &downloaded, &output_path_str);
                     let _ = app_handle.emit("merge-complete", output_path_str);
                 }
                 Err(e) => {
@@ -239,70 +766,283 @@ async fn start_download(
                 }
             }
         }
-    } else if request.auto_merge && !ffmpeg_available {
-        let _ = app_handle.emit("merge-error", "FFmpeg not found - cannot merge videos".to_string());
+    } else if request.options.auto_merge && !ffmpeg_available {
+        let _ = app_handle.emit("merge-error", MergeError::FfmpegNotFound);
     } else {
-        let _ = app_handle.emit("log-info", format!("Merge skipped: auto_merge={}, files={}", request.auto_merge, files_count));
+        let _ = app_handle.emit("log-info", format!("Merge skipped: auto_merge={}, files={}", request.options.auto_merge, files_count));
     }
 
+    let _ = state.session.save();
     Ok(results)
 }
 
+/// Cookies currently stored for the sites (names only, no values)
+#[tauri::command]
+fn get_cookies(state: State<'_, AppState>) -> Vec<CookieInfo> {
+    state.session.cookies()
+}
+
+/// Import a Netscape-format cookies.txt export, e.g. from a logged-in browser
 #[tauri::command]
-async fn pause_download(episode: i32, state: State<'_, AppState>) -> Result<(), String> {
+async fn import_cookies(path: String, state: State<'_, AppState>) -> Result<usize, AppError> {
+    let path = expand_path(&path);
+    let text = std::fs::read_to_string(&path).map_err(|e| StorageError::read(&path, e))?;
+    Ok(state.session.import_netscape(&text)?)
+}
+
+/// Log out of every site by dropping all stored cookies
+#[tauri::command]
+fn clear_cookies(state: State<'_, AppState>) -> Result<(), AppError> {
+    Ok(state.session.clear()?)
+}
+
+#[tauri::command]
+fn get_proxy_settings(state: State<'_, AppState>) -> ProxySettings {
+    state.proxy.lock().unwrap().clone()
+}
+
+/// Validate, apply to every HTTP client and persist new proxy settings
+#[tauri::command]
+fn set_proxy_settings(settings: ProxySettings, state: State<'_, AppState>) -> Result<(), AppError> {
+    let client = build_client(&state.session, &settings)?;
+    settings.save()?;
+
+    state.parser.set_client(client.clone());
+    state.posters.set_client(client);
+    *state.proxy.lock().unwrap() = settings;
+    Ok(())
+}
+
+/// Check that a site is reachable through the given settings (the saved ones by default)
+#[tauri::command]
+async fn test_proxy(settings: Option<ProxySettings>, state: State<'_, AppState>) -> Result<ProxyTestResult, AppError> {
+    let settings = settings.unwrap_or_else(|| state.proxy.lock().unwrap().clone());
+    let target = state.parser.site_for(None)?.base_url().to_string();
+    Ok(network::test_proxy(&settings, &target).await?)
+}
+
+/// Check the site rules file (or unsaved `contents`) for errors without loading it
+#[tauri::command]
+fn validate_site_rules(contents: Option<String>, state: State<'_, AppState>) -> RulesReport {
+    state.site_rules.validate(contents.as_deref())
+}
+
+/// Load the site rules file now instead of waiting for the change to be noticed
+#[tauri::command]
+fn reload_site_rules(state: State<'_, AppState>) -> RulesReport {
+    reload_site_rules_into(&state)
+}
+
+/// Active downloads of an episode; without a site or series id, that episode in every
+/// running batch that matches the rest
+fn active_downloads(
+    state: &AppState,
+    site: Option<&str>,
+    series_id: Option<i32>,
+    episode: i32,
+) -> Result<Vec<Arc<DownloadState>>, AppError> {
     let states = state.download_states.lock().unwrap();
-    if let Some(download_state) = states.get(&episode) {
-        download_state.is_paused.store(true, std::sync::atomic::Ordering::SeqCst);
-        Ok(())
+    let matching: Vec<_> = states
+        .iter()
+        .filter(|((s, id, ep), _)| {
+            *ep == episode && site.is_none_or(|site| site == s) && series_id.is_none_or(|i| i == *id)
+        })
+        .map(|(_, download_state)| download_state.clone())
+        .collect();
+    if matching.is_empty() {
+        Err(AppError::NoActiveDownload { episode })
     } else {
-        Err(format!("No active download for episode {}", episode))
+        Ok(matching)
     }
 }
 
 #[tauri::command]
-async fn resume_download(episode: i32, state: State<'_, AppState>) -> Result<(), String> {
-    let states = state.download_states.lock().unwrap();
-    if let Some(download_state) = states.get(&episode) {
+async fn pause_download(
+    episode: i32,
+    series_id: Option<i32>,
+    site: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    for download_state in active_downloads(&state, site.as_deref(), series_id, episode)? {
+        download_state.is_paused.store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn resume_download(
+    episode: i32,
+    series_id: Option<i32>,
+    site: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    for download_state in active_downloads(&state, site.as_deref(), series_id, episode)? {
         download_state.is_paused.store(false, std::sync::atomic::Ordering::SeqCst);
-        Ok(())
-    } else {
-        Err(format!("No active download for episode {}", episode))
     }
+    Ok(())
 }
 
 #[tauri::command]
-async fn cancel_download(episode: i32, state: State<'_, AppState>) -> Result<(), String> {
-    let states = state.download_states.lock().unwrap();
-    if let Some(download_state) = states.get(&episode) {
+async fn cancel_download(
+    episode: i32,
+    series_id: Option<i32>,
+    site: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    for download_state in active_downloads(&state, site.as_deref(), series_id, episode)? {
         download_state.is_cancelled.store(true, std::sync::atomic::Ordering::SeqCst);
-        Ok(())
+    }
+    Ok(())
+}
+
+/// Subscribed series, in the order they were added
+#[tauri::command]
+fn list_subscriptions(state: State<'_, AppState>) -> Vec<Subscription> {
+    state.subscriptions.list()
+}
+
+/// Subscribe to a loaded (or cached) series, checking it every `interval_mins` (default 6 hours).
+/// Episodes already out don't count as new. With `download`, new episodes are downloaded with
+/// those options. Subscribing again updates the interval and download options.
+#[tauri::command]
+fn subscribe_series(
+    site: String,
+    series_id: i32,
+    interval_mins: Option<u64>,
+    download: Option<DownloadOptions>,
+    state: State<'_, AppState>,
+) -> Result<Subscription, AppError> {
+    let series = state
+        .loaded_series
+        .get(Some(&site), series_id)
+        .or_else(|| state.series_cache.get(&site, series_id).map(|e| e.series))
+        .ok_or(AppError::NoSeriesLoaded { series_id })?;
+    let existing = state.subscriptions.list().into_iter().find(|s| s.site == site && s.series_id == series_id);
+
+    let subscription = Subscription {
+        site,
+        series_id,
+        title: series.title.clone(),
+        source_url: series.source_url.clone(),
+        interval_mins: interval_mins.unwrap_or(DEFAULT_SUBSCRIPTION_INTERVAL_MINS).max(MIN_INTERVAL_MINS),
+        download: download.map(SubscriptionDownload::from),
+        reported: match &existing {
+            Some(existing) => existing.reported.clone(),
+            None => series.available_episodes().into_iter().collect(),
+        },
+        last_checked: existing.and_then(|s| s.last_checked).or(Some(series_cache::now())),
+    };
+    state.subscriptions.upsert(subscription.clone())?;
+    Ok(subscription)
+}
+
+/// Stop following a series; returns whether it was subscribed
+#[tauri::command]
+fn unsubscribe_series(site: String, series_id: i32, state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.subscriptions.remove(&site, series_id)?)
+}
+
+/// Check every subscription now, regardless of interval; returns those with new episodes
+#[tauri::command]
+async fn check_subscriptions_now(app_handle: AppHandle, state: State<'_, AppState>) -> Result<Vec<NewEpisodes>, AppError> {
+    let mut found = Vec::new();
+    for subscription in state.subscriptions.list() {
+        let title = subscription.title.clone();
+        match check_subscription(&app_handle, subscription).await {
+            Ok(new_episodes) if !new_episodes.episodes.is_empty() => found.push(new_episodes),
+            Ok(_) => {}
+            Err(e) => {
+                let _ = app_handle.emit("log-info", format!("Checking {} failed: {}", title, e));
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Probe each episode URL of a loaded series (size, type, range support, liveness) and estimate
+/// the total size and download time, so dead links and disk space show up before queuing
+#[tauri::command]
+async fn probe_download(request: ProbeRequest, app_handle: AppHandle, state: State<'_, AppState>) -> Result<SeriesProbe, AppError> {
+    let mut series = state
+        .loaded_series
+        .get(request.site.as_deref(), request.series_id)
+        .ok_or(AppError::NoSeriesLoaded { series_id: request.series_id })?;
+    let episodes = if request.episodes.is_empty() {
+        (1..=series.total_episodes).collect()
     } else {
-        Err(format!("No active download for episode {}", episode))
+        request.episodes.clone()
+    };
+
+    // Expiring signed URLs would only probe as dead
+    let expiring = episodes.iter().any(|ep| {
+        series
+            .video_url_for(*ep, request.quality)
+            .is_some_and(|url| url_expires_soon(&url, URL_EXPIRY_MARGIN_SECS))
+    });
+    if expiring {
+        refresh_series(&app_handle, &mut series).await?;
     }
+
+    let proxy = state.proxy.lock().unwrap().clone();
+    let client = build_client(&state.session, &proxy)?;
+    let probes = probe_episodes(&client, &series, &episodes, request.quality).await;
+
+    let sample_url = probes
+        .iter()
+        .find(|p| p.alive && p.kind == StreamKind::Progressive && p.accepts_ranges)
+        .and_then(|p| p.url.clone());
+    let sampled = match sample_url {
+        Some(url) => sample_throughput(&client, &url).await,
+        None => None,
+    };
+    let limit = (request.speed_limit > 0).then_some(request.speed_limit as f64 * 1024.0);
+    let per_download = match (sampled, limit) {
+        (Some(sampled), Some(limit)) => Some(sampled.min(limit)),
+        (sampled, limit) => sampled.or(limit),
+    };
+    let bytes_per_sec = per_download.map(|rate| rate * request.concurrent_downloads.max(1) as f64);
+
+    Ok(SeriesProbe::new(probes, bytes_per_sec))
+}
+
+/// Series loaded this run, most recently loaded first
+#[tauri::command]
+fn list_loaded_series(state: State<'_, AppState>) -> Vec<LoadedSeriesSummary> {
+    state.loaded_series.list()
+}
+
+/// Drop a loaded series from memory; returns whether it was loaded
+#[tauri::command]
+fn unload_series(site: String, series_id: i32, state: State<'_, AppState>) -> bool {
+    state.loaded_series.remove(&site, series_id)
 }
 
 #[tauri::command]
 async fn get_episode_url(
     series_id: i32,
     episode: i32,
+    site: Option<String>,
+    app_handle: AppHandle,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    // Try from cached series first
-    if let Some(series) = state.current_series.lock().unwrap().as_ref() {
-        if series.series_id == series_id {
-            if let Some(url) = series.episode_urls.get(&episode) {
-                return Ok(url.clone());
-            }
+) -> Result<String, AppError> {
+    // Only a loaded series knows which site (and page) the id belongs to
+    let mut series = state
+        .loaded_series
+        .get(site.as_deref(), series_id)
+        .ok_or(AppError::NoSeriesLoaded { series_id })?;
+    if let Some(url) = series.episode_urls.get(&episode) {
+        if !url_expires_soon(url, URL_EXPIRY_MARGIN_SECS) {
+            return Ok(url.clone());
         }
     }
 
-    // Fetch fresh
-    let series_info = state.parser.get_series_info(series_id, None).await?;
-    series_info
+    // Fetch fresh signed URLs from the series' own page
+    refresh_series(&app_handle, &mut series).await?;
+    series
         .episode_urls
         .get(&episode)
         .cloned()
-        .ok_or(format!("No URL for episode {}", episode))
+        .ok_or(DownloadError::NoSource { episode }.into())
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -316,7 +1056,7 @@ struct FileInfo {
 }
 
 #[tauri::command]
-async fn open_folder(path: String) -> Result<(), String> {
+async fn open_folder(path: String) -> Result<(), AppError> {
     let expanded_path = expand_path(&path);
     let path_str = expanded_path.to_string_lossy().to_string();
 
@@ -325,27 +1065,27 @@ async fn open_folder(path: String) -> Result<(), String> {
         std::process::Command::new("open")
             .arg(&path_str)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::OpenFailed { path: path_str.clone(), message: e.to_string() })?;
     }
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
             .arg(&path_str)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::OpenFailed { path: path_str.clone(), message: e.to_string() })?;
     }
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
             .arg(&path_str)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::OpenFailed { path: path_str.clone(), message: e.to_string() })?;
     }
     Ok(())
 }
 
 #[tauri::command]
-async fn list_files(dir: String) -> Result<Vec<FileInfo>, String> {
+async fn list_files(dir: String) -> Result<Vec<FileInfo>, AppError> {
     let expanded_path = expand_path(&dir);
     if !expanded_path.exists() {
         // Try to create the directory
@@ -354,16 +1094,16 @@ async fn list_files(dir: String) -> Result<Vec<FileInfo>, String> {
     }
 
     let mut files = Vec::new();
-    let entries = std::fs::read_dir(&expanded_path).map_err(|e| e.to_string())?;
+    let entries = std::fs::read_dir(&expanded_path).map_err(|e| StorageError::read(&expanded_path, e))?;
 
     for entry in entries {
-        let entry = entry.map_err(|e| e.to_string())?;
+        let entry = entry.map_err(|e| StorageError::read(&expanded_path, e))?;
         let file_path = entry.path();
 
         if file_path.is_file() {
             if let Some(ext) = file_path.extension() {
                 if ext == "mp4" || ext == "ts" || ext == "mkv" {
-                    let metadata = std::fs::metadata(&file_path).map_err(|e| e.to_string())?;
+                    let metadata = std::fs::metadata(&file_path).map_err(|e| StorageError::read(&file_path, e))?;
                     let name = file_path.file_name()
                         .and_then(|n| n.to_str())
                         .unwrap_or("")
@@ -391,7 +1131,7 @@ async fn list_files(dir: String) -> Result<Vec<FileInfo>, String> {
 }
 
 #[tauri::command]
-async fn delete_files(paths: Vec<String>) -> Result<u32, String> {
+async fn delete_files(paths: Vec<String>) -> Result<u32, AppError> {
     let mut deleted = 0;
     for path in paths {
         if std::fs::remove_file(&path).is_ok() {
@@ -402,27 +1142,27 @@ async fn delete_files(paths: Vec<String>) -> Result<u32, String> {
 }
 
 #[tauri::command]
-async fn play_file(path: String) -> Result<(), String> {
+async fn play_file(path: String) -> Result<(), AppError> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
             .arg(&path)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::OpenFailed { path: path.clone(), message: e.to_string() })?;
     }
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("cmd")
             .args(["/c", "start", "", &path])
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::OpenFailed { path: path.clone(), message: e.to_string() })?;
     }
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
             .arg(&path)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::OpenFailed { path: path.clone(), message: e.to_string() })?;
     }
     Ok(())
 }
@@ -439,22 +1179,114 @@ pub fn run() {
         }
     }
 
+    let posters = Arc::new(PosterCache::new());
+    let session = Session::load();
+    let proxy = ProxySettings::load();
+    let parser = RongyokParser::new();
+
+    // Mirror sites from the user's rules file; broken rules are skipped, not fatal
+    let site_rules = SiteRulesFile::new();
+    let (rule_sites, report) = site_rules.load();
+    parser.set_rule_sites(rule_sites);
+    for issue in &report.issues {
+        eprintln!("Site rules ({}): {:?}", report.path, issue);
+    }
+
+    // A corrupt list is kept aside and the app starts with no subscriptions
+    let (subscriptions, subscriptions_error) = Subscriptions::new();
+    if let Some(e) = subscriptions_error {
+        eprintln!("Subscriptions: {}", e);
+    }
+
+    // Settings are validated before they are saved, but never fail to start over a bad file
+    match build_client(&session, &proxy) {
+        Ok(client) => {
+            parser.set_client(client.clone());
+            posters.set_client(client);
+        }
+        Err(e) => eprintln!("Ignoring proxy settings: {}", e),
+    }
+    let poster_source = posters.clone();
+
     tauri::Builder::default()
+        .register_uri_scheme_protocol(POSTER_SCHEME, move |_ctx, request| {
+            let key = request.uri().path().trim_start_matches('/');
+            match poster_source.load(key) {
+                Some((bytes, content_type)) => tauri::http::Response::builder()
+                    .header("Content-Type", content_type)
+                    .header("Cache-Control", "max-age=86400")
+                    .body(bytes)
+                    .unwrap(),
+                None => tauri::http::Response::builder()
+                    .status(404)
+                    .body(Vec::new())
+                    .unwrap(),
+            }
+        })
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .manage(AppState {
-            parser: RongyokParser::new(),
+            parser,
+            site_rules,
+            posters,
+            series_cache: SeriesCache::new(),
+            subscriptions,
+            session,
+            proxy: Mutex::new(proxy),
             downloader: Mutex::new(None),
-            current_series: Mutex::new(None),
+            loaded_series: LoadedSeries::new(),
             download_states: Mutex::new(HashMap::new()),
         })
+        .setup(|app| {
+            // Hot-reload the site rules whenever the file is edited
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(RULES_POLL_INTERVAL).await;
+                    let state = handle.state::<AppState>();
+                    if state.site_rules.changed() {
+                        let report = reload_site_rules_into(&state);
+                        let _ = handle.emit("site-rules-reloaded", &report);
+                    }
+                }
+            });
+
+            // Look for new episodes of subscribed series
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(SUBSCRIPTION_POLL_INTERVAL).await;
+                    let due = handle.state::<AppState>().subscriptions.due(series_cache::now());
+                    for subscription in due {
+                        let title = subscription.title.clone();
+                        if let Err(e) = check_subscription(&handle, subscription).await {
+                            let _ = handle.emit("log-info", format!("Checking {} failed: {}", title, e));
+                        }
+                    }
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             fetch_series,
+            refresh_cached_series,
+            list_cached_series,
+            evict_cached_series,
+            list_loaded_series,
+            import_series_list,
+            list_subscriptions,
+            subscribe_series,
+            unsubscribe_series,
+            check_subscriptions_now,
+            unload_series,
+            search_series,
+            fetch_listing,
             check_ffmpeg_available,
             start_download,
+            probe_download,
             pause_download,
             resume_download,
             cancel_download,
@@ -463,6 +1295,14 @@ pub fn run() {
             list_files,
             delete_files,
             play_file,
+            get_cookies,
+            import_cookies,
+            clear_cookies,
+            get_proxy_settings,
+            set_proxy_settings,
+            test_proxy,
+            validate_site_rules,
+            reload_site_rules,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,135 @@
+//! Series opened during this run, kept in memory by site and series id.
+//!
+//! Downloads look up their own series here rather than whichever one was fetched last, so a
+//! second series can be opened (or downloaded) while the first one's batch is still running.
+
+use crate::parser::SeriesInfo;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A loaded series as listed to the UI
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedSeriesSummary {
+    pub series_id: i32,
+    pub site: String,
+    pub source_url: String,
+    pub title: String,
+    pub poster_url: Option<String>,
+    pub total_episodes: i32,
+}
+
+impl From<&SeriesInfo> for LoadedSeriesSummary {
+    fn from(series: &SeriesInfo) -> Self {
+        Self {
+            series_id: series.series_id,
+            site: series.site.clone(),
+            source_url: series.source_url.clone(),
+            title: series.title.clone(),
+            poster_url: series.poster_url.clone(),
+            total_episodes: series.total_episodes,
+        }
+    }
+}
+
+struct Loaded {
+    series: SeriesInfo,
+    /// Load order, so lookups without a site prefer the latest
+    seq: u64,
+}
+
+#[derive(Default)]
+pub struct LoadedSeries {
+    entries: Mutex<HashMap<(String, i32), Loaded>>,
+    next_seq: Mutex<u64>,
+}
+
+impl LoadedSeries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a series, replacing an earlier load of the same one (e.g. after refreshing its URLs)
+    pub fn insert(&self, series: SeriesInfo) {
+        let seq = {
+            let mut next = self.next_seq.lock().unwrap();
+            *next += 1;
+            *next
+        };
+        let key = (series.site.clone(), series.series_id);
+        self.entries.lock().unwrap().insert(key, Loaded { series, seq });
+    }
+
+    /// A loaded series by id. Without a site, the most recently loaded series with that id.
+    pub fn get(&self, site: Option<&str>, series_id: i32) -> Option<SeriesInfo> {
+        let entries = self.entries.lock().unwrap();
+        match site {
+            Some(site) => entries.get(&(site.to_string(), series_id)).map(|l| l.series.clone()),
+            None => entries
+                .values()
+                .filter(|l| l.series.series_id == series_id)
+                .max_by_key(|l| l.seq)
+                .map(|l| l.series.clone()),
+        }
+    }
+
+    /// Forget a series; returns whether it was loaded
+    pub fn remove(&self, site: &str, series_id: i32) -> bool {
+        self.entries.lock().unwrap().remove(&(site.to_string(), series_id)).is_some()
+    }
+
+    /// Every loaded series, most recently loaded first
+    pub fn list(&self) -> Vec<LoadedSeriesSummary> {
+        let entries = self.entries.lock().unwrap();
+        let mut loaded: Vec<&Loaded> = entries.values().collect();
+        loaded.sort_by_key(|l| std::cmp::Reverse(l.seq));
+        loaded.into_iter().map(|l| LoadedSeriesSummary::from(&l.series)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(site: &str, series_id: i32, title: &str) -> SeriesInfo {
+        SeriesInfo {
+            series_id,
+            site: site.to_string(),
+            source_url: format!("https://{}.example.com/series/{}", site, series_id),
+            title: title.to_string(),
+            description: None,
+            genres: Vec::new(),
+            alternate_titles: Vec::new(),
+            total_episodes: 10,
+            poster_url: None,
+            episode_urls: HashMap::new(),
+            episodes: Vec::new(),
+            resolution_report: Vec::new(),
+            availability: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_loaded_series_by_id() {
+        let loaded = LoadedSeries::new();
+        loaded.insert(series("rongyok", 7, "First"));
+        loaded.insert(series("thongyok", 7, "Mirror"));
+        loaded.insert(series("rongyok", 8, "Second"));
+
+        // Loading another series leaves the earlier ones in place
+        assert_eq!(loaded.get(Some("rongyok"), 7).unwrap().title, "First");
+        assert_eq!(loaded.get(None, 7).unwrap().title, "Mirror");
+        assert!(loaded.get(None, 9).is_none());
+
+        // Reloading replaces the entry and moves it to the front
+        loaded.insert(series("rongyok", 7, "First (refreshed)"));
+        let titles: Vec<_> = loaded.list().into_iter().map(|s| s.title).collect();
+        assert_eq!(titles, vec!["First (refreshed)", "Second", "Mirror"]);
+        assert_eq!(loaded.get(None, 7).unwrap().title, "First (refreshed)");
+
+        assert!(loaded.remove("thongyok", 7));
+        assert!(!loaded.remove("thongyok", 7));
+        assert_eq!(loaded.list().len(), 2);
+    }
+}
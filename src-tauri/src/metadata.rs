@@ -0,0 +1,308 @@
+//! Descriptive metadata scraped from series and episode pages: series description,
+//! genres and alternate titles, plus per-episode titles, thumbnails and durations.
+
+use crate::dash::parse_duration;
+use crate::parser::EpisodeInfo;
+use regex::Regex;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Series-level details beyond the title and poster
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SeriesMetadata {
+    pub description: Option<String>,
+    pub genres: Vec<String>,
+    pub alternate_titles: Vec<String>,
+}
+
+/// Collect description, genres and alternate titles from meta tags, JSON-LD and inline JSON
+pub fn extract_series_metadata(document: &Html, html: &str, title: &str) -> SeriesMetadata {
+    let mut meta = SeriesMetadata::default();
+
+    // JSON-LD (schema.org TVSeries / Movie)
+    let ld_selector = Selector::parse("script[type='application/ld+json']").unwrap();
+    for script in document.select(&ld_selector) {
+        if let Ok(value) = serde_json::from_str::<Value>(&script.text().collect::<String>()) {
+            collect_json_ld(&value, &mut meta);
+        }
+    }
+
+    // Meta tags
+    if meta.description.is_none() {
+        meta.description = meta_content(document, "meta[property='og:description']")
+            .or_else(|| meta_content(document, "meta[name='description']"));
+    }
+    for selector in ["meta[property='video:tag']", "meta[property='article:tag']"] {
+        let selector = Selector::parse(selector).unwrap();
+        for el in document.select(&selector) {
+            if let Some(tag) = el.value().attr("content") {
+                push_unique(&mut meta.genres, tag);
+            }
+        }
+    }
+    if let Some(og_title) = meta_content(document, "meta[property='og:title']") {
+        push_unique(&mut meta.alternate_titles, &og_title);
+    }
+
+    // Inline player/page JSON, e.g. "genres": ["Drama"], "alt_titles": ["..."]
+    let json_re = Regex::new(
+        r#""(genres?|alt_titles?|alternate_titles?|alternative_titles?|other_names?)"\s*:\s*(\[[^\[\]]*\]|"(?:[^"\\]|\\.)*")"#
+    ).unwrap();
+    for caps in json_re.captures_iter(html) {
+        let Ok(value) = serde_json::from_str::<Value>(&caps[2]) else { continue };
+        let target = if caps[1].starts_with("genre") {
+            &mut meta.genres
+        } else {
+            &mut meta.alternate_titles
+        };
+        for item in string_list(&value) {
+            push_unique(target, &item);
+        }
+    }
+
+    meta.alternate_titles.retain(|t| t != title);
+    meta
+}
+
+/// Episode titles, thumbnails, durations and page links listed on a series page.
+/// Links come from anchors such as "ตอนที่ 3"; inline JSON episode objects fill the gaps.
+pub fn extract_episode_list(document: &Html, html: &str, page_url: &str) -> HashMap<i32, EpisodeInfo> {
+    let mut episodes: HashMap<i32, EpisodeInfo> = HashMap::new();
+    let base = Url::parse(page_url).ok();
+
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let img_selector = Selector::parse("img").unwrap();
+    let title_selector = Selector::parse("[class*='title']").unwrap();
+    let duration_selector = Selector::parse("[class*='duration']").unwrap();
+    let ep_re = Regex::new(r"(?i)(?:ตอนที่|EP\.?|Episode)\s*(\d+)").unwrap();
+    let clock_re = Regex::new(r"\b\d{1,2}:\d{2}(?::\d{2})?\b").unwrap();
+
+    for link in document.select(&link_selector) {
+        let text = collapse_whitespace(&link.text().collect::<String>());
+        let label = link.value().attr("title").unwrap_or_default();
+        let Some(ep) = [text.as_str(), label]
+            .iter()
+            .find_map(|t| ep_re.captures(t)?[1].parse::<i32>().ok())
+        else {
+            continue;
+        };
+        if episodes.contains_key(&ep) {
+            continue;
+        }
+
+        let mut info = EpisodeInfo::new(ep);
+        info.source_page_url = link.value().attr("href").and_then(|href| join_url(base.as_ref(), href));
+        info.title = link
+            .value()
+            .attr("title")
+            .map(collapse_whitespace)
+            .or_else(|| link.select(&title_selector).next().map(|el| element_text(&el)))
+            .or_else(|| Some(clock_re.replace_all(&text, "").trim().to_string()))
+            .filter(|t| !t.is_empty());
+        info.thumbnail = link
+            .select(&img_selector)
+            .next()
+            .and_then(|img| img.value().attr("data-src").or_else(|| img.value().attr("src")))
+            .and_then(|src| join_url(base.as_ref(), src));
+        info.duration = link
+            .select(&duration_selector)
+            .next()
+            .map(|el| element_text(&el))
+            .or_else(|| clock_re.find(&text).map(|m| m.as_str().to_string()))
+            .and_then(|d| parse_duration_text(&d));
+
+        episodes.insert(ep, info);
+    }
+
+    // Flat JSON objects such as {"ep": 3, "title": "...", "thumbnail": "...", "duration": 2712}
+    let object_re = Regex::new(r"\{[^{}]*\}").unwrap();
+    for m in object_re.find_iter(html) {
+        let Ok(Value::Object(object)) = serde_json::from_str::<Value>(m.as_str()) else { continue };
+        let Some(ep) = ["ep", "episode", "episode_number", "episodeNumber", "number"]
+            .iter()
+            .find_map(|key| json_number(object.get(*key)?))
+        else {
+            continue;
+        };
+
+        let field = |keys: &[&str]| keys.iter().find_map(|key| object.get(*key)?.as_str().map(str::to_string));
+        let info = episodes.entry(ep).or_insert_with(|| EpisodeInfo::new(ep));
+        if info.title.is_none() {
+            info.title = field(&["title", "name"]);
+        }
+        if info.thumbnail.is_none() {
+            info.thumbnail = field(&["thumbnail", "thumb", "image", "poster"])
+                .and_then(|src| join_url(base.as_ref(), &src));
+        }
+        if info.duration.is_none() {
+            info.duration = object.get("duration").and_then(|d| match d {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => parse_duration_text(s),
+                _ => None,
+            });
+        }
+        if info.source_page_url.is_none() {
+            info.source_page_url = field(&["url", "page_url", "link"])
+                .and_then(|href| join_url(base.as_ref(), &href));
+        }
+    }
+
+    episodes
+}
+
+/// Title, thumbnail and duration from an individual episode page
+pub fn extract_episode_page(html: &str, episode: i32, page_url: &str) -> EpisodeInfo {
+    let document = Html::parse_document(html);
+    let base = Url::parse(page_url).ok();
+    let mut info = EpisodeInfo::new(episode);
+
+    info.source_page_url = Some(page_url.to_string());
+    info.title = meta_content(&document, "meta[property='og:title']").or_else(|| {
+        let selector = Selector::parse("title").unwrap();
+        document.select(&selector).next().map(|el| element_text(&el))
+    });
+    info.thumbnail = meta_content(&document, "meta[property='og:image']")
+        .or_else(|| {
+            let selector = Selector::parse("video[poster]").unwrap();
+            document.select(&selector).next()?.value().attr("poster").map(str::to_string)
+        })
+        .and_then(|src| join_url(base.as_ref(), &src));
+    info.duration = meta_content(&document, "meta[property='video:duration']")
+        .or_else(|| meta_content(&document, "meta[itemprop='duration']"))
+        .and_then(|d| parse_duration_text(&d));
+
+    info
+}
+
+/// Parse "2712", "45:12", "1:02:03" or "PT45M12S" into seconds
+pub fn parse_duration_text(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if value.starts_with('P') {
+        return parse_duration(value);
+    }
+    if !value.contains(':') {
+        return value.parse().ok();
+    }
+
+    value.split(':').try_fold(0.0, |total, part| Some(total * 60.0 + part.trim().parse::<f64>().ok()?))
+}
+
+fn collect_json_ld(value: &Value, meta: &mut SeriesMetadata) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| collect_json_ld(item, meta)),
+        Value::Object(object) => {
+            if let Some(graph) = object.get("@graph") {
+                collect_json_ld(graph, meta);
+            }
+            if meta.description.is_none() {
+                meta.description = object
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .map(collapse_whitespace)
+                    .filter(|d| !d.is_empty());
+            }
+            for genre in object.get("genre").map(string_list).unwrap_or_default() {
+                push_unique(&mut meta.genres, &genre);
+            }
+            for key in ["alternateName", "alternativeHeadline"] {
+                for name in object.get(key).map(string_list).unwrap_or_default() {
+                    push_unique(&mut meta.alternate_titles, &name);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn meta_content(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).unwrap();
+    document
+        .select(&selector)
+        .next()
+        .and_then(|el| el.value().attr("content"))
+        .map(collapse_whitespace)
+        .filter(|s| !s.is_empty())
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn json_number(value: &Value) -> Option<i32> {
+    match value {
+        Value::Number(n) => n.as_i64().and_then(|n| i32::try_from(n).ok()),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, item: &str) {
+    let item = collapse_whitespace(item);
+    if !item.is_empty() && !list.contains(&item) {
+        list.push(item);
+    }
+}
+
+pub(crate) fn join_url(base: Option<&Url>, href: &str) -> Option<String> {
+    match base {
+        Some(base) => base.join(href).ok().map(|u| u.to_string()),
+        None => Some(href.to_string()),
+    }
+}
+
+fn element_text(el: &ElementRef) -> String {
+    collapse_whitespace(&el.text().collect::<String>())
+}
+
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixture;
+
+    #[test]
+    fn test_extract_series_metadata_fixture() {
+        let html = fixture("rongyok_series.html");
+        let document = Html::parse_document(&html);
+        let meta = extract_series_metadata(&document, &html, "รักนี้ต้องห้าม");
+
+        assert_eq!(meta.description.as_deref(), Some("Two families, one forbidden love."));
+        assert_eq!(meta.genres, vec!["Drama", "Romance", "ซีรีย์จีน"]);
+        assert_eq!(meta.alternate_titles, vec!["Forbidden Love", "禁忌之恋"]);
+    }
+
+    #[test]
+    fn test_extract_episode_list_fixture() {
+        let html = fixture("rongyok_series.html");
+        let document = Html::parse_document(&html);
+        let episodes = extract_episode_list(&document, &html, "https://rongyok.com/watch/?series_id=1004");
+
+        assert_eq!(episodes.len(), 4);
+        let first = &episodes[&1];
+        assert_eq!(first.title.as_deref(), Some("ตอนที่ 1"));
+        assert_eq!(first.source_page_url.as_deref(), Some("https://rongyok.com/watch/?series_id=1004&ep=1"));
+        assert_eq!(first.thumbnail.as_deref(), Some("https://rongyok.com/images/1004/ep1.jpg"));
+        assert_eq!(first.duration, Some(2712.0));
+        // Thumbnail and duration from the inline JSON episode list
+        assert_eq!(episodes[&2].thumbnail.as_deref(), Some("https://rongyok.com/images/1004/ep2.jpg"));
+        assert_eq!(episodes[&4].duration, Some(2580.0));
+    }
+
+    #[test]
+    fn test_parse_duration_text() {
+        assert_eq!(parse_duration_text("45:12"), Some(2712.0));
+        assert_eq!(parse_duration_text("1:02:03"), Some(3723.0));
+        assert_eq!(parse_duration_text("PT43M"), Some(2580.0));
+        assert_eq!(parse_duration_text("2712"), Some(2712.0));
+        assert_eq!(parse_duration_text("soon"), None);
+    }
+}
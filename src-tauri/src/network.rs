@@ -0,0 +1,147 @@
+//! HTTP clients for all network traffic: the shared cookie jar plus the user's proxy settings.
+//!
+//! Every `reqwest::Client` the app uses (parser, downloader, poster cache) is built by
+//! `build_client`, so a proxy configured here covers page fetches, video downloads and posters.
+
+use crate::error::{ProxyError, StorageError};
+use crate::session::Session;
+use reqwest::{Client, NoProxy, Proxy, Url};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36";
+
+/// Proxy for all app traffic, persisted in the app data directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxySettings {
+    pub enabled: bool,
+    /// `http://`, `https://`, `socks5://` or `socks5h://` (DNS through the proxy) with host and port
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Hosts reached directly: `example.com` (and subdomains), `.example.com`, IPs or CIDR ranges
+    pub bypass: Vec<String>,
+}
+
+/// Outcome of a request sent through a proxy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyTestResult {
+    pub reachable: bool,
+    /// HTTP status of the test request, if one came back
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+impl ProxySettings {
+    fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("com.rongyok.downloader")
+            .join("proxy.json")
+    }
+
+    /// Saved settings, or no proxy if none were saved
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::default_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        let path = Self::default_path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| StorageError::write(dir, e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| StorageError::write(&path, e.into()))?;
+        std::fs::write(&path, json).map_err(|e| StorageError::write(&path, e))
+    }
+
+    /// The proxy to install on a client, or None when disabled
+    pub fn to_proxy(&self) -> Result<Option<Proxy>, ProxyError> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let mut url = Url::parse(self.url.trim()).map_err(|e| ProxyError::InvalidUrl(e.to_string()))?;
+        if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+            return Err(ProxyError::UnsupportedScheme(url.scheme().to_string()));
+        }
+        if url.host_str().is_none() {
+            return Err(ProxyError::NoHost);
+        }
+
+        // Credentials in the URL work for both HTTP (Proxy-Authorization) and SOCKS5
+        if let Some(username) = self.username.as_deref().filter(|u| !u.is_empty()) {
+            url.set_username(username).map_err(|_| ProxyError::Credentials)?;
+            url.set_password(self.password.as_deref()).map_err(|_| ProxyError::Credentials)?;
+        }
+
+        let proxy = Proxy::all(url.as_str()).map_err(|e| ProxyError::InvalidUrl(e.to_string()))?;
+        let bypass: Vec<&str> = self.bypass.iter().map(|h| h.trim()).filter(|h| !h.is_empty()).collect();
+        Ok(Some(proxy.no_proxy(NoProxy::from_string(&bypass.join(",")))))
+    }
+}
+
+/// Client with the shared cookie jar, routed through the proxy when one is enabled.
+/// Without a proxy, the system proxy environment variables still apply.
+pub fn build_client(session: &Session, proxy: &ProxySettings) -> Result<Client, ProxyError> {
+    let mut builder = session.apply(Client::builder()).user_agent(USER_AGENT);
+    if let Some(proxy) = proxy.to_proxy()? {
+        builder = builder.proxy(proxy);
+    }
+    builder.build().map_err(|e| ProxyError::Client(e.to_string()))
+}
+
+/// Send one request to `target` with the given (possibly unsaved) settings.
+/// Invalid settings are an error; an unreachable proxy or site is reported in the result.
+pub async fn test_proxy(proxy: &ProxySettings, target: &str) -> Result<ProxyTestResult, ProxyError> {
+    let client = build_client(&Session::default(), proxy)?;
+    let started = Instant::now();
+    let response = client.head(target).timeout(Duration::from_secs(15)).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match response {
+        Ok(response) => ProxyTestResult {
+            reachable: true,
+            status: Some(response.status().as_u16()),
+            latency_ms,
+            error: None,
+        },
+        Err(e) => ProxyTestResult {
+            reachable: false,
+            status: e.status().map(|s| s.as_u16()),
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FixtureServer;
+
+    #[tokio::test]
+    async fn test_proxy_routes_requests() {
+        // A forward proxy sees the absolute URL as the request path
+        let server = FixtureServer::bind();
+        let proxy_url = server.bytes("http://rongyok.invalid/", "text/html", b"ok").start();
+
+        let mut settings = ProxySettings { enabled: true, url: proxy_url, ..Default::default() };
+        let result = test_proxy(&settings, "http://rongyok.invalid/").await.unwrap();
+        assert!(result.reachable);
+        assert_eq!(result.status, Some(200));
+
+        // Bypassed hosts go direct, and .invalid never resolves
+        settings.bypass = vec!["rongyok.invalid".to_string()];
+        assert!(!test_proxy(&settings, "http://rongyok.invalid/").await.unwrap().reachable);
+
+        settings.url = "ftp://proxy.example.com:21".to_string();
+        assert!(build_client(&Session::default(), &settings).is_err());
+    }
+}
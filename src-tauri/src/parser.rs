@@ -1,91 +1,468 @@
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use crate::catalog::{SeriesPage, SeriesSummary};
+use crate::error::ParserError;
+use crate::metadata::{extract_episode_list, extract_episode_page, extract_series_metadata};
+use crate::network::{build_client, ProxySettings};
+use crate::quality::{describe_source, source_context, QualityPreference, VideoSource};
+use crate::session::Session;
+use crate::sites::{ExtractorRegistry, SiteExtractor};
+use crate::structured::extract_structured_sources;
+use crate::subtitles::{extract_subtitle_tracks, SubtitleTrack};
+use futures_util::{future, stream, StreamExt};
 use regex::Regex;
-use reqwest::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode, Url};
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, RwLock};
+use tokio::time::{sleep, Duration};
+
+/// Number of episode pages fetched in parallel
+const EPISODE_PAGE_CONCURRENCY: usize = 6;
+/// Attempts per episode page before it is reported as failed
+const EPISODE_PAGE_ATTEMPTS: u32 = 3;
+/// How many levels of nested player iframes are followed from an episode page
+const MAX_IFRAME_DEPTH: usize = 3;
+
+static SERIES_ID_QUERY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"series_id=(\d+)").unwrap());
+static SERIES_PATH_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/series/(\d+)").unwrap());
+static WATCH_PATH_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/watch/(\d+)").unwrap());
+static WATCH_EPISODE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/watch/\d+/(\d+)").unwrap());
+static EPISODE_QUERY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[?&](?:ep|episode)=(\d+)").unwrap());
+static TITLE_EPISODE_SUFFIX_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*-\s*ตอนที่\s*\d+.*$").unwrap());
+static TITLE_SITE_SUFFIX_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*(Thongyok|Rongyok).*$").unwrap());
+static EPISODE_COUNT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+)\s*ตอน").unwrap());
+static EPISODE_MENTION_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"ตอนที่\s*(\d+)").unwrap());
+
+/// Discord CDN video on an episode page
+static DISCORD_VIDEO_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"https?://cdn\.discordapp\.com/attachments/\d+/\d+/\d+\.mp4\?[^"'\s<>]+"#).unwrap()
+});
+/// HLS playlist or DASH manifest (may be JSON-escaped inside player config)
+static MANIFEST_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"https?:(?:\\/\\/|//)[^"'\s<>]+?\.(?:m3u8|mpd)(?:\?[^"'\s<>]*)?"#).unwrap()
+});
+/// Episode number from URL, e.g. /1.mp4, /EP01.mp4, /12/index.m3u8, /12/manifest.mpd
+static EPISODE_FILE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"[/\\](?:EP)?(\d+)(?:[/\\](?:index|playlist|master|manifest|stream))?\.(?:mp4|m3u8|mpd)"#).unwrap()
+});
+/// Discord CDN with numeric filename (e.g., 1.mp4, 2.mp4)
+static DISCORD_NUMBERED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"https?:(?:\\/\\/|//)cdn\.discordapp\.com(?:\\/|/)attachments(?:\\/|/)(\d+)(?:\\/|/)(\d+)(?:\\/|/)(\d+)\.mp4\?[^"'<>\s\\]+"#,
+    )
+    .unwrap()
+});
+/// Discord CDN with EP prefix (e.g., EP01.mp4, EP02.mp4)
+static DISCORD_EP_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)https?:(?:\\/\\/|//)cdn\.discordapp\.com(?:\\/|/)attachments(?:\\/|/)(\d+)(?:\\/|/)(\d+)(?:\\/|/)EP(\d+)\.mp4\?[^"'<>\s\\]+"#,
+    )
+    .unwrap()
+});
+/// Generic video_url in JSON (progressive MP4, HLS playlist or DASH manifest)
+static VIDEO_URL_JSON_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""video_url"\s*:\s*"(https?:[^"]+\.(?:mp4|m3u8|mpd)[^"]*)""#).unwrap()
+});
+/// HLS/DASH manifests linked directly in the page (e.g. <source src="...">)
+static PAGE_MANIFEST_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)https?:(?:\\/\\/|//)[^"'<>\s]+?\.(?:m3u8|mpd)(?:\?[^"'<>\s\\]*)?"#).unwrap()
+});
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SeriesInfo {
     pub series_id: i32,
+    pub site: String,
+    /// Series page the info was scraped from, used to re-resolve expired video URLs
+    pub source_url: String,
     pub title: String,
+    pub description: Option<String>,
+    pub genres: Vec<String>,
+    pub alternate_titles: Vec<String>,
     pub total_episodes: i32,
+    /// Poster image URL as found on the page; the app swaps in a cached `poster://` URL
     pub poster_url: Option<String>,
     pub episode_urls: HashMap<i32, String>,
+    /// Per-episode details, sorted by episode number
+    pub episodes: Vec<EpisodeInfo>,
+    pub resolution_report: Vec<EpisodeResolution>,
+    /// Whether each episode from 1 to `total_episodes` can be downloaded
+    #[serde(default)]
+    pub availability: BTreeMap<i32, Availability>,
+}
+
+impl SeriesInfo {
+    /// An episode's availability; series cached before availability was tracked fall back to
+    /// whether the episode has a URL
+    pub fn availability_of(&self, episode: i32) -> Availability {
+        match self.availability.get(&episode) {
+            Some(availability) => availability.clone(),
+            None if self.episode_urls.contains_key(&episode) => Availability::Available,
+            None => Availability::Missing,
+        }
+    }
+
+    /// Episodes that can be downloaded, in order
+    pub fn available_episodes(&self) -> Vec<i32> {
+        (1..=self.total_episodes)
+            .filter(|ep| self.availability_of(*ep) == Availability::Available)
+            .collect()
+    }
+
+    /// Check a download selection up front; the episodes that can't be downloaded, if any
+    pub fn check_selection(&self, episodes: &[i32]) -> Result<(), BTreeMap<i32, Availability>> {
+        let unavailable: BTreeMap<i32, Availability> = episodes
+            .iter()
+            .map(|ep| (*ep, self.availability_of(*ep)))
+            .filter(|(_, availability)| *availability != Availability::Available)
+            .collect();
+        if unavailable.is_empty() {
+            Ok(())
+        } else {
+            Err(unavailable)
+        }
+    }
+
+    /// Video URL for an episode, choosing among its candidate sources by preference
+    pub fn video_url_for(&self, episode: i32, quality: QualityPreference) -> Option<String> {
+        self.episodes
+            .iter()
+            .find(|e| e.episode_number == episode)
+            .and_then(|e| quality.select(&e.sources))
+            .map(|s| s.url.clone())
+            .or_else(|| self.episode_urls.get(&episode).cloned())
+    }
+}
+
+/// Details for a single episode; fields are `None` when the site doesn't provide them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeInfo {
+    pub episode_number: i32,
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    /// Duration in seconds
+    pub duration: Option<f64>,
+    /// Page the episode is watched on
+    pub source_page_url: Option<String>,
+    pub video_url: Option<String>,
+    /// Every candidate source found, with whatever quality hints the page gave
+    pub sources: Vec<VideoSource>,
+    pub subtitles: Vec<SubtitleTrack>,
+}
+
+impl EpisodeInfo {
+    pub fn new(episode_number: i32) -> Self {
+        Self { episode_number, ..Default::default() }
+    }
+
+    /// Fill in fields from `other`, preferring its values where present
+    fn merge(&mut self, other: EpisodeInfo) {
+        self.title = other.title.or(self.title.take());
+        self.thumbnail = other.thumbnail.or(self.thumbnail.take());
+        self.duration = other.duration.or(self.duration);
+        self.source_page_url = other.source_page_url.or(self.source_page_url.take());
+        self.video_url = other.video_url.or(self.video_url.take());
+        if !other.sources.is_empty() {
+            self.sources = other.sources;
+        }
+        if !other.subtitles.is_empty() {
+            self.subtitles = other.subtitles;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolutionStatus {
+    Resolved,
+    Failed,
+}
+
+/// Whether an episode can be downloaded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum Availability {
+    Available,
+    /// The site doesn't list a video for the episode
+    Missing,
+    /// The episode has a page but its video couldn't be resolved; a refresh may fix it
+    Unresolved { reason: String },
+    /// Same video URL as an earlier episode, usually a copy-paste mistake on the site
+    Duplicate { of: i32 },
+}
+
+/// Availability of episodes 1 to `total_episodes` (and any found beyond it)
+fn episode_availability(
+    total_episodes: i32,
+    episode_urls: &HashMap<i32, String>,
+    episodes: &[EpisodeInfo],
+    report: &[EpisodeResolution],
+) -> BTreeMap<i32, Availability> {
+    let last = episode_urls.keys().copied().max().unwrap_or(0).max(total_episodes);
+    let mut first_with_url: HashMap<&str, i32> = HashMap::new();
+
+    (1..=last)
+        .map(|ep| {
+            let availability = if let Some(url) = episode_urls.get(&ep) {
+                match first_with_url.get(url.as_str()) {
+                    Some(first) => Availability::Duplicate { of: *first },
+                    None => {
+                        first_with_url.insert(url, ep);
+                        Availability::Available
+                    }
+                }
+            } else if episodes.iter().any(|e| e.episode_number == ep && e.source_page_url.is_some()) {
+                let reason = report
+                    .iter()
+                    .find(|r| r.episode == ep)
+                    .and_then(|r| r.reason.clone())
+                    .unwrap_or_else(|| "No video URL found on episode page".to_string());
+                Availability::Unresolved { reason }
+            } else {
+                Availability::Missing
+            };
+            (ep, availability)
+        })
+        .collect()
+}
+
+/// Outcome of resolving a single episode's video URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeResolution {
+    pub episode: i32,
+    pub status: ResolutionStatus,
+    pub reason: Option<String>,
+    pub attempts: u32,
+}
+
+impl EpisodeResolution {
+    fn resolved(episode: i32, attempts: u32) -> Self {
+        Self { episode, status: ResolutionStatus::Resolved, reason: None, attempts }
+    }
+
+    fn failed(episode: i32, attempts: u32, reason: &str) -> Self {
+        Self { episode, status: ResolutionStatus::Failed, reason: Some(reason.to_string()), attempts }
+    }
+}
+
+/// What a pasted link points at: a series on one of the sites, and possibly one of its episodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesTarget {
+    pub site: String,
+    pub series_id: i32,
+    /// Set for episode links such as thongyok's /watch/{id}/{ep}
+    pub episode: Option<i32>,
+    /// Series page on the site's main domain
+    pub series_url: String,
+}
+
+/// HTTP validators of a fetched page, sent back to ask whether it changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl PageValidators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Result of a conditional page fetch
+#[derive(Debug, Clone)]
+pub enum PageFetch {
+    Modified { html: String, validators: PageValidators },
+    /// The server confirmed the cached copy is current (HTTP 304)
+    NotModified,
+}
+
+/// Episode video URLs plus how each episode was (or wasn't) resolved
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedEpisodes {
+    pub episode_urls: HashMap<i32, String>,
+    pub report: Vec<EpisodeResolution>,
+    /// Per-episode sources and any details scraped from individual episode pages
+    pub episodes: HashMap<i32, EpisodeInfo>,
+}
+
+impl ResolvedEpisodes {
+    /// Build a report for sources extracted from a single page: episodes up to
+    /// `total_episodes` that weren't found are reported as failed.
+    pub fn from_page(episode_sources: HashMap<i32, Vec<VideoSource>>, total_episodes: i32) -> Self {
+        let mut episode_urls = HashMap::new();
+        let mut episodes = HashMap::new();
+        for (ep, sources) in episode_sources {
+            let video_url = QualityPreference::default().select(&sources).map(|s| s.url.clone());
+            if let Some(ref url) = video_url {
+                episode_urls.insert(ep, url.clone());
+            }
+            episodes.insert(ep, EpisodeInfo { video_url, sources, ..EpisodeInfo::new(ep) });
+        }
+
+        let last = episode_urls.keys().copied().max().unwrap_or(0).max(total_episodes);
+        let report = (1..=last)
+            .map(|ep| {
+                if episode_urls.contains_key(&ep) {
+                    EpisodeResolution::resolved(ep, 1)
+                } else {
+                    EpisodeResolution::failed(ep, 1, "No video URL found in series page")
+                }
+            })
+            .collect();
+
+        Self { episode_urls, report, episodes }
+    }
 }
 
 pub struct RongyokParser {
-    client: Client,
+    client: RwLock<Client>,
+    registry: RwLock<ExtractorRegistry>,
 }
 
 impl RongyokParser {
     pub fn new() -> Self {
-        let client = Client::builder()
-            .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36")
-            .build()
-            .expect("Failed to create HTTP client");
+        Self::with_registry(ExtractorRegistry::default())
+    }
 
-        Self { client }
+    pub fn with_registry(registry: ExtractorRegistry) -> Self {
+        let client = build_client(&Session::default(), &ProxySettings::default()).expect("Failed to create HTTP client");
+        Self { client: RwLock::new(client), registry: RwLock::new(registry) }
+    }
+
+    /// Use another HTTP client from now on, e.g. one with the user's cookies or proxy
+    pub fn set_client(&self, client: Client) {
+        *self.client.write().unwrap() = client;
+    }
+
+    /// Swap in the sites defined by the user's rules file
+    pub fn set_rule_sites(&self, sites: Vec<Arc<dyn SiteExtractor>>) {
+        self.registry.write().unwrap().set_rule_sites(sites);
+    }
+
+    /// Pick the site extractor for a URL (falls back to the default site)
+    pub fn site_for(&self, url: Option<&str>) -> Result<Arc<dyn SiteExtractor>, ParserError> {
+        self.registry
+            .read()
+            .unwrap()
+            .resolve(url)
+            .ok_or_else(|| ParserError::UnsupportedSite(url.unwrap_or_default().to_string()))
     }
 
     /// Extract series_id from URL
     pub fn parse_series_url(url: &str) -> Option<i32> {
         // Format 1: ?series_id=XXX
-        let re1 = Regex::new(r"series_id=(\d+)").ok()?;
-        if let Some(caps) = re1.captures(url) {
+        if let Some(caps) = SERIES_ID_QUERY_RE.captures(url) {
             return caps.get(1)?.as_str().parse().ok();
         }
 
         // Format 2: /series/XXX/
-        let re2 = Regex::new(r"/series/(\d+)").ok()?;
-        if let Some(caps) = re2.captures(url) {
+        if let Some(caps) = SERIES_PATH_RE.captures(url) {
+            return caps.get(1)?.as_str().parse().ok();
+        }
+
+        // Format 3: episode page /watch/XXX/EP
+        if let Some(caps) = WATCH_PATH_RE.captures(url) {
             return caps.get(1)?.as_str().parse().ok();
         }
 
         None
     }
 
-    /// Fetch series information
-    pub async fn get_series_info(&self, series_id: i32, original_url: Option<&str>) -> Result<SeriesInfo, String> {
-        let (url, domain) = Self::construct_series_url(series_id, original_url);
-        let is_thongyok = url.contains("thongyok.com");
+    /// Extract the episode number from an episode link, if it is one
+    pub fn parse_episode_url(url: &str) -> Option<i32> {
+        // Format 1: /watch/XXX/EP
+        if let Some(caps) = WATCH_EPISODE_RE.captures(url) {
+            return caps.get(1)?.as_str().parse().ok();
+        }
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "text/html,application/xhtml+xml")
-            .header("Accept-Language", "th,en-US;q=0.9,en;q=0.8")
-            .header("Referer", &domain)
+        // Format 2: ?series_id=XXX&ep=EP
+        if let Some(caps) = EPISODE_QUERY_RE.captures(url) {
+            return caps.get(1)?.as_str().parse().ok();
+        }
+
+        None
+    }
+
+    /// Parse a link to a series or episode without touching the network
+    pub fn parse_target(&self, url: &str) -> Option<SeriesTarget> {
+        let site = self.registry.read().unwrap().find(url)?;
+        let series_id = site.parse_series_id(url)?;
+        Some(SeriesTarget {
+            site: site.name().to_string(),
+            series_id,
+            episode: site.parse_episode(url),
+            series_url: site.series_url(series_id, Some(url)),
+        })
+    }
+
+    /// Work out which series (and episode) a link points at. Links on hosts no site handles
+    /// are taken to be short links: their redirects are followed and the landing URL parsed.
+    pub async fn resolve_target(&self, url: &str) -> Result<SeriesTarget, ParserError> {
+        let url = url.trim();
+        if let Some(target) = self.parse_target(url) {
+            return Ok(target);
+        }
+        if self.registry.read().unwrap().find(url).is_some() || Url::parse(url).is_err() {
+            return Err(ParserError::InvalidUrl(url.to_string()));
+        }
+
+        let client = self.client.read().unwrap().clone();
+        let response = client
+            .get(url)
             .send()
             .await
-            .map_err(|e| format!("HTTP request failed: {}", e))?;
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ParserError::from_reqwest(e, url))?;
+        let landed = response.url().to_string();
+        self.parse_target(&landed).ok_or_else(|| ParserError::InvalidUrl(url.to_string()))
+    }
 
-        let html = response
-            .text()
-            .await
-            .map_err(|e| format!("Failed to read response: {}", e))?;
+    /// Fetch series information, ignoring cache validators (the app goes through
+    /// `get_series_info_if_modified`)
+    #[cfg(test)]
+    pub async fn get_series_info(&self, series_id: i32, original_url: Option<&str>) -> Result<SeriesInfo, ParserError> {
+        // Without validators only a misbehaving server answers 304
+        self.get_series_info_if_modified(series_id, original_url, &PageValidators::default())
+            .await?
+            .map(|(series, _)| series)
+            .ok_or_else(|| ParserError::HttpStatus { status: 304, url: original_url.unwrap_or_default().to_string() })
+    }
+
+    /// Fetch series information unless the series page still matches `validators`,
+    /// returning the page's new validators with it. `None` means the cached copy is current.
+    pub async fn get_series_info_if_modified(
+        &self,
+        series_id: i32,
+        original_url: Option<&str>,
+        validators: &PageValidators,
+    ) -> Result<Option<(SeriesInfo, PageValidators)>, ParserError> {
+        let (url, site) = self.construct_series_url(series_id, original_url)?;
 
-        // Extract episode URLs from HTML (doesn't need document)
-        let mut episode_urls = self.extract_all_episode_urls(&html);
+        let (html, validators) = match site.fetch_series_page(self, &url, validators).await? {
+            PageFetch::Modified { html, validators } => (html, validators),
+            PageFetch::NotModified => return Ok(None),
+        };
 
         // Parse document in a block to ensure it's dropped before any await
-        let (title, poster_url, total_episodes_from_doc) = {
+        let (title, poster_url, total_episodes_from_doc, metadata, mut episodes) = {
             let document = Html::parse_document(&html);
 
             // Get title
             let title_selector = Selector::parse("title").unwrap();
-            let title = document
-                .select(&title_selector)
-                .next()
-                .map(|el| el.text().collect::<String>())
+            let title = site
+                .extract_title(&document)
+                .or_else(|| document.select(&title_selector).next().map(|el| el.text().collect::<String>()))
                 .unwrap_or_else(|| format!("Series {}", series_id));
 
             // Clean title - remove " - ตอนที่ X" suffix and site name
-            let title_re = Regex::new(r"\s*-\s*ตอนที่\s*\d+.*$").unwrap();
-            let title = title_re.replace(&title, "").to_string();
-            let title_re2 = Regex::new(r"\s*(Thongyok|Rongyok).*$").unwrap();
-            let title = title_re2.replace(&title, "").to_string().trim().to_string();
+            let title = TITLE_EPISODE_SUFFIX_RE.replace(&title, "").to_string();
+            let title = TITLE_SITE_SUFFIX_RE.replace(&title, "").to_string().trim().to_string();
 
             // Get poster URL
             let og_image_selector = Selector::parse("meta[property='og:image']").unwrap();
@@ -96,190 +473,371 @@ impl RongyokParser {
                 .map(|s| s.to_string());
 
             // Extract total episodes from document
-            let total_eps = self.extract_total_episodes(&document, &html);
+            let total_eps = site
+                .extract_total_episodes(&document, &html)
+                .unwrap_or_else(|| self.extract_total_episodes(&document, &html));
+
+            let metadata = extract_series_metadata(&document, &html, &title);
+            let episodes = extract_episode_list(&document, &html, &url);
 
-            (title, poster_url, total_eps)
+            (title, poster_url, total_eps, metadata, episodes)
         }; // document is dropped here
 
-        // For thongyok.com, video URLs are on individual episode pages
-        if is_thongyok && episode_urls.is_empty() {
-            episode_urls = self.fetch_thongyok_episode_urls(series_id, total_episodes_from_doc, &domain).await?;
-        }
+        // Let the site decide where the episode video URLs come from
+        let ResolvedEpisodes { episode_urls, report, episodes: page_episodes } = site
+            .resolve_episodes(self, series_id, &html, total_episodes_from_doc)
+            .await?;
 
-        let total_episodes = if episode_urls.is_empty() {
-            total_episodes_from_doc
-        } else {
-            episode_urls.keys().max().copied().unwrap_or(1)
-        };
+        // Episodes the page counts but that weren't found still count, and show up as gaps
+        let total_episodes = episode_urls.keys().copied().max().unwrap_or(0).max(total_episodes_from_doc);
 
-        // Convert poster URL to base64 data URL for Tauri v2 compatibility
-        let poster_data_url = if let Some(ref url) = poster_url {
-            self.fetch_image_as_data_url(url).await
-        } else {
-            None
-        };
+        // Subtitle tracks listed per episode on the series page
+        for (ep, track) in extract_subtitle_tracks(&html, &url) {
+            if let Some(ep) = ep {
+                episodes.entry(ep).or_insert_with(|| EpisodeInfo::new(ep)).subtitles.push(track);
+            }
+        }
+
+        // Episode pages know more about their episode than the series listing
+        for (ep, info) in page_episodes {
+            episodes.entry(ep).or_insert_with(|| EpisodeInfo::new(ep)).merge(info);
+        }
+        for ep in 1..=total_episodes {
+            episodes.entry(ep).or_insert_with(|| EpisodeInfo::new(ep));
+        }
+        for (ep, video_url) in &episode_urls {
+            episodes.entry(*ep).or_insert_with(|| EpisodeInfo::new(*ep)).video_url = Some(video_url.clone());
+        }
+        let mut episodes: Vec<EpisodeInfo> = episodes.into_values().collect();
+        episodes.sort_by_key(|e| e.episode_number);
+        let availability = episode_availability(total_episodes, &episode_urls, &episodes, &report);
 
-        Ok(SeriesInfo {
+        let series = SeriesInfo {
             series_id,
+            site: site.name().to_string(),
+            source_url: url,
             title,
+            description: metadata.description,
+            genres: metadata.genres,
+            alternate_titles: metadata.alternate_titles,
             total_episodes,
-            poster_url: poster_data_url,
+            poster_url,
             episode_urls,
-        })
+            episodes,
+            resolution_report: report,
+            availability,
+        };
+        Ok(Some((series, validators)))
     }
 
-    /// Fetch episode URLs from thongyok.com individual episode pages
-    async fn fetch_thongyok_episode_urls(&self, series_id: i32, total_episodes: i32, domain: &str) -> Result<HashMap<i32, String>, String> {
-        let mut episode_urls = HashMap::new();
+    /// Search the catalog of one site (by name) or of every registered site
+    pub async fn search_series(&self, query: &str, site: Option<&str>) -> Result<Vec<SeriesSummary>, ParserError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(ParserError::EmptyQuery);
+        }
 
-        for ep in 1..=total_episodes {
-            let watch_url = format!("https://thongyok.com/watch/{}/{}", series_id, ep);
-
-            match self.client
-                .get(&watch_url)
-                .header("Accept", "text/html,application/xhtml+xml")
-                .header("Accept-Language", "th,en-US;q=0.9,en;q=0.8")
-                .header("Referer", domain)
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    if let Ok(html) = response.text().await {
-                        // Extract video URL from episode page
-                        if let Some(video_url) = self.extract_video_url_from_page(&html) {
-                            episode_urls.insert(ep, video_url);
-                        }
-                    }
+        let sites: Vec<Arc<dyn SiteExtractor>> = {
+            let registry = self.registry.read().unwrap();
+            match site {
+                Some(name) => vec![registry.get(name).ok_or_else(|| ParserError::UnsupportedSite(name.to_string()))?],
+                None => registry.iter().collect(),
+            }
+        };
+
+        let pages = future::join_all(sites.into_iter().map(|site| async move {
+            let url = site.search_url(query);
+            let html = self.fetch_page(&url, site.base_url()).await?;
+            Ok::<_, ParserError>(site.parse_series_list(&html, &url))
+        }))
+        .await;
+
+        // Report failures only if no site returned anything
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+        for page in pages {
+            match page {
+                Ok(series) => results.extend(series),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        if results.is_empty() && !errors.is_empty() {
+            return Err(ParserError::SearchFailed(errors));
+        }
+
+        Ok(results)
+    }
+
+    /// Fetch one page of a genre, category or "latest updates" listing
+    pub async fn get_listing(&self, url: &str) -> Result<SeriesPage, ParserError> {
+        let site = self.registry.read().unwrap().find(url).ok_or_else(|| ParserError::UnsupportedSite(url.to_string()))?;
+        let html = self.fetch_page(url, site.base_url()).await?;
+        Ok(site.parse_listing_page(&html, url))
+    }
+
+    /// Follow a listing's "next" links for up to `max_pages` pages, collecting every series once.
+    /// The result's `next_page_url` is the first page that was not crawled.
+    pub async fn crawl_listing(&self, url: &str, max_pages: u32) -> Result<SeriesPage, ParserError> {
+        let mut listing = self.get_listing(url).await?;
+        let mut visited = vec![url.to_string()];
+
+        while visited.len() < max_pages as usize {
+            let Some(next) = listing.next_page_url.take().filter(|n| !visited.contains(n)) else { break };
+            let page = self.get_listing(&next).await?;
+            visited.push(next);
+
+            for series in page.series {
+                if !listing.series.iter().any(|s| s.site == series.site && s.series_id == series.series_id) {
+                    listing.series.push(series);
                 }
-                Err(_) => continue, // Skip failed requests
             }
+            listing.page = page.page;
+            listing.total_pages = listing.total_pages.max(page.total_pages);
+            listing.next_page_url = page.next_page_url;
         }
 
-        Ok(episode_urls)
+        Ok(listing)
     }
 
-    /// Fetch an image and convert it to a base64 data URL
-    async fn fetch_image_as_data_url(&self, image_url: &str) -> Option<String> {
-        let response = self.client
-            .get(image_url)
-            .header("Accept", "image/*")
+    /// Fetch an HTML page with browser-like headers
+    pub async fn fetch_page(&self, url: &str, referer: &str) -> Result<String, ParserError> {
+        match self.fetch_page_if_modified(url, referer, &PageValidators::default()).await? {
+            PageFetch::Modified { html, .. } => Ok(html),
+            PageFetch::NotModified => Err(ParserError::HttpStatus { status: 304, url: url.to_string() }),
+        }
+    }
+
+    /// Fetch an HTML page unless it still matches `validators` (If-None-Match / If-Modified-Since)
+    pub async fn fetch_page_if_modified(&self, url: &str, referer: &str, validators: &PageValidators) -> Result<PageFetch, ParserError> {
+        let client = self.client.read().unwrap().clone();
+        let mut request = client
+            .get(url)
+            .header("Accept", "text/html,application/xhtml+xml")
+            .header("Accept-Language", "th,en-US;q=0.9,en;q=0.8")
+            .header("Referer", referer);
+        if let Some(ref etag) = validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(ref modified) = validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, modified);
+        }
+
+        let response = request
             .send()
             .await
-            .ok()?;
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ParserError::from_reqwest(e, url))?;
+
+        if response.status() == StatusCode::NOT_MODIFIED && !validators.is_empty() {
+            return Ok(PageFetch::NotModified);
+        }
 
-        // Get content type
-        let content_type = response
-            .headers()
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("image/jpeg")
-            .to_string();
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let validators = PageValidators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
 
-        // Get image bytes
-        let bytes = response.bytes().await.ok()?;
+        let html = response
+            .text()
+            .await
+            .map_err(|e| ParserError::Network(e.to_string()))?;
+        Ok(PageFetch::Modified { html, validators })
+    }
 
-        // Convert to base64
-        let base64_data = BASE64.encode(&bytes);
+    /// Fetch episode URLs from individual episode pages (e.g. thongyok.com /watch/{id}/{ep}).
+    /// Pages are fetched with bounded concurrency and retried before being reported as failed.
+    pub async fn fetch_episode_page_urls<S: SiteExtractor + ?Sized>(&self, site: &S, pages: Vec<(i32, String)>) -> ResolvedEpisodes {
+        let mut results: Vec<(EpisodeResolution, EpisodeInfo)> = stream::iter(pages)
+            .map(|(ep, watch_url)| async move { self.resolve_episode_page(site, ep, &watch_url).await })
+            .buffer_unordered(EPISODE_PAGE_CONCURRENCY)
+            .collect()
+            .await;
+        results.sort_by_key(|(resolution, _)| resolution.episode);
 
-        // Return as data URL
-        Some(format!("data:{};base64,{}", content_type, base64_data))
+        let mut resolved = ResolvedEpisodes::default();
+        for (resolution, info) in results {
+            if let Some(ref url) = info.video_url {
+                resolved.episode_urls.insert(resolution.episode, url.clone());
+            }
+            resolved.episodes.insert(resolution.episode, info);
+            resolved.report.push(resolution);
+        }
+        resolved
     }
 
-    /// Extract a single video URL from an episode page
-    fn extract_video_url_from_page(&self, html: &str) -> Option<String> {
-        // Pattern: Discord CDN URL
-        let pattern = Regex::new(
-            r#"https?://cdn\.discordapp\.com/attachments/\d+/\d+/\d+\.mp4\?[^"'\s<>]+"#
-        ).ok()?;
+    /// Fetch one episode page (retrying network errors and 5xx) and extract its video URL and details
+    async fn resolve_episode_page<S: SiteExtractor + ?Sized>(&self, site: &S, episode: i32, watch_url: &str) -> (EpisodeResolution, EpisodeInfo) {
+        let mut last_error = String::new();
+        let mut attempts = 0;
 
-        if let Some(m) = pattern.find(html) {
-            let mut url = m.as_str().to_string();
-            url = url.replace("&amp;", "&");
-            return Some(url);
+        for attempt in 1..=EPISODE_PAGE_ATTEMPTS {
+            attempts = attempt;
+            match self.fetch_page(watch_url, site.base_url()).await {
+                Ok(html) => {
+                    // Extract video sources from episode page, the site's own rules first
+                    let mut info = extract_episode_page(&html, episode, watch_url);
+                    let site_sources = site
+                        .extract_video_sources(&html)
+                        .into_iter()
+                        .filter(|(ep, _)| ep.is_none_or(|ep| ep == episode))
+                        .map(|(_, source)| source);
+                    let page_sources = self.extract_video_sources_following_iframes(&html, watch_url).await;
+                    for source in site_sources.chain(page_sources) {
+                        push_described(&mut info.sources, source);
+                    }
+                    info.video_url = QualityPreference::default().select(&info.sources).map(|s| s.url.clone());
+                    info.subtitles = extract_subtitle_tracks(&html, watch_url).into_iter().map(|(_, t)| t).collect();
+                    let resolution = match info.video_url {
+                        Some(_) => EpisodeResolution::resolved(episode, attempt),
+                        None => EpisodeResolution::failed(episode, attempt, "No video URL found on episode page"),
+                    };
+                    return (resolution, info);
+                }
+                Err(e) => {
+                    last_error = e.to_string();
+                    // A missing or forbidden page won't come back on a retry
+                    if !e.is_transient() {
+                        break;
+                    }
+                }
+            }
+
+            if attempt < EPISODE_PAGE_ATTEMPTS {
+                sleep(Duration::from_millis(500 * attempt as u64)).await;
+            }
         }
 
-        None
+        let info = EpisodeInfo {
+            source_page_url: Some(watch_url.to_string()),
+            ..EpisodeInfo::new(episode)
+        };
+        (EpisodeResolution::failed(episode, attempts, &last_error), info)
     }
 
-    /// Helper to construct URL and domain
-    fn construct_series_url(series_id: i32, original_url: Option<&str>) -> (String, String) {
-        let url = if let Some(orig) = original_url {
-            if orig.contains("thongyok.com") {
-                orig.to_string()
-            } else {
-                format!("https://rongyok.com/watch/?series_id={}", series_id)
+    /// Collect the video sources on an episode page. When the page has none, the embedded
+    /// player iframes are fetched in turn (up to `MAX_IFRAME_DEPTH` levels, each with the page
+    /// that embeds it as Referer) and searched the same way.
+    async fn extract_video_sources_following_iframes(&self, html: &str, page_url: &str) -> Vec<VideoSource> {
+        let mut sources = self.extract_video_sources_on(html, page_url);
+        let mut frames: Vec<(String, String)> = extract_iframe_urls(html, page_url)
+            .into_iter()
+            .map(|src| (src, page_url.to_string()))
+            .collect();
+        let mut visited = vec![page_url.to_string()];
+
+        for _ in 0..MAX_IFRAME_DEPTH {
+            if !sources.is_empty() || frames.is_empty() {
+                break;
             }
-        } else {
-            format!("https://rongyok.com/watch/?series_id={}", series_id)
-        };
+            let mut nested = Vec::new();
+            for (frame_url, referer) in frames {
+                if visited.contains(&frame_url) {
+                    continue;
+                }
+                visited.push(frame_url.clone());
 
-        let domain = if url.contains("thongyok.com") {
-            "https://thongyok.com/"
-        } else {
-            "https://rongyok.com/"
-        };
+                // A player that won't load just means no sources from it
+                let Ok(frame_html) = self.fetch_page(&frame_url, &referer).await else { continue };
+                sources.extend(self.extract_video_sources_on(&frame_html, &frame_url));
+                nested.extend(extract_iframe_urls(&frame_html, &frame_url).into_iter().map(|src| (src, frame_url.clone())));
+            }
+            frames = nested;
+        }
 
-        (url, domain.to_string())
+        sources
     }
 
-    /// Extract all episode URLs from JavaScript
-    fn extract_all_episode_urls(&self, html: &str) -> HashMap<i32, String> {
-        let mut episode_urls = HashMap::new();
+    /// Video sources on one page, tagged with the page's host
+    fn extract_video_sources_on(&self, html: &str, page_url: &str) -> Vec<VideoSource> {
+        let host = Url::parse(page_url).ok().and_then(|u| u.host_str().map(str::to_string));
+        self.extract_video_sources_from_page(html)
+            .into_iter()
+            .map(|source| VideoSource { found_on: host.clone(), ..source })
+            .collect()
+    }
 
-        // Pattern 1: Discord CDN with numeric filename (e.g., 1.mp4, 2.mp4)
-        let pattern1 = Regex::new(
-            r#"https?:(?:\\/\\/|//)cdn\.discordapp\.com(?:\\/|/)attachments(?:\\/|/)(\d+)(?:\\/|/)(\d+)(?:\\/|/)(\d+)\.mp4\?[^"'<>\s\\]+"#
-        ).unwrap();
+    /// Collect every video source on an episode page: structured data first, then URL patterns
+    fn extract_video_sources_from_page(&self, html: &str) -> Vec<VideoSource> {
+        let mut sources = Vec::new();
+        for found in extract_structured_sources(html) {
+            push_described(&mut sources, found.source);
+        }
 
-        for caps in pattern1.captures_iter(html) {
+        for m in DISCORD_VIDEO_RE.find_iter(html).chain(MANIFEST_URL_RE.find_iter(html)) {
+            push_source(&mut sources, unescape_url(m.as_str()), source_context(html, m.start(), m.end()));
+        }
+
+        sources
+    }
+
+    /// Helper to construct URL and pick the site that serves it
+    fn construct_series_url(&self, series_id: i32, original_url: Option<&str>) -> Result<(String, Arc<dyn SiteExtractor>), ParserError> {
+        let site = self.site_for(original_url)?;
+        Ok((site.series_url(series_id, original_url), site))
+    }
+
+    /// Extract every candidate video source per episode. JSON-LD and script JSON are read first;
+    /// the URL patterns below catch whatever isn't in structured data.
+    pub(crate) fn extract_all_episode_sources(&self, html: &str) -> HashMap<i32, Vec<VideoSource>> {
+        let mut episode_sources: HashMap<i32, Vec<VideoSource>> = HashMap::new();
+        for found in extract_structured_sources(html) {
+            let episode = found.episode.or_else(|| EPISODE_FILE_RE.captures(&found.source.url).and_then(|c| c[1].parse().ok()));
+            if let Some(ep) = episode {
+                push_described(episode_sources.entry(ep).or_default(), found.source);
+            }
+        }
+
+        let mut add = |ep: i32, url: String, whole: regex::Match| {
+            let context = source_context(html, whole.start(), whole.end());
+            push_source(episode_sources.entry(ep).or_default(), url, context);
+        };
+
+        // Pattern 1: Discord CDN with numeric filename
+        for caps in DISCORD_NUMBERED_RE.captures_iter(html) {
             if let (Some(full_match), Some(ep_num)) = (caps.get(0), caps.get(3)) {
                 if let Ok(ep) = ep_num.as_str().parse::<i32>() {
-                    let mut url = full_match.as_str().to_string();
-                    url = url.replace("\\/", "/");
-                    url = url.replace("\\u0026", "&");
-                    url = url.replace("&amp;", "&");
-                    episode_urls.insert(ep, url);
+                    add(ep, unescape_url(full_match.as_str()), full_match);
                 }
             }
         }
 
-        // Pattern 2: Discord CDN with EP prefix (e.g., EP01.mp4, EP02.mp4)
-        let pattern2 = Regex::new(
-            r#"(?i)https?:(?:\\/\\/|//)cdn\.discordapp\.com(?:\\/|/)attachments(?:\\/|/)(\d+)(?:\\/|/)(\d+)(?:\\/|/)EP(\d+)\.mp4\?[^"'<>\s\\]+"#
-        ).unwrap();
-
-        for caps in pattern2.captures_iter(html) {
+        // Pattern 2: Discord CDN with EP prefix
+        for caps in DISCORD_EP_RE.captures_iter(html) {
             if let (Some(full_match), Some(ep_num)) = (caps.get(0), caps.get(3)) {
                 if let Ok(ep) = ep_num.as_str().parse::<i32>() {
-                    let mut url = full_match.as_str().to_string();
-                    url = url.replace("\\/", "/");
-                    url = url.replace("\\u0026", "&");
-                    url = url.replace("&amp;", "&");
-                    episode_urls.entry(ep).or_insert(url);
+                    add(ep, unescape_url(full_match.as_str()), full_match);
                 }
             }
         }
 
         // Pattern 3: Generic video_url in JSON
-        let pattern3 = Regex::new(r#""video_url"\s*:\s*"(https?:[^"]+\.mp4[^"]*)""#).unwrap();
-
-        for caps in pattern3.captures_iter(html) {
-            if let Some(url_match) = caps.get(1) {
-                let mut url = url_match.as_str().to_string();
-                url = url.replace("\\/", "/");
-                url = url.replace("\\u0026", "&");
+        for caps in VIDEO_URL_JSON_RE.captures_iter(html) {
+            if let (Some(whole), Some(url_match)) = (caps.get(0), caps.get(1)) {
+                let url = unescape_url(url_match.as_str());
 
                 // Try to extract episode number from URL
-                let ep_re = Regex::new(r#"[/\\](?:EP)?(\d+)\.mp4"#).unwrap();
-                if let Some(ep_caps) = ep_re.captures(&url) {
-                    if let Ok(ep) = ep_caps.get(1).unwrap().as_str().parse::<i32>() {
-                        episode_urls.entry(ep).or_insert(url);
-                    }
+                if let Some(ep) = EPISODE_FILE_RE.captures(&url).and_then(|c| c[1].parse::<i32>().ok()) {
+                    add(ep, url, whole);
                 }
             }
         }
 
-        episode_urls
+        // Pattern 4: HLS/DASH manifests linked directly in the page
+        for m in PAGE_MANIFEST_RE.find_iter(html) {
+            let url = unescape_url(m.as_str());
+            if let Some(ep) = EPISODE_FILE_RE.captures(&url).and_then(|c| c[1].parse::<i32>().ok()) {
+                add(ep, url, m);
+            }
+        }
+
+        episode_sources
     }
 
     /// Extract total episode count
@@ -288,8 +846,7 @@ impl RongyokParser {
         let desc_selector = Selector::parse("meta[name='description']").unwrap();
         if let Some(desc_el) = document.select(&desc_selector).next() {
             if let Some(content) = desc_el.value().attr("content") {
-                let re = Regex::new(r"(\d+)\s*ตอน").unwrap();
-                if let Some(caps) = re.captures(content) {
+                if let Some(caps) = EPISODE_COUNT_RE.captures(content) {
                     if let Ok(count) = caps.get(1).unwrap().as_str().parse::<i32>() {
                         return count;
                     }
@@ -298,8 +855,7 @@ impl RongyokParser {
         }
 
         // Method 2: Count episode mentions
-        let re = Regex::new(r"ตอนที่\s*(\d+)").unwrap();
-        let max_ep = re
+        let max_ep = EPISODE_MENTION_RE
             .captures_iter(html)
             .filter_map(|caps| caps.get(1)?.as_str().parse::<i32>().ok())
             .max()
@@ -309,9 +865,53 @@ impl RongyokParser {
     }
 }
 
+/// Undo JSON and HTML escaping in a scraped URL
+pub(crate) fn unescape_url(url: &str) -> String {
+    url.replace("\\/", "/").replace("\\u0026", "&").replace("&amp;", "&")
+}
+
+/// Absolute URLs of the iframes embedded in a page, in document order
+fn extract_iframe_urls(html: &str, page_url: &str) -> Vec<String> {
+    let Ok(base) = Url::parse(page_url) else { return Vec::new() };
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("iframe[src], iframe[data-src]").unwrap();
+
+    let mut urls: Vec<String> = Vec::new();
+    for iframe in document.select(&selector) {
+        let src = iframe.value().attr("src").filter(|s| !s.trim().is_empty() && !s.starts_with("about:"));
+        let Some(src) = src.or_else(|| iframe.value().attr("data-src")) else { continue };
+        let Ok(url) = base.join(&unescape_url(src.trim())) else { continue };
+        if matches!(url.scheme(), "http" | "https") && !urls.contains(&url.to_string()) {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+/// Add a candidate source found by URL pattern unless its URL is already listed
+fn push_source(sources: &mut Vec<VideoSource>, url: String, context: &str) {
+    push_described(sources, describe_source(url, context));
+}
+
+/// Add a candidate source. A URL that is already listed keeps its provenance and only
+/// picks up quality hints it was missing.
+fn push_described(sources: &mut Vec<VideoSource>, source: VideoSource) {
+    match sources.iter_mut().find(|s| s.url == source.url) {
+        Some(existing) => {
+            existing.resolution = existing.resolution.or(source.resolution);
+            existing.bitrate = existing.bitrate.or(source.bitrate);
+            existing.size = existing.size.or(source.size);
+        }
+        None => sources.push(source),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::quality::Provenance;
+    use crate::sites::{RongyokSite, ThongyokSite};
+    use crate::test_support::{fixture, FixtureServer};
 
     #[test]
     fn test_parse_series_url() {
@@ -322,32 +922,319 @@ mod tests {
         // Thongyok format
         assert_eq!(RongyokParser::parse_series_url("https://thongyok.com/series/1004/%E0%B8%84%E0%B8%B7%E0%B8%99..."), Some(1004));
 
+        // Thongyok episode page
+        assert_eq!(RongyokParser::parse_series_url("https://thongyok.com/watch/1004/7"), Some(1004));
+        assert_eq!(RongyokParser::parse_episode_url("https://thongyok.com/watch/1004/7"), Some(7));
+        assert_eq!(RongyokParser::parse_episode_url("https://rongyok.com/watch/?series_id=1004&ep=3"), Some(3));
+        assert_eq!(RongyokParser::parse_episode_url("https://rongyok.com/watch/?series_id=1004"), None);
+
         // Invalid
         assert_eq!(RongyokParser::parse_series_url("https://rongyok.com/invalid"), None);
     }
 
+    #[tokio::test]
+    async fn test_resolve_target() {
+        let parser = RongyokParser::new();
+
+        // Mobile-domain episode link resolves to the series page on the main domain
+        let target = parser.resolve_target("https://m.thongyok.com/watch/1004/7").await.unwrap();
+        assert_eq!(target, SeriesTarget {
+            site: "thongyok".to_string(),
+            series_id: 1004,
+            episode: Some(7),
+            series_url: "https://thongyok.com/series/1004".to_string(),
+        });
+
+        // Known site, but not a series link
+        assert_eq!(
+            parser.resolve_target("https://rongyok.com/genre/drama").await,
+            Err(ParserError::InvalidUrl("https://rongyok.com/genre/drama".to_string()))
+        );
+
+        // Short links are followed to wherever they land
+        let site = FixtureServer::bind().page("/watch/?series_id=1004&ep=3", "rongyok_series.html").start();
+        let shortener = FixtureServer::bind().redirect("/s/abc", &format!("{}watch/?series_id=1004&ep=3", site)).start();
+        // Same machine, but a host no registered site claims
+        let short = shortener.replace("127.0.0.1", "localhost");
+        let mut registry = ExtractorRegistry::new();
+        registry.register(Box::new(RongyokSite::with_base_url(&site)));
+        let parser = RongyokParser::with_registry(registry);
+
+        let target = parser.resolve_target(&format!("{}s/abc", short)).await.unwrap();
+        assert_eq!((target.series_id, target.episode), (1004, Some(3)));
+        assert_eq!(target.series_url, format!("{}watch/?series_id=1004", site));
+
+        // A numeric path on an unknown host is not taken for a series of the first site,
+        // and a dead short link reports the status it got
+        let dead = format!("{}watch/1004/7", short);
+        assert_eq!(parser.parse_target(&dead), None);
+        assert_eq!(
+            parser.resolve_target(&dead).await,
+            Err(ParserError::HttpStatus { status: 404, url: dead.clone() })
+        );
+    }
+
     #[test]
     fn test_construct_series_url() {
+        let parser = RongyokParser::new();
+
         // Case 1: Rongyok URL (standard)
-        let (url, domain) = RongyokParser::construct_series_url(1004, Some("https://rongyok.com/watch/?series_id=1004"));
+        let (url, site) = parser.construct_series_url(1004, Some("https://rongyok.com/watch/?series_id=1004")).unwrap();
         assert_eq!(url, "https://rongyok.com/watch/?series_id=1004");
-        assert_eq!(domain, "https://rongyok.com/");
+        assert_eq!(site.base_url(), "https://rongyok.com/");
 
         // Case 2: Thongyok URL
         let th_url = "https://thongyok.com/series/1004/test-title";
-        let (url, domain) = RongyokParser::construct_series_url(1004, Some(th_url));
+        let (url, site) = parser.construct_series_url(1004, Some(th_url)).unwrap();
         assert_eq!(url, th_url);
-        assert_eq!(domain, "https://thongyok.com/");
+        assert_eq!(site.base_url(), "https://thongyok.com/");
 
         // Case 3: None (fallback)
-        let (url, domain) = RongyokParser::construct_series_url(1004, None);
+        let (url, site) = parser.construct_series_url(1004, None).unwrap();
         assert_eq!(url, "https://rongyok.com/watch/?series_id=1004");
-        assert_eq!(domain, "https://rongyok.com/");
+        assert_eq!(site.base_url(), "https://rongyok.com/");
 
-        // Case 4: Other domain/format treated as Rongyok if not explicit Thongyok
-        let (url, domain) = RongyokParser::construct_series_url(1004, Some("https://other.com/1004"));
-        // Current implementation falls back to Rongyok format if not containing "thongyok.com"
+        // Case 4: Other domain/format treated as Rongyok if no registered site matches
+        let (url, site) = parser.construct_series_url(1004, Some("https://other.com/1004")).unwrap();
+        // Unknown domains fall back to the first registered site (Rongyok)
         assert_eq!(url, "https://rongyok.com/watch/?series_id=1004");
-        assert_eq!(domain, "https://rongyok.com/");
+        assert_eq!(site.base_url(), "https://rongyok.com/");
+    }
+
+    #[test]
+    fn test_resolution_report_from_page() {
+        let source = |url: &str| vec![VideoSource { url: url.to_string(), ..Default::default() }];
+        let mut sources = HashMap::new();
+        sources.insert(1, source("https://example.com/1.mp4"));
+        sources.insert(3, source("https://example.com/3.mp4"));
+
+        let resolved = ResolvedEpisodes::from_page(sources, 4);
+        let statuses: Vec<_> = resolved.report.iter().map(|r| (r.episode, r.status)).collect();
+
+        assert_eq!(statuses, vec![
+            (1, ResolutionStatus::Resolved),
+            (2, ResolutionStatus::Failed),
+            (3, ResolutionStatus::Resolved),
+            (4, ResolutionStatus::Failed),
+        ]);
+    }
+
+    #[test]
+    fn test_extract_all_episode_sources_fixture() {
+        let parser = RongyokParser::new();
+        let sources = parser.extract_all_episode_sources(&fixture("rongyok_series.html"));
+        assert!(sources.values().all(|s| s.len() == 1));
+        let urls: HashMap<i32, &str> = sources.iter().map(|(ep, s)| (*ep, s[0].url.as_str())).collect();
+
+        assert_eq!(urls.len(), 4);
+        // Script JSON episode list, JSON-escaped numeric filename
+        assert_eq!(urls[&1], "https://cdn.discordapp.com/attachments/1111/2222/1.mp4?ex=67a1b2c3&is=67a06143&hm=aa11");
+        // Script JSON, EP-prefixed filename
+        assert_eq!(urls[&2], "https://cdn.discordapp.com/attachments/1111/2223/EP02.mp4?ex=67a1b2c3&is=67a06143&hm=bb22");
+        // Pattern 1 in HTML with &amp; entities
+        assert_eq!(urls[&3], "https://cdn.discordapp.com/attachments/1111/2224/3.mp4?ex=67a1b2c3&is=67a06143&hm=cc33");
+        // Script JSON video_url pointing at an HLS playlist
+        assert_eq!(urls[&4], "https://stream.example.com/1004/4/index.m3u8");
+
+        let provenance: Vec<_> = (1..=4).map(|ep| sources[&ep][0].provenance).collect();
+        assert_eq!(provenance, vec![Provenance::ScriptJson, Provenance::ScriptJson, Provenance::Pattern, Provenance::ScriptJson]);
+    }
+
+    #[test]
+    fn test_episode_availability() {
+        let episode_urls = HashMap::from([
+            (1, "https://cdn.example.com/1.mp4".to_string()),
+            (2, "https://cdn.example.com/1.mp4".to_string()),
+            (4, "https://cdn.example.com/4.mp4".to_string()),
+        ]);
+        let availability = episode_availability(5, &episode_urls, &[], &[]);
+        assert_eq!(availability, BTreeMap::from([
+            (1, Availability::Available),
+            (2, Availability::Duplicate { of: 1 }),
+            (3, Availability::Missing),
+            (4, Availability::Available),
+            (5, Availability::Missing),
+        ]));
+    }
+
+    #[test]
+    fn test_extract_total_episodes_fixture() {
+        let parser = RongyokParser::new();
+
+        for (name, expected) in [("rongyok_series.html", 4), ("thongyok_series.html", 3)] {
+            let html = fixture(name);
+            let document = Html::parse_document(&html);
+            assert_eq!(parser.extract_total_episodes(&document, &html), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_extract_video_sources_from_page_fixture() {
+        let parser = RongyokParser::new();
+
+        // Two renditions labelled with Plyr-style size attributes
+        let sources = parser.extract_video_sources_from_page(&fixture("thongyok_watch_1.html"));
+        assert_eq!(sources.len(), 2);
+        assert_eq!(
+            QualityPreference::Highest.select(&sources).map(|s| s.url.as_str()),
+            Some("https://cdn.discordapp.com/attachments/3333/4444/1.mp4?ex=67a1b2c3&is=67a06143&hm=dd44")
+        );
+        assert_eq!(QualityPreference::Lowest.select(&sources).and_then(|s| s.resolution), Some(480));
+
+        let sources = parser.extract_video_sources_from_page(&fixture("thongyok_watch_2.html"));
+        assert_eq!(sources[0].url, "https://stream.example.com/7/2/master.m3u8?token=xyz");
+        assert!(parser.extract_video_sources_from_page(&fixture("thongyok_series.html")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_follows_player_iframes() {
+        let server = FixtureServer::bind();
+        let base = server.base_url().to_string();
+        // The player lives on another host and only loads when embedded from it
+        let player_host = base.replace("127.0.0.1", "localhost");
+        let watch = format!(r#"<html><body><iframe src="{}embed/1"></iframe></body></html>"#, player_host);
+        let embed = r#"<iframe src="/embed/1"></iframe><iframe src="/player/1?autoplay=1"></iframe>"#;
+        let player = r#"<video><source src="https://stream.example.com/7/1/master.m3u8" type="application/x-mpegURL"></video>"#;
+        let base = server
+            .bytes("/watch/7/1", "text/html", watch.as_bytes())
+            .bytes("/embed/1", "text/html", embed.as_bytes())
+            .with_referer("/embed/1", &format!("{}watch/7/1", base))
+            .bytes("/player/1?autoplay=1", "text/html", player.as_bytes())
+            .with_referer("/player/1?autoplay=1", &format!("{}embed/1", player_host))
+            .start();
+
+        let parser = RongyokParser::new();
+        let site = ThongyokSite::with_base_url(&base);
+        let resolved = parser.fetch_episode_page_urls(&site, vec![(1, format!("{}watch/7/1", base))]).await;
+
+        assert_eq!(resolved.report[0].status, ResolutionStatus::Resolved);
+        let sources = &resolved.episodes[&1].sources;
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].url, "https://stream.example.com/7/1/master.m3u8");
+        assert_eq!(sources[0].found_on.as_deref(), Some("localhost"));
+    }
+
+    #[tokio::test]
+    async fn test_get_series_info_rongyok_offline() {
+        let server = FixtureServer::bind();
+        let base = server
+            .page("/watch/?series_id=1004", "rongyok_series.html")
+            .start();
+
+        let mut registry = ExtractorRegistry::new();
+        registry.register(Box::new(RongyokSite::with_base_url(&base)));
+        let parser = RongyokParser::with_registry(registry);
+
+        let info = parser.get_series_info(1004, None).await.unwrap();
+
+        assert_eq!(info.site, "rongyok");
+        assert_eq!(info.title, "รักนี้ต้องห้าม");
+        assert_eq!(info.total_episodes, 4);
+        assert_eq!(info.episode_urls.len(), 4);
+        assert_eq!(info.poster_url, Some(format!("{}images/poster-1004.jpg", base)));
+        assert!(info.resolution_report.iter().all(|r| r.status == ResolutionStatus::Resolved));
+        assert_eq!(info.genres, vec!["Drama", "Romance", "ซีรีย์จีน"]);
+        assert_eq!(info.episodes.len(), 4);
+        assert_eq!(info.episodes[0].duration, Some(2712.0));
+        assert_eq!(info.episodes[3].video_url.as_deref(), Some("https://stream.example.com/1004/4/index.m3u8"));
+    }
+
+    #[tokio::test]
+    async fn test_search_series_offline() {
+        let server = FixtureServer::bind();
+        let base = server.page("/?s=love", "rongyok_search.html").start();
+
+        let mut registry = ExtractorRegistry::new();
+        registry.register(Box::new(RongyokSite::with_base_url(&base)));
+        let parser = RongyokParser::with_registry(registry);
+
+        let results = parser.search_series(" love ", None).await.unwrap();
+        let found: Vec<_> = results.iter().map(|r| (r.series_id, r.title.as_str(), r.total_episodes)).collect();
+        assert_eq!(found, vec![(1004, "รักนี้ต้องห้าม", Some(4)), (2001, "Love in Bangkok", Some(16))]);
+
+        // Results feed straight into get_series_info
+        assert_eq!(results[0].url, format!("{}watch/?series_id=1004", base));
+        assert_eq!(results[1].poster_url, Some(format!("{}images/poster-2001.jpg", base)));
+        assert_eq!(
+            parser.search_series("love", Some("thongyok")).await,
+            Err(ParserError::UnsupportedSite("thongyok".to_string()))
+        );
+        assert_eq!(parser.search_series("  ", None).await, Err(ParserError::EmptyQuery));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_listing_offline() {
+        let server = FixtureServer::bind();
+        let base = server
+            .page("/category/chinese/", "rongyok_category.html")
+            .page("/category/chinese/page/2/", "rongyok_category_2.html")
+            .start();
+
+        let mut registry = ExtractorRegistry::new();
+        registry.register(Box::new(RongyokSite::with_base_url(&base)));
+        let parser = RongyokParser::with_registry(registry);
+        let url = format!("{}category/chinese/", base);
+
+        let first = parser.get_listing(&url).await.unwrap();
+        assert_eq!((first.page, first.total_pages), (1, Some(3)));
+        assert_eq!(first.next_page_url, Some(format!("{}category/chinese/page/2/", base)));
+        assert_eq!(first.series[1].total_episodes, Some(58));
+
+        // Stop after two pages, leaving page 3 for a later crawl
+        let crawled = parser.crawl_listing(&url, 2).await.unwrap();
+        let ids: Vec<i32> = crawled.series.iter().map(|s| s.series_id).collect();
+        assert_eq!(ids, vec![1004, 1010, 1023]);
+        assert_eq!(crawled.page, 2);
+        assert_eq!(crawled.next_page_url, Some(format!("{}category/chinese/page/3/", base)));
+
+        let missing = crawled.next_page_url.unwrap();
+        assert_eq!(
+            parser.get_listing(&missing).await.unwrap_err(),
+            ParserError::HttpStatus { status: 404, url: missing.clone() }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_series_info_thongyok_offline() {
+        let server = FixtureServer::bind();
+        let base = server
+            .page("/series/7/title", "thongyok_series.html")
+            .page("/watch/7/1", "thongyok_watch_1.html")
+            .page("/watch/7/2", "thongyok_watch_2.html")
+            .start(); // /watch/7/3 is missing and returns 404
+
+        let mut registry = ExtractorRegistry::new();
+        registry.register(Box::new(ThongyokSite::with_base_url(&base)));
+        let parser = RongyokParser::with_registry(registry);
+
+        let series_url = format!("{}series/7/title", base);
+        let info = parser.get_series_info(7, Some(&series_url)).await.unwrap();
+
+        assert_eq!(info.site, "thongyok");
+        assert_eq!(info.title, "คืนนี้ที่รอคอย");
+        assert_eq!(info.episode_urls.len(), 2);
+        assert!(info.episode_urls[&2].ends_with("master.m3u8?token=xyz"));
+        assert_eq!(info.episodes[0].sources.len(), 2);
+        assert_eq!(info.episodes[1].subtitles[0].url, format!("{}subs/7/2.vtt", base));
+        assert!(info.video_url_for(1, QualityPreference::Lowest).unwrap().ends_with("hm=ee55"));
+
+        let failed = &info.resolution_report[2];
+        assert_eq!((failed.episode, failed.status), (3, ResolutionStatus::Failed));
+        // A 404 is not retried
+        assert_eq!(failed.attempts, 1);
+        assert!(failed.reason.as_deref().unwrap().contains("HTTP 404"));
+
+        // The failed episode still counts and blocks a selection that includes it
+        assert_eq!(info.total_episodes, 3);
+        assert!(matches!(info.availability[&3], Availability::Unresolved { .. }));
+        assert!(info.check_selection(&[1, 2]).is_ok());
+        assert_eq!(info.check_selection(&[2, 3]).unwrap_err().keys().collect::<Vec<_>>(), vec![&3]);
+
+        // Episode page details override the series listing
+        let second = &info.episodes[1];
+        assert_eq!(second.title.as_deref(), Some("คืนนี้ที่รอคอย ตอนที่ 2"));
+        assert_eq!(second.duration, Some(2712.0));
+        assert_eq!(second.thumbnail, Some(format!("{}images/7/ep2.jpg", base)));
+        assert_eq!(info.episodes[2].source_page_url, Some(format!("{}watch/7/3", base)));
     }
 }
@@ -0,0 +1,178 @@
+//! On-disk cache for series posters.
+//!
+//! Posters are stored under the app cache directory, keyed by a hash of their URL, and
+//! revalidated with ETag / Last-Modified on each fetch. The webview loads them through the
+//! `poster://` URI scheme instead of receiving base64 data URLs over IPC.
+
+use crate::network::{build_client, ProxySettings};
+use crate::session::Session;
+use reqwest::header::{CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// URI scheme registered with the webview for cached posters
+pub const POSTER_SCHEME: &str = "poster";
+
+/// Validators and content type stored next to each cached image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    url: String,
+    content_type: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+pub struct PosterCache {
+    dir: PathBuf,
+    client: RwLock<Client>,
+}
+
+impl PosterCache {
+    /// Cache in the app cache directory (falls back to the system temp dir)
+    pub fn new() -> Self {
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("com.rongyok.downloader")
+            .join("posters");
+        Self::with_dir(dir)
+    }
+
+    pub fn with_dir(dir: PathBuf) -> Self {
+        let client = build_client(&Session::default(), &ProxySettings::default()).expect("Failed to create HTTP client");
+        Self { dir, client: RwLock::new(client) }
+    }
+
+    /// Use another HTTP client from now on, e.g. one going through the user's proxy
+    pub fn set_client(&self, client: Client) {
+        *self.client.write().unwrap() = client;
+    }
+
+    /// Stable cache key for a poster URL (64-bit FNV-1a, hex)
+    pub fn key_for(url: &str) -> String {
+        let hash = url.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        format!("{:016x}", hash)
+    }
+
+    /// URL the webview uses to load a cached poster
+    pub fn scheme_url(key: &str) -> String {
+        // Windows and Android serve custom schemes as http://<scheme>.localhost
+        if cfg!(any(windows, target_os = "android")) {
+            format!("http://{}.localhost/{}", POSTER_SCHEME, key)
+        } else {
+            format!("{}://localhost/{}", POSTER_SCHEME, key)
+        }
+    }
+
+    /// Make sure the poster is cached and current; returns its cache key.
+    /// A stale copy is kept and used if revalidation fails.
+    pub async fn fetch(&self, url: &str) -> Result<String, String> {
+        let key = Self::key_for(url);
+        let cached = self.read_entry(&key).filter(|_| self.image_path(&key).exists());
+
+        let client = self.client.read().unwrap().clone();
+        let mut request = client.get(url).header("Accept", "image/*");
+        if let Some(ref entry) = cached {
+            if let Some(ref etag) = entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(ref modified) = entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, modified);
+            }
+        }
+
+        let response = match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response,
+            Err(_) if cached.is_some() => return Ok(key),
+            Err(e) => return Err(format!("Failed to fetch poster: {}", e)),
+        };
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(key);
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let entry = CacheEntry {
+            url: url.to_string(),
+            content_type: header(CONTENT_TYPE).unwrap_or_else(|| "image/jpeg".to_string()),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read poster: {}", e))?;
+
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create poster cache: {}", e))?;
+        std::fs::write(self.image_path(&key), &bytes).map_err(|e| format!("Failed to write poster: {}", e))?;
+        let json = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        std::fs::write(self.entry_path(&key), json).map_err(|e| format!("Failed to write poster: {}", e))?;
+
+        Ok(key)
+    }
+
+    /// Read a cached poster and its content type
+    pub fn load(&self, key: &str) -> Option<(Vec<u8>, String)> {
+        // Keys are hex hashes; anything else could escape the cache directory
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let entry = self.read_entry(key)?;
+        let bytes = std::fs::read(self.image_path(key)).ok()?;
+        Some((bytes, entry.content_type))
+    }
+
+    fn read_entry(&self, key: &str) -> Option<CacheEntry> {
+        let json = std::fs::read_to_string(self.entry_path(key)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    fn image_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.img", key))
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FixtureServer;
+
+    #[tokio::test]
+    async fn test_fetch_revalidates_with_etag() {
+        let base = FixtureServer::bind()
+            .bytes("/poster.png", "image/png", b"\x89PNGposter")
+            .with_etag("/poster.png", "\"v1\"")
+            .start();
+        let dir = std::env::temp_dir().join(format!("poster-cache-test-{}", std::process::id()));
+        let cache = PosterCache::with_dir(dir.clone());
+        let url = format!("{}poster.png", base);
+
+        let key = cache.fetch(&url).await.unwrap();
+        assert_eq!(key, PosterCache::key_for(&url));
+        assert_eq!(cache.load(&key), Some((b"\x89PNGposter".to_vec(), "image/png".to_string())));
+
+        // A 304 leaves the cached bytes untouched
+        std::fs::write(cache.image_path(&key), b"cached").unwrap();
+        cache.fetch(&url).await.unwrap();
+        assert_eq!(cache.load(&key).unwrap().0, b"cached");
+
+        assert_eq!(cache.load("../secrets"), None);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
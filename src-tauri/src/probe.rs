@@ -0,0 +1,279 @@
+//! Pre-download probing of episode URLs.
+//!
+//! Every selected episode's video URL is checked in parallel with a HEAD request, falling back to
+//! a one-byte ranged GET for servers that reject HEAD or leave out the length. The results give
+//! the series' total size (for disk-space checks), dead links, and a download time estimate from
+//! a short throughput sample.
+
+use crate::dash;
+use crate::hls;
+use crate::parser::SeriesInfo;
+use crate::quality::{QualityPreference, VideoSource};
+use futures_util::{stream, StreamExt};
+use reqwest::header::{HeaderMap, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use reqwest::{Client, Response, StatusCode};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Number of URLs probed in parallel
+const PROBE_CONCURRENCY: usize = 8;
+/// Time allowed for each probe request
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+/// Bytes downloaded to sample throughput
+const SAMPLE_BYTES: u64 = 512 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamKind {
+    /// A single file downloaded in one request
+    Progressive,
+    Hls,
+    Dash,
+}
+
+/// What a probe found out about one episode's video URL
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeProbe {
+    pub episode: i32,
+    pub url: Option<String>,
+    pub kind: StreamKind,
+    /// The server answered with a success status
+    pub alive: bool,
+    pub status: Option<u16>,
+    /// Length the server reported; for HLS/DASH this is the playlist's
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+    pub accepts_ranges: bool,
+    /// Expected download size; HLS/DASH sizes are estimated from bitrate and duration
+    pub size: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Probe results for a download selection
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesProbe {
+    pub episodes: Vec<EpisodeProbe>,
+    /// Sum of the known sizes
+    pub total_size: u64,
+    /// Live episodes whose size couldn't be determined
+    pub unknown_size: Vec<i32>,
+    pub dead: Vec<i32>,
+    /// Expected overall download rate, in bytes per second
+    pub bytes_per_sec: Option<f64>,
+    pub estimated_secs: Option<u64>,
+}
+
+impl SeriesProbe {
+    /// Sum up episode probes and estimate the download time at `bytes_per_sec`
+    pub fn new(episodes: Vec<EpisodeProbe>, bytes_per_sec: Option<f64>) -> Self {
+        let total_size = episodes.iter().filter(|p| p.alive).filter_map(|p| p.size).sum();
+        let unknown_size = episodes.iter().filter(|p| p.alive && p.size.is_none()).map(|p| p.episode).collect();
+        let dead = episodes.iter().filter(|p| !p.alive).map(|p| p.episode).collect();
+        let estimated_secs = bytes_per_sec
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| (total_size as f64 / rate).ceil() as u64);
+        Self { episodes, total_size, unknown_size, dead, bytes_per_sec, estimated_secs }
+    }
+}
+
+/// Probe the chosen source of each episode, in episode order
+pub async fn probe_episodes(client: &Client, series: &SeriesInfo, episodes: &[i32], quality: QualityPreference) -> Vec<EpisodeProbe> {
+    let mut probes: Vec<EpisodeProbe> = stream::iter(episodes.iter().copied())
+        .map(|ep| probe_episode(client, series, ep, quality))
+        .buffer_unordered(PROBE_CONCURRENCY)
+        .collect()
+        .await;
+    probes.sort_by_key(|p| p.episode);
+    probes
+}
+
+async fn probe_episode(client: &Client, series: &SeriesInfo, episode: i32, quality: QualityPreference) -> EpisodeProbe {
+    let info = series.episodes.iter().find(|e| e.episode_number == episode);
+    let source = info
+        .and_then(|e| quality.select(&e.sources).cloned())
+        .or_else(|| series.episode_urls.get(&episode).map(|url| VideoSource { url: url.clone(), ..Default::default() }));
+
+    let Some(source) = source else {
+        return EpisodeProbe {
+            episode,
+            url: None,
+            kind: StreamKind::Progressive,
+            alive: false,
+            status: None,
+            content_length: None,
+            content_type: None,
+            accepts_ranges: false,
+            size: None,
+            error: Some("No video URL".to_string()),
+        };
+    };
+
+    let kind = if hls::is_hls_url(&source.url) {
+        StreamKind::Hls
+    } else if dash::is_dash_url(&source.url) {
+        StreamKind::Dash
+    } else {
+        StreamKind::Progressive
+    };
+
+    let mut probe = probe_url(client, &source.url).await;
+    probe.episode = episode;
+    probe.kind = kind;
+    // A playlist's own length says nothing about the video
+    probe.size = match kind {
+        StreamKind::Progressive => probe.content_length.or(source.size),
+        StreamKind::Hls | StreamKind::Dash => source.size.or_else(|| {
+            let duration = info?.duration?;
+            Some((source.bitrate? as f64 * duration / 8.0) as u64)
+        }),
+    };
+    probe
+}
+
+/// HEAD the URL, or GET its first byte when HEAD is refused or gives no length
+async fn probe_url(client: &Client, url: &str) -> EpisodeProbe {
+    let mut probe = EpisodeProbe {
+        episode: 0,
+        url: Some(url.to_string()),
+        kind: StreamKind::Progressive,
+        alive: false,
+        status: None,
+        content_length: None,
+        content_type: None,
+        accepts_ranges: false,
+        size: None,
+        error: None,
+    };
+
+    let head = client.head(url).timeout(PROBE_TIMEOUT).send().await;
+    let response = match head {
+        Ok(r) if r.status().is_success() && header_u64(r.headers(), CONTENT_LENGTH).is_some() => Ok(r),
+        _ => client.get(url).header(RANGE, "bytes=0-0").timeout(PROBE_TIMEOUT).send().await,
+    };
+
+    match response {
+        Ok(response) => read_probe_headers(&mut probe, &response),
+        Err(e) => {
+            probe.status = e.status().map(|s| s.as_u16());
+            probe.error = Some(e.to_string());
+        }
+    }
+    probe
+}
+
+fn read_probe_headers(probe: &mut EpisodeProbe, response: &Response) {
+    let status = response.status();
+    let headers = response.headers();
+    probe.status = Some(status.as_u16());
+    probe.alive = status.is_success();
+    if !probe.alive {
+        probe.error = Some(format!("HTTP {}", status.as_u16()));
+        return;
+    }
+
+    probe.content_type = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).map(str::to_string);
+    probe.accepts_ranges = status == StatusCode::PARTIAL_CONTENT
+        || headers.get(ACCEPT_RANGES).and_then(|v| v.to_str().ok()).is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+    // A ranged answer carries the full length after the slash: "bytes 0-0/12345"
+    probe.content_length = if status == StatusCode::PARTIAL_CONTENT {
+        headers
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit_once('/'))
+            .and_then(|(_, total)| total.parse().ok())
+    } else {
+        header_u64(headers, CONTENT_LENGTH)
+    };
+}
+
+fn header_u64(headers: &HeaderMap, name: reqwest::header::HeaderName) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+/// Download speed for one connection, measured by fetching the start of `url`
+pub async fn sample_throughput(client: &Client, url: &str) -> Option<f64> {
+    let started = Instant::now();
+    let response = client
+        .get(url)
+        .header(RANGE, format!("bytes=0-{}", SAMPLE_BYTES - 1))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+
+    let mut received = 0u64;
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        received += chunk.ok()?.len() as u64;
+        // Servers that ignore the range send the whole file
+        if received >= SAMPLE_BYTES {
+            break;
+        }
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    (received > 0 && elapsed > 0.0).then(|| received as f64 / elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::ProxySettings;
+    use crate::parser::EpisodeInfo;
+    use crate::session::Session;
+    use crate::test_support::FixtureServer;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_probe_episodes() {
+        let base = FixtureServer::bind()
+            .bytes("/v/1.mp4", "video/mp4", &[0u8; 2048])
+            .with_ranges("/v/1.mp4")
+            .bytes("/v/2.m3u8", "application/vnd.apple.mpegurl", b"#EXTM3U\n")
+            .start(); // /v/3.mp4 is gone
+
+        let mut episodes: Vec<EpisodeInfo> = (1..=3).map(EpisodeInfo::new).collect();
+        episodes[1].duration = Some(60.0);
+        episodes[1].sources = vec![VideoSource {
+            url: format!("{}v/2.m3u8", base),
+            bitrate: Some(800_000),
+            ..Default::default()
+        }];
+        let series = SeriesInfo {
+            series_id: 1,
+            site: "rongyok".to_string(),
+            source_url: base.clone(),
+            title: "Probe".to_string(),
+            description: None,
+            genres: Vec::new(),
+            alternate_titles: Vec::new(),
+            total_episodes: 3,
+            poster_url: None,
+            episode_urls: HashMap::from([(1, format!("{}v/1.mp4", base)), (3, format!("{}v/3.mp4", base))]),
+            episodes,
+            resolution_report: Vec::new(),
+            availability: Default::default(),
+        };
+
+        let client = crate::network::build_client(&Session::default(), &ProxySettings::default()).unwrap();
+        let probes = probe_episodes(&client, &series, &[1, 2, 3], QualityPreference::default()).await;
+
+        let first = &probes[0];
+        assert!(first.alive && first.accepts_ranges);
+        assert_eq!((first.size, first.content_type.as_deref()), (Some(2048), Some("video/mp4")));
+        // The playlist is alive, and its size comes from 800 kbps over 60 seconds
+        assert_eq!((probes[1].kind, probes[1].alive, probes[1].size), (StreamKind::Hls, true, Some(6_000_000)));
+        assert_eq!((probes[2].alive, probes[2].status), (false, Some(404)));
+
+        let summary = SeriesProbe::new(probes, Some(1_000_000.0));
+        assert_eq!((summary.total_size, summary.dead.clone()), (6_002_048, vec![3]));
+        assert_eq!(summary.estimated_secs, Some(7));
+
+        let rate = sample_throughput(&client, &format!("{}v/1.mp4", base)).await;
+        assert!(rate.is_some_and(|r| r > 0.0));
+    }
+}
@@ -0,0 +1,196 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// One candidate video source for an episode
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoSource {
+    pub url: String,
+    /// Vertical resolution, e.g. 720
+    pub resolution: Option<u32>,
+    /// Bits per second
+    pub bitrate: Option<u64>,
+    /// File size in bytes
+    pub size: Option<u64>,
+    /// Host of the page the source was found on, e.g. an embedded player's host
+    pub found_on: Option<String>,
+    #[serde(default)]
+    pub provenance: Provenance,
+}
+
+/// How a source URL was found on the page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Provenance {
+    /// schema.org JSON-LD block
+    JsonLd,
+    /// JSON embedded in an inline script, e.g. a player config
+    ScriptJson,
+    /// URL pattern matched in the raw HTML
+    #[default]
+    Pattern,
+    /// Pattern from a user-defined site rule
+    Rule,
+}
+
+/// Which rendition to download when several are available
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum QualityPreference {
+    #[default]
+    Highest,
+    Lowest,
+    /// Closest to the given height (ties go to the higher one)
+    Closest { height: u32 },
+    /// Smallest known file size, falling back to the lowest bitrate/resolution
+    Smallest,
+}
+
+/// Anything with detectable quality attributes: page sources, HLS variants, DASH representations
+pub trait Rendition {
+    fn height(&self) -> Option<u32>;
+    fn bitrate(&self) -> Option<u64>;
+    fn size(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl Rendition for VideoSource {
+    fn height(&self) -> Option<u32> {
+        self.resolution
+    }
+
+    fn bitrate(&self) -> Option<u64> {
+        self.bitrate
+    }
+
+    fn size(&self) -> Option<u64> {
+        self.size
+    }
+}
+
+impl QualityPreference {
+    /// Pick a rendition; when candidates are indistinguishable the first one wins
+    pub fn select<'a, T: Rendition + 'a>(&self, items: impl IntoIterator<Item = &'a T>) -> Option<&'a T> {
+        let mut items = items.into_iter();
+        let first = items.next()?;
+
+        Some(items.fold(first, |best, item| {
+            let better = match *self {
+                QualityPreference::Highest => highest_key(item) > highest_key(best),
+                QualityPreference::Lowest => lowest_key(item) < lowest_key(best),
+                QualityPreference::Closest { height } => closest_key(item, height) < closest_key(best, height),
+                QualityPreference::Smallest => smallest_key(item) < smallest_key(best),
+            };
+            if better { item } else { best }
+        }))
+    }
+}
+
+fn highest_key<T: Rendition>(r: &T) -> (u32, u64) {
+    (r.height().unwrap_or(0), r.bitrate().unwrap_or(0))
+}
+
+fn lowest_key<T: Rendition>(r: &T) -> (u32, u64) {
+    (r.height().unwrap_or(u32::MAX), r.bitrate().unwrap_or(u64::MAX))
+}
+
+fn closest_key<T: Rendition>(r: &T, target: u32) -> (u32, std::cmp::Reverse<u32>, std::cmp::Reverse<u64>) {
+    let distance = r.height().map(|h| h.abs_diff(target)).unwrap_or(u32::MAX);
+    (
+        distance,
+        std::cmp::Reverse(r.height().unwrap_or(0)),
+        std::cmp::Reverse(r.bitrate().unwrap_or(0)),
+    )
+}
+
+fn smallest_key<T: Rendition>(r: &T) -> (u64, u64, u32) {
+    (
+        r.size().unwrap_or(u64::MAX),
+        r.bitrate().unwrap_or(u64::MAX),
+        r.height().unwrap_or(u32::MAX),
+    )
+}
+
+/// Build a source from its URL and the surrounding markup (JSON object or HTML tag),
+/// picking up hints like `"label": "720p"`, `size="1080"`, `"bitrate": 2500` or `"filesize": 123456789`
+pub fn describe_source(url: String, context: &str) -> VideoSource {
+    let height_re = Regex::new(
+        r#"(?i)["']?(?:res|resolution|height|quality|label|size|data-res)["']?\s*[:=]\s*["']?(\d{3,4})p?\b"#
+    ).unwrap();
+    let url_height_re = Regex::new(r"(?i)[/_\-.=](\d{3,4})p(?:[/_\-.?&]|$)").unwrap();
+    let bitrate_re = Regex::new(r#"(?i)["']?(?:bitrate|bandwidth|data-bitrate)["']?\s*[:=]\s*["']?(\d+)"#).unwrap();
+    let size_re = Regex::new(r#"(?i)["']?(?:filesize|file_size|data-size|size)["']?\s*[:=]\s*["']?(\d{5,})"#).unwrap();
+
+    let resolution = height_re
+        .captures(context)
+        .or_else(|| url_height_re.captures(&url))
+        .and_then(|c| c[1].parse::<u32>().ok())
+        .filter(|h| (144..=4320).contains(h));
+    // Small values are almost always kbps
+    let bitrate = bitrate_re
+        .captures(context)
+        .and_then(|c| c[1].parse::<u64>().ok())
+        .map(|b| if b < 100_000 { b * 1000 } else { b });
+    let size = size_re.captures(context).and_then(|c| c[1].parse::<u64>().ok());
+
+    VideoSource { url, resolution, bitrate, size, ..Default::default() }
+}
+
+/// The JSON object or HTML tag enclosing `html[start..end]`, if it is reasonably close
+pub fn source_context(html: &str, start: usize, end: usize) -> &str {
+    const MAX_DISTANCE: usize = 400;
+
+    let open = html[..start]
+        .rfind(['{', '<'])
+        .filter(|i| start - i < MAX_DISTANCE)
+        .unwrap_or(start);
+    let close = html[end..]
+        .find(['}', '>'])
+        .filter(|i| *i < MAX_DISTANCE)
+        .map(|i| end + i + 1)
+        .unwrap_or(end);
+
+    &html[open..close]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(url: &str, resolution: Option<u32>, size: Option<u64>) -> VideoSource {
+        VideoSource { url: url.to_string(), resolution, size, ..Default::default() }
+    }
+
+    #[test]
+    fn test_select_preference() {
+        let sources = vec![
+            source("480", Some(480), Some(300_000_000)),
+            source("1080", Some(1080), Some(900_000_000)),
+            source("720", Some(720), Some(250_000_000)),
+        ];
+        let pick = |p: QualityPreference| p.select(&sources).map(|s| s.url.as_str());
+
+        assert_eq!(pick(QualityPreference::Highest), Some("1080"));
+        assert_eq!(pick(QualityPreference::Lowest), Some("480"));
+        assert_eq!(pick(QualityPreference::Closest { height: 600 }), Some("720"));
+        assert_eq!(pick(QualityPreference::Closest { height: 2160 }), Some("1080"));
+        assert_eq!(pick(QualityPreference::Smallest), Some("720"));
+
+        // Without any hints the first candidate is kept
+        let unknown = vec![source("a", None, None), source("b", None, None)];
+        assert_eq!(QualityPreference::Highest.select(&unknown).unwrap().url, "a");
+    }
+
+    #[test]
+    fn test_describe_source() {
+        let s = describe_source("https://cdn.example.com/ep1.mp4".into(), r#"{"label": "720p", "bitrate": 2500, "filesize": 412000000}"#);
+        assert_eq!((s.resolution, s.bitrate, s.size), (Some(720), Some(2_500_000), Some(412_000_000)));
+
+        let s = describe_source("https://cdn.example.com/ep1.mp4".into(), r#"<source src="x" type="video/mp4" size="1080">"#);
+        assert_eq!(s.resolution, Some(1080));
+
+        let s = describe_source("https://cdn.example.com/ep1_480p.mp4".into(), "");
+        assert_eq!(s.resolution, Some(480));
+    }
+}
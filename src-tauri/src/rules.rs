@@ -0,0 +1,564 @@
+//! User-defined sites for mirrors, declared in `sites.json` in the app config directory.
+//!
+//! Each rule names a site, the hosts it handles, URL templates for its series and episode pages,
+//! and optional CSS selectors and regexes for the title, episode count and video URLs. Rules are
+//! compiled into `RuleSite` extractors; a rule with errors is skipped and reported, the others
+//! still load. The file is re-read whenever it changes on disk.
+//!
+//! ```json
+//! { "sites": [{
+//!     "name": "mirror",
+//!     "baseUrl": "https://mirror.example/",
+//!     "domains": ["mirror.example", "*.mirror-cdn.example"],
+//!     "seriesUrl": "{base}series/{id}",
+//!     "episodeUrl": "{base}series/{id}/ep/{ep}",
+//!     "titleSelector": "h1.series-title",
+//!     "episodeCountPattern": "(\\d+) episodes",
+//!     "videoUrlPatterns": ["\"ep(?P<ep>\\d+)\"\\s*:\\s*\"(?P<url>https?:[^\"]+\\.m3u8)\""]
+//! }] }
+//! ```
+
+use crate::error::ParserError;
+use crate::parser::{unescape_url, ResolvedEpisodes, RongyokParser};
+use crate::quality::{describe_source, Provenance, VideoSource};
+use crate::sites::{host_of, with_trailing_slash, SiteExtractor, BUILT_IN_DOMAINS, BUILT_IN_SITES};
+use futures_util::future::BoxFuture;
+use regex::Regex;
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// One site as written in the rules file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct SiteRule {
+    pub name: String,
+    pub base_url: String,
+    /// Hosts handled by this site; "*.example.com" also matches subdomains. Defaults to the
+    /// base URL's host and its subdomains.
+    pub domains: Vec<String>,
+    /// Series page, with {base} and {id} filled in
+    pub series_url: String,
+    /// Episode page ({base}, {id}, {ep}), fetched when the series page has no video URLs
+    pub episode_url: Option<String>,
+    /// Catalog search page ({base}, {query}); defaults to WordPress-style `?s=`
+    pub search_url: Option<String>,
+    /// Reads the series id from a URL (first capture group); defaults to the built-in formats
+    pub series_id_pattern: Option<String>,
+    /// Reads the episode number from a URL (first capture group)
+    pub episode_pattern: Option<String>,
+    pub title_selector: Option<String>,
+    /// Elements counted to get the number of episodes
+    pub episode_count_selector: Option<String>,
+    /// Reads the number of episodes from the page (first capture group); tried before the selector
+    pub episode_count_pattern: Option<String>,
+    /// Video URLs on series and episode pages: the `url` group (or first group, or whole match)
+    /// is the URL and an optional `ep` group the episode number
+    pub video_url_patterns: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RulesFile {
+    sites: Vec<SiteRule>,
+}
+
+/// A problem found while loading the rules file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleIssue {
+    /// Site name (or "sites[3]" when unnamed); None for problems with the file as a whole
+    pub site: Option<String>,
+    pub field: Option<String>,
+    pub message: String,
+    /// Position of a JSON syntax error
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+/// Outcome of loading or validating the rules file
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesReport {
+    pub path: String,
+    pub exists: bool,
+    /// Sites that compiled cleanly
+    pub sites: Vec<String>,
+    pub issues: Vec<RuleIssue>,
+}
+
+/// Host pattern from the `domains` list
+#[derive(Debug, Clone)]
+enum DomainPattern {
+    Exact(String),
+    /// "*.example.com": example.com and any subdomain
+    Wildcard(String),
+}
+
+impl DomainPattern {
+    fn parse(pattern: &str) -> Self {
+        let pattern = pattern.trim().to_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => DomainPattern::Wildcard(domain.to_string()),
+            None => DomainPattern::Exact(pattern),
+        }
+    }
+
+    fn matches(&self, host: &str) -> bool {
+        match self {
+            DomainPattern::Exact(domain) => host == domain,
+            DomainPattern::Wildcard(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+        }
+    }
+
+    /// Whether this pattern takes any host of `domain` or its subdomains
+    fn overlaps(&self, domain: &str) -> bool {
+        let (DomainPattern::Exact(host) | DomainPattern::Wildcard(host)) = self;
+        host == domain || host.ends_with(&format!(".{}", domain)) || self.matches(domain)
+    }
+}
+
+/// A site compiled from a rule
+pub struct RuleSite {
+    name: String,
+    base_url: String,
+    domains: Vec<DomainPattern>,
+    series_url: String,
+    episode_url: Option<String>,
+    search_url: Option<String>,
+    series_id_pattern: Option<Regex>,
+    episode_pattern: Option<Regex>,
+    title_selector: Option<Selector>,
+    episode_count_selector: Option<Selector>,
+    episode_count_pattern: Option<Regex>,
+    video_url_patterns: Vec<Regex>,
+}
+
+impl RuleSite {
+    /// Check every field of a rule, reporting all problems rather than the first
+    pub fn compile(rule: SiteRule, label: &str) -> Result<Self, Vec<RuleIssue>> {
+        let mut issues = Vec::new();
+        let mut issue = |field: &str, message: String| {
+            issues.push(RuleIssue {
+                site: Some(label.to_string()),
+                field: Some(field.to_string()),
+                message,
+                line: None,
+                column: None,
+            });
+        };
+
+        if rule.name.trim().is_empty() {
+            issue("name", "Site name is required".to_string());
+        }
+        match Url::parse(&rule.base_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() => {}
+            Ok(_) => issue("baseUrl", format!("Not an http(s) URL: {}", rule.base_url)),
+            Err(e) => issue("baseUrl", format!("Invalid URL {:?}: {}", rule.base_url, e)),
+        }
+        check_template(&mut issue, "seriesUrl", Some(&rule.series_url), &["{id}"]);
+        check_template(&mut issue, "episodeUrl", rule.episode_url.as_deref(), &["{id}", "{ep}"]);
+        check_template(&mut issue, "searchUrl", rule.search_url.as_deref(), &["{query}"]);
+        if rule.domains.iter().any(|d| d.trim().is_empty() || d.contains('/')) {
+            issue("domains", "Domains are bare hosts such as \"example.com\" or \"*.example.com\"".to_string());
+        }
+
+        let base_url = with_trailing_slash(&rule.base_url);
+        let domains: Vec<DomainPattern> = if rule.domains.is_empty() {
+            host_of(&base_url).map(|h| DomainPattern::Wildcard(h.trim_start_matches("www.").to_string())).into_iter().collect()
+        } else {
+            rule.domains.iter().map(|d| DomainPattern::parse(d)).collect()
+        };
+        // Rule sites are consulted first, so they must not take links from a built-in site
+        if let Some(domain) = BUILT_IN_DOMAINS.iter().find(|b| domains.iter().any(|d| d.overlaps(b))) {
+            let field = if rule.domains.is_empty() { "baseUrl" } else { "domains" };
+            issue(field, format!("{} belongs to a built-in site", domain));
+        }
+
+        let series_id_pattern = compile_regex(&mut issue, "seriesIdPattern", rule.series_id_pattern.as_deref(), true);
+        let episode_pattern = compile_regex(&mut issue, "episodePattern", rule.episode_pattern.as_deref(), true);
+        let episode_count_pattern = compile_regex(&mut issue, "episodeCountPattern", rule.episode_count_pattern.as_deref(), true);
+        let video_url_patterns: Vec<Regex> = rule
+            .video_url_patterns
+            .iter()
+            .filter_map(|p| compile_regex(&mut issue, "videoUrlPatterns", Some(p), false))
+            .collect();
+        let title_selector = compile_selector(&mut issue, "titleSelector", rule.title_selector.as_deref());
+        let episode_count_selector = compile_selector(&mut issue, "episodeCountSelector", rule.episode_count_selector.as_deref());
+
+        if !issues.is_empty() {
+            return Err(issues);
+        }
+
+        Ok(Self {
+            name: rule.name.trim().to_string(),
+            base_url,
+            domains,
+            series_url: rule.series_url,
+            episode_url: rule.episode_url,
+            search_url: rule.search_url,
+            series_id_pattern,
+            episode_pattern,
+            title_selector,
+            episode_count_selector,
+            episode_count_pattern,
+            video_url_patterns,
+        })
+    }
+
+    fn fill(&self, template: &str, series_id: i32, episode: i32) -> String {
+        template
+            .replace("{base}", &self.base_url)
+            .replace("{id}", &series_id.to_string())
+            .replace("{ep}", &episode.to_string())
+    }
+
+    /// Video URLs matched by the rule's patterns, per episode where the pattern says
+    fn pattern_sources(&self, html: &str) -> Vec<(Option<i32>, VideoSource)> {
+        let mut found: Vec<(Option<i32>, VideoSource)> = Vec::new();
+        for pattern in &self.video_url_patterns {
+            for caps in pattern.captures_iter(html) {
+                let Some(url) = caps.name("url").or_else(|| caps.get(1)).or_else(|| caps.get(0)) else { continue };
+                let url = unescape_url(url.as_str());
+                if found.iter().any(|(_, s)| s.url == url) {
+                    continue;
+                }
+                let episode = caps.name("ep").and_then(|ep| ep.as_str().parse().ok());
+                let source = VideoSource { provenance: Provenance::Rule, ..describe_source(url, &caps[0]) };
+                found.push((episode, source));
+            }
+        }
+        found
+    }
+}
+
+impl SiteExtractor for RuleSite {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        host_of(url).is_some_and(|host| self.domains.iter().any(|d| d.matches(&host)))
+    }
+
+    fn parse_series_id(&self, url: &str) -> Option<i32> {
+        match &self.series_id_pattern {
+            Some(pattern) => pattern.captures(url)?.get(1)?.as_str().parse().ok(),
+            None => RongyokParser::parse_series_url(url),
+        }
+    }
+
+    fn parse_episode(&self, url: &str) -> Option<i32> {
+        match &self.episode_pattern {
+            Some(pattern) => pattern.captures(url)?.get(1)?.as_str().parse().ok(),
+            None => RongyokParser::parse_episode_url(url),
+        }
+    }
+
+    fn series_url(&self, series_id: i32, _original_url: Option<&str>) -> String {
+        self.fill(&self.series_url, series_id, 1)
+    }
+
+    fn search_url(&self, query: &str) -> String {
+        match &self.search_url {
+            Some(template) => template.replace("{base}", &self.base_url).replace("{query}", &encode_query(query)),
+            None => format!("{}?s={}", self.base_url, encode_query(query)),
+        }
+    }
+
+    fn extract_title(&self, document: &Html) -> Option<String> {
+        let element = document.select(self.title_selector.as_ref()?).next()?;
+        let title = element.text().collect::<String>().trim().to_string();
+        (!title.is_empty()).then_some(title)
+    }
+
+    fn extract_total_episodes(&self, document: &Html, html: &str) -> Option<i32> {
+        if let Some(count) = self
+            .episode_count_pattern
+            .as_ref()
+            .and_then(|p| p.captures(html)?.get(1)?.as_str().parse().ok())
+        {
+            return Some(count);
+        }
+        let count = document.select(self.episode_count_selector.as_ref()?).count() as i32;
+        (count > 0).then_some(count)
+    }
+
+    fn extract_video_sources(&self, html: &str) -> Vec<(Option<i32>, VideoSource)> {
+        self.pattern_sources(html)
+    }
+
+    fn resolve_episodes<'a>(
+        &'a self,
+        parser: &'a RongyokParser,
+        series_id: i32,
+        html: &'a str,
+        total_episodes: i32,
+    ) -> BoxFuture<'a, Result<ResolvedEpisodes, ParserError>> {
+        Box::pin(async move {
+            // The rule's own patterns first, then the built-in extraction
+            let mut episode_sources: HashMap<i32, Vec<VideoSource>> = HashMap::new();
+            for (episode, source) in self.pattern_sources(html) {
+                if let Some(ep) = episode {
+                    episode_sources.entry(ep).or_default().push(source);
+                }
+            }
+            for (ep, sources) in parser.extract_all_episode_sources(html) {
+                let known = episode_sources.entry(ep).or_default();
+                for source in sources {
+                    if !known.iter().any(|s| s.url == source.url) {
+                        known.push(source);
+                    }
+                }
+            }
+
+            match &self.episode_url {
+                Some(template) if episode_sources.is_empty() => {
+                    let pages = (1..=total_episodes).map(|ep| (ep, self.fill(template, series_id, ep))).collect();
+                    Ok(parser.fetch_episode_page_urls(self, pages).await)
+                }
+                _ => Ok(ResolvedEpisodes::from_page(episode_sources, total_episodes)),
+            }
+        })
+    }
+}
+
+/// Compile a rules file. Rules with errors are left out and reported; a JSON syntax error
+/// leaves out everything.
+pub fn compile_rules(json: &str) -> (Vec<RuleSite>, Vec<RuleIssue>) {
+    let file: RulesFile = match serde_json::from_str(json) {
+        Ok(file) => file,
+        Err(e) => {
+            let issue = RuleIssue {
+                site: None,
+                field: None,
+                message: e.to_string(),
+                line: Some(e.line()),
+                column: Some(e.column()),
+            };
+            return (Vec::new(), vec![issue]);
+        }
+    };
+
+    let mut sites: Vec<RuleSite> = Vec::new();
+    let mut issues = Vec::new();
+    for (i, rule) in file.sites.into_iter().enumerate() {
+        let label = if rule.name.trim().is_empty() { format!("sites[{}]", i) } else { rule.name.trim().to_string() };
+        if BUILT_IN_SITES.iter().any(|name| name.eq_ignore_ascii_case(&label)) {
+            issues.push(RuleIssue {
+                site: Some(label),
+                field: Some("name".to_string()),
+                message: "This name belongs to a built-in site".to_string(),
+                line: None,
+                column: None,
+            });
+            continue;
+        }
+        if sites.iter().any(|s| s.name == label) {
+            issues.push(RuleIssue {
+                site: Some(label),
+                field: Some("name".to_string()),
+                message: "Another site already uses this name".to_string(),
+                line: None,
+                column: None,
+            });
+            continue;
+        }
+        match RuleSite::compile(rule, &label) {
+            Ok(site) => sites.push(site),
+            Err(rule_issues) => issues.extend(rule_issues),
+        }
+    }
+    (sites, issues)
+}
+
+/// The rules file on disk, remembering when it was last loaded
+pub struct SiteRulesFile {
+    path: PathBuf,
+    loaded_modified: Mutex<Option<SystemTime>>,
+}
+
+impl SiteRulesFile {
+    /// `sites.json` in the app config directory
+    pub fn new() -> Self {
+        let path = dirs::config_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("com.rongyok.downloader")
+            .join("sites.json");
+        Self::with_path(path)
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path, loaded_modified: Mutex::new(None) }
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.path).and_then(|m| m.modified()).ok()
+    }
+
+    /// Whether the file was created, edited or removed since it was last loaded
+    pub fn changed(&self) -> bool {
+        *self.loaded_modified.lock().unwrap() != self.modified()
+    }
+
+    /// Read and compile the file; a missing file means no rule sites
+    pub fn load(&self) -> (Vec<Arc<dyn SiteExtractor>>, RulesReport) {
+        *self.loaded_modified.lock().unwrap() = self.modified();
+        let contents = std::fs::read_to_string(&self.path).ok();
+        let (sites, report) = self.compile(contents.as_deref());
+        let sites = sites.into_iter().map(|s| Arc::new(s) as Arc<dyn SiteExtractor>).collect();
+        (sites, report)
+    }
+
+    /// Check `contents` (or the file on disk) without loading it
+    pub fn validate(&self, contents: Option<&str>) -> RulesReport {
+        let on_disk = match contents {
+            Some(_) => None,
+            None => std::fs::read_to_string(&self.path).ok(),
+        };
+        self.compile(contents.or(on_disk.as_deref())).1
+    }
+
+    fn compile(&self, contents: Option<&str>) -> (Vec<RuleSite>, RulesReport) {
+        let (sites, issues) = contents.map(compile_rules).unwrap_or_default();
+        let report = RulesReport {
+            path: self.path.to_string_lossy().to_string(),
+            exists: self.path.exists(),
+            sites: sites.iter().map(|s| s.name.clone()).collect(),
+            issues,
+        };
+        (sites, report)
+    }
+}
+
+fn check_template(issue: &mut impl FnMut(&str, String), field: &str, template: Option<&str>, placeholders: &[&str]) {
+    let Some(template) = template else { return };
+    for placeholder in placeholders {
+        if !template.contains(placeholder) {
+            issue(field, format!("Template must contain {}", placeholder));
+        }
+    }
+    let filled = template.replace("{base}", "https://example.com/").replace(['{', '}'], "");
+    if !template.contains("{base}") && Url::parse(&filled).is_err() {
+        issue(field, format!("Not a URL and doesn't start from {{base}}: {}", template));
+    }
+}
+
+fn compile_regex(issue: &mut impl FnMut(&str, String), field: &str, pattern: Option<&str>, needs_group: bool) -> Option<Regex> {
+    let pattern = pattern?;
+    match Regex::new(pattern) {
+        Ok(regex) if needs_group && regex.captures_len() < 2 => {
+            issue(field, format!("Pattern needs a capture group: {}", pattern));
+            None
+        }
+        Ok(regex) => Some(regex),
+        Err(e) => {
+            issue(field, e.to_string());
+            None
+        }
+    }
+}
+
+fn compile_selector(issue: &mut impl FnMut(&str, String), field: &str, selector: Option<&str>) -> Option<Selector> {
+    let selector = selector?;
+    match Selector::parse(selector) {
+        Ok(selector) => Some(selector),
+        Err(e) => {
+            issue(field, format!("Invalid CSS selector {:?}: {}", selector, e));
+            None
+        }
+    }
+}
+
+/// Percent-encode a search keyword for a query string
+fn encode_query(query: &str) -> String {
+    query
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b' ' => "+".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sites::ExtractorRegistry;
+    use crate::test_support::FixtureServer;
+
+    #[test]
+    fn test_compile_rules_reports_issues() {
+        let json = r#"{"sites": [
+            {"name": "good", "baseUrl": "https://good.example", "seriesUrl": "{base}s/{id}"},
+            {"name": "bad", "baseUrl": "ftp://bad.example", "seriesUrl": "{base}s/",
+             "seriesIdPattern": "id=\\d+", "titleSelector": "h1[", "videoUrlPatterns": ["(unclosed"]},
+            {"name": "good", "baseUrl": "https://other.example", "seriesUrl": "{base}{id}"},
+            {"name": "rongyok", "baseUrl": "https://mirror.example", "seriesUrl": "{base}{id}"},
+            {"name": "clone", "baseUrl": "https://m.thongyok.com", "seriesUrl": "{base}{id}"},
+            {"name": "greedy", "baseUrl": "https://greedy.example", "domains": ["*.com"], "seriesUrl": "{base}{id}"}
+        ]}"#;
+        let (sites, issues) = compile_rules(json);
+        assert_eq!(sites.iter().map(|s| s.name()).collect::<Vec<_>>(), vec!["good"]);
+
+        let fields: Vec<_> = issues.iter().map(|i| (i.site.as_deref().unwrap(), i.field.as_deref().unwrap())).collect();
+        assert_eq!(fields, vec![
+            ("bad", "baseUrl"),
+            ("bad", "seriesUrl"),
+            ("bad", "seriesIdPattern"),
+            ("bad", "videoUrlPatterns"),
+            ("bad", "titleSelector"),
+            ("good", "name"),
+            ("rongyok", "name"),
+            ("clone", "baseUrl"),
+            ("greedy", "domains"),
+        ]);
+        assert_eq!(issues[7].message, "thongyok.com belongs to a built-in site");
+
+        let (sites, issues) = compile_rules("{\"sites\": [\n  {\"name\": \"x\",}\n]}");
+        assert!(sites.is_empty());
+        assert_eq!((issues[0].line, issues[0].site.as_deref()), (Some(2), None));
+    }
+
+    #[tokio::test]
+    async fn test_rule_site_end_to_end() {
+        let server = FixtureServer::bind();
+        let series = r#"<html><h1 class="name">Mirror Drama</h1><p>2 episodes</p></html>"#;
+        let episode = |ep: u32| format!(r#"<script>load({{"stream": "https:\/\/cdn.mirror.example\/{}.m3u8"}})</script>"#, ep);
+        let base = server
+            .bytes("/show/42", "text/html", series.as_bytes())
+            .bytes("/show/42/ep-1", "text/html", episode(1).as_bytes())
+            .bytes("/show/42/ep-2", "text/html", episode(2).as_bytes())
+            .start();
+
+        let json = format!(
+            r#"{{"sites": [{{"name": "mirror", "baseUrl": "{}", "seriesUrl": "{{base}}show/{{id}}",
+                "episodeUrl": "{{base}}show/{{id}}/ep-{{ep}}", "seriesIdPattern": "/show/(\\d+)",
+                "episodePattern": "/ep-(\\d+)", "titleSelector": "h1.name", "episodeCountPattern": "(\\d+) episodes",
+                "videoUrlPatterns": ["\"stream\":\\s*\"([^\"]+)\""]}}]}}"#,
+            base
+        );
+        let (sites, issues) = compile_rules(&json);
+        assert!(issues.is_empty(), "{:?}", issues);
+
+        let parser = RongyokParser::with_registry(ExtractorRegistry::new());
+        parser.set_rule_sites(sites.into_iter().map(|s| Arc::new(s) as Arc<dyn SiteExtractor>).collect());
+
+        let target = parser.resolve_target(&format!("{}show/42/ep-2", base)).await.unwrap();
+        assert_eq!((target.site.as_str(), target.series_id, target.episode), ("mirror", 42, Some(2)));
+
+        let info = parser.get_series_info(42, Some(&target.series_url)).await.unwrap();
+        assert_eq!((info.title.as_str(), info.total_episodes), ("Mirror Drama", 2));
+        assert_eq!(info.episode_urls[&2], "https://cdn.mirror.example/2.m3u8");
+        assert_eq!(info.episodes[1].sources[0].provenance, Provenance::Rule);
+    }
+}
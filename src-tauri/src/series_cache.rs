@@ -0,0 +1,257 @@
+//! On-disk cache of fetched series info.
+//!
+//! Each series is stored as JSON under the app cache directory, keyed by site and series id,
+//! together with the series page's ETag / Last-Modified so an expired entry can be revalidated
+//! instead of re-scraped. Entries also remember which episodes were downloaded and where, so a
+//! recently viewed series can be reopened offline with its downloaded episodes marked.
+
+use crate::error::StorageError;
+use crate::parser::{PageValidators, SeriesInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a cached series is served without asking the site, unless the caller says otherwise
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A cached series as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub series: SeriesInfo,
+    /// Unix seconds when the series was last scraped or confirmed unchanged
+    pub fetched_at: u64,
+    /// Unix seconds when the series was last opened
+    pub viewed_at: u64,
+    pub validators: PageValidators,
+    /// Downloaded episodes and the file holding each (the merged file after a merge)
+    #[serde(default)]
+    pub downloaded: BTreeMap<i32, String>,
+}
+
+impl CacheEntry {
+    pub fn is_fresh(&self, max_age: Duration) -> bool {
+        now().saturating_sub(self.fetched_at) < max_age.as_secs()
+    }
+
+    /// Downloaded episodes whose files are still on disk
+    pub fn downloaded_episodes(&self) -> Vec<i32> {
+        self.downloaded
+            .iter()
+            .filter(|(_, path)| Path::new(path).exists())
+            .map(|(episode, _)| *episode)
+            .collect()
+    }
+
+    pub fn into_info(self, offline: bool) -> CachedSeriesInfo {
+        CachedSeriesInfo {
+            downloaded_episodes: self.downloaded_episodes(),
+            fetched_at: self.fetched_at,
+            offline,
+            target_episode: None,
+            series: self.series,
+        }
+    }
+}
+
+/// Series info served to the UI, with where it came from
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedSeriesInfo {
+    #[serde(flatten)]
+    pub series: SeriesInfo,
+    pub fetched_at: u64,
+    /// The site could not be reached and this is an expired cached copy
+    pub offline: bool,
+    pub downloaded_episodes: Vec<i32>,
+    /// Episode the fetched link pointed at, for the UI to preselect
+    pub target_episode: Option<i32>,
+}
+
+/// One cached series in the "recently viewed" list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedSeriesSummary {
+    pub series_id: i32,
+    pub site: String,
+    pub source_url: String,
+    pub title: String,
+    pub poster_url: Option<String>,
+    pub total_episodes: i32,
+    pub fetched_at: u64,
+    pub viewed_at: u64,
+    /// Older than the max age; opening it will revalidate with the site
+    pub expired: bool,
+    pub downloaded_episodes: Vec<i32>,
+}
+
+pub struct SeriesCache {
+    dir: PathBuf,
+}
+
+impl SeriesCache {
+    /// Cache in the app cache directory (falls back to the system temp dir)
+    pub fn new() -> Self {
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("com.rongyok.downloader")
+            .join("series");
+        Self::with_dir(dir)
+    }
+
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn get(&self, site: &str, series_id: i32) -> Option<CacheEntry> {
+        let json = std::fs::read_to_string(self.entry_path(site, series_id)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Store freshly scraped series info, keeping the download records of an older entry.
+    /// The entry is returned along with the write result, so a failed write can be reported
+    /// without losing the series.
+    pub fn store(&self, series: SeriesInfo, validators: PageValidators) -> (CacheEntry, Result<(), StorageError>) {
+        let downloaded = self
+            .get(&series.site, series.series_id)
+            .map(|entry| entry.downloaded)
+            .unwrap_or_default();
+        let entry = CacheEntry {
+            series,
+            fetched_at: now(),
+            viewed_at: now(),
+            validators,
+            downloaded,
+        };
+        let written = self.write(&entry);
+        (entry, written)
+    }
+
+    /// Mark an entry as viewed, and as confirmed current when `revalidated`
+    pub fn touch(&self, mut entry: CacheEntry, revalidated: bool) -> (CacheEntry, Result<(), StorageError>) {
+        entry.viewed_at = now();
+        if revalidated {
+            entry.fetched_at = now();
+        }
+        let written = self.write(&entry);
+        (entry, written)
+    }
+
+    /// Remember where downloaded episodes were saved
+    pub fn record_downloads(&self, site: &str, series_id: i32, files: &[(i32, String)]) -> Result<(), StorageError> {
+        let Some(mut entry) = self.get(site, series_id) else { return Ok(()) };
+        entry.downloaded.extend(files.iter().cloned());
+        self.write(&entry)
+    }
+
+    /// Every cached series, most recently viewed first
+    pub fn list(&self, max_age: Duration) -> Vec<CachedSeriesSummary> {
+        let Ok(dir) = std::fs::read_dir(&self.dir) else { return Vec::new() };
+        let mut summaries: Vec<CachedSeriesSummary> = dir
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|e| serde_json::from_str::<CacheEntry>(&std::fs::read_to_string(e.path()).ok()?).ok())
+            .map(|entry| CachedSeriesSummary {
+                series_id: entry.series.series_id,
+                site: entry.series.site.clone(),
+                source_url: entry.series.source_url.clone(),
+                title: entry.series.title.clone(),
+                poster_url: entry.series.poster_url.clone(),
+                total_episodes: entry.series.total_episodes,
+                fetched_at: entry.fetched_at,
+                viewed_at: entry.viewed_at,
+                expired: !entry.is_fresh(max_age),
+                downloaded_episodes: entry.downloaded_episodes(),
+            })
+            .collect();
+
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.viewed_at));
+        summaries
+    }
+
+    /// Drop a series from the cache; returns whether it was cached
+    pub fn evict(&self, site: &str, series_id: i32) -> Result<bool, StorageError> {
+        let path = self.entry_path(site, series_id);
+        match std::fs::remove_file(&path) {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(StorageError::write(&path, e)),
+        }
+    }
+
+    fn write(&self, entry: &CacheEntry) -> Result<(), StorageError> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| StorageError::write(&self.dir, e))?;
+        let path = self.entry_path(&entry.series.site, entry.series.series_id);
+        let json = serde_json::to_string(entry).map_err(|e| StorageError::write(&path, e.into()))?;
+        std::fs::write(&path, json).map_err(|e| StorageError::write(&path, e))
+    }
+
+    fn entry_path(&self, site: &str, series_id: i32) -> PathBuf {
+        // Site names come from extractors; keep them from escaping the cache directory
+        let site: String = site.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_').collect();
+        self.dir.join(format!("{}-{}.json", site, series_id))
+    }
+}
+
+/// Current Unix time in seconds
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::RongyokParser;
+    use crate::sites::{ExtractorRegistry, RongyokSite};
+    use crate::test_support::FixtureServer;
+
+    #[tokio::test]
+    async fn test_cache_revalidates_and_tracks_downloads() {
+        let base = FixtureServer::bind()
+            .page("/watch/?series_id=1004", "rongyok_series.html")
+            .with_etag("/watch/?series_id=1004", "\"s1\"")
+            .start();
+        let mut registry = ExtractorRegistry::new();
+        registry.register(Box::new(RongyokSite::with_base_url(&base)));
+        let parser = RongyokParser::with_registry(registry);
+
+        let dir = std::env::temp_dir().join(format!("series-cache-test-{}", std::process::id()));
+        let cache = SeriesCache::with_dir(dir.clone());
+
+        let (series, validators) = parser
+            .get_series_info_if_modified(1004, None, &PageValidators::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(validators.etag.as_deref(), Some("\"s1\""));
+        let (entry, written) = cache.store(series, validators);
+        assert_eq!(written, Ok(()));
+        assert!(entry.is_fresh(DEFAULT_MAX_AGE));
+        assert!(!entry.is_fresh(Duration::ZERO));
+
+        // The stored validators turn the next fetch into a 304
+        let cached = cache.get("rongyok", 1004).unwrap();
+        assert!(parser.get_series_info_if_modified(1004, None, &cached.validators).await.unwrap().is_none());
+
+        // Only downloads still on disk count, and they survive a re-scrape
+        let file = dir.join("ep_001.mp4");
+        std::fs::write(&file, b"video").unwrap();
+        let files = [(1, file.to_string_lossy().to_string()), (2, dir.join("gone.mp4").to_string_lossy().to_string())];
+        cache.record_downloads("rongyok", 1004, &files).unwrap();
+        cache.store(cached.series, cached.validators).1.unwrap();
+
+        let listed = cache.list(DEFAULT_MAX_AGE);
+        assert_eq!(listed.len(), 1);
+        assert_eq!((listed[0].title.as_str(), listed[0].expired), ("รักนี้ต้องห้าม", false));
+        assert_eq!(listed[0].downloaded_episodes, vec![1]);
+
+        assert_eq!(cache.evict("rongyok", 1004), Ok(true));
+        assert_eq!(cache.evict("rongyok", 1004), Ok(false));
+        assert!(cache.list(DEFAULT_MAX_AGE).is_empty());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
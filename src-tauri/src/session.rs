@@ -0,0 +1,209 @@
+//! Persistent cookie jar shared by the parser and the downloader.
+//!
+//! Cookies set by the sites (logins, age gates) are kept in one store that every HTTP client
+//! uses, saved as JSON in the app data directory. Browser sessions can be brought over by
+//! importing a Netscape-format cookies.txt export.
+
+use crate::error::{CookieError, StorageError};
+use reqwest::{ClientBuilder, Url};
+use reqwest_cookie_store::CookieStoreMutex;
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A stored cookie as shown to the user (values are left out)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieInfo {
+    pub domain: String,
+    pub path: String,
+    pub name: String,
+    /// Unix timestamp, or None for a session cookie
+    pub expires: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+/// Cookie jar handle; clones share the same store
+#[derive(Clone, Default)]
+pub struct Session {
+    jar: Arc<CookieStoreMutex>,
+    /// Where the jar is saved; None keeps it in memory only
+    path: Option<PathBuf>,
+}
+
+impl Session {
+    /// Session saved in the app data directory, loaded from disk if present
+    pub fn load() -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("com.rongyok.downloader")
+            .join("cookies.json");
+        Self::with_file(path)
+    }
+
+    /// Session saved at `path`. An unreadable file starts an empty jar.
+    pub fn with_file(path: PathBuf) -> Self {
+        let store = std::fs::File::open(&path)
+            .ok()
+            .and_then(|file| cookie_store::serde::json::load(BufReader::new(file)).ok())
+            .unwrap_or_default();
+
+        Self {
+            jar: Arc::new(CookieStoreMutex::new(store)),
+            path: Some(path),
+        }
+    }
+
+    /// Attach the shared jar to an HTTP client
+    pub fn apply(&self, builder: ClientBuilder) -> ClientBuilder {
+        builder.cookie_provider(self.jar.clone())
+    }
+
+    /// Write the jar to disk, session cookies included so imported logins survive a restart
+    pub fn save(&self) -> Result<(), StorageError> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| StorageError::write(dir, e))?;
+        }
+
+        let file = std::fs::File::create(path).map_err(|e| StorageError::write(path, e))?;
+        let store = self.jar.lock().unwrap();
+        cookie_store::serde::json::save_incl_expired_and_nonpersistent(&store, &mut BufWriter::new(file))
+            .map_err(|e| StorageError::Write { path: path.to_string_lossy().to_string(), message: e.to_string() })
+    }
+
+    /// Unexpired cookies in the jar, sorted by domain and name
+    pub fn cookies(&self) -> Vec<CookieInfo> {
+        let store = self.jar.lock().unwrap();
+        let mut cookies: Vec<CookieInfo> = store
+            .iter_unexpired()
+            .map(|cookie| CookieInfo {
+                domain: String::from(&cookie.domain),
+                path: cookie.path.as_ref().to_string(),
+                name: cookie.name().to_string(),
+                expires: match cookie.expires {
+                    cookie_store::CookieExpiration::AtUtc(at) => Some(at.unix_timestamp()),
+                    cookie_store::CookieExpiration::SessionEnd => None,
+                },
+                secure: cookie.secure().unwrap_or(false),
+                http_only: cookie.http_only().unwrap_or(false),
+            })
+            .collect();
+
+        cookies.sort_by(|a, b| (&a.domain, &a.name).cmp(&(&b.domain, &b.name)));
+        cookies
+    }
+
+    /// Remove every cookie, on disk too
+    pub fn clear(&self) -> Result<(), StorageError> {
+        self.jar.lock().unwrap().clear();
+        self.save()
+    }
+
+    /// Add cookies from a Netscape cookies.txt export; returns how many were imported.
+    /// Expired entries are skipped, malformed lines are an error.
+    pub fn import_netscape(&self, text: &str) -> Result<usize, CookieError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        let mut parsed = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            // curl and browser extensions mark HttpOnly cookies with a comment-like prefix
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(rest) => (rest, true),
+                None => (line, false),
+            };
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, include_subdomains, path, secure, expires, name, value] = fields[..] else {
+                return Err(CookieError::InvalidLine {
+                    line: number + 1,
+                    reason: "expected 7 tab-separated fields".to_string(),
+                });
+            };
+            let expires: i64 = expires
+                .parse()
+                .map_err(|_| CookieError::InvalidLine { line: number + 1, reason: "invalid expiry".to_string() })?;
+            if expires != 0 && expires <= now {
+                continue;
+            }
+
+            let host = domain.trim_start_matches('.');
+            let secure = secure.eq_ignore_ascii_case("TRUE");
+            let mut set_cookie = format!("{}={}; Path={}", name, value, path);
+            if include_subdomains.eq_ignore_ascii_case("TRUE") {
+                set_cookie.push_str(&format!("; Domain={}", host));
+            }
+            if expires != 0 {
+                set_cookie.push_str(&format!("; Max-Age={}", expires - now));
+            }
+            if secure {
+                set_cookie.push_str("; Secure");
+            }
+            if http_only {
+                set_cookie.push_str("; HttpOnly");
+            }
+
+            let url = Url::parse(&format!("{}://{}{}", if secure { "https" } else { "http" }, host, path))
+                .map_err(|_| CookieError::InvalidLine { line: number + 1, reason: format!("invalid domain {}", domain) })?;
+            parsed.push((set_cookie, url));
+        }
+
+        let imported = {
+            let mut store = self.jar.lock().unwrap();
+            parsed
+                .iter()
+                .filter(|(set_cookie, url)| store.parse(set_cookie, url).is_ok())
+                .count()
+        };
+        self.save()?;
+        Ok(imported)
+    }
+}
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session").field("path", &self.path).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_netscape_and_persist() {
+        let path = std::env::temp_dir().join(format!("rongyok-cookies-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let cookies_txt = "# Netscape HTTP Cookie File\n\
+            .rongyok.com\tTRUE\t/\tTRUE\t4102444800\twordpress_logged_in\tabc123\n\
+            #HttpOnly_thongyok.com\tFALSE\t/\tFALSE\t0\tPHPSESSID\txyz\n\
+            rongyok.com\tFALSE\t/\tFALSE\t1000\told\tgone\n";
+
+        let session = Session::with_file(path.clone());
+        assert_eq!(session.import_netscape(cookies_txt), Ok(2));
+        assert!(session.import_netscape("rongyok.com\tTRUE\t/").is_err());
+
+        // Reloading from disk keeps both, including the session cookie
+        let reloaded = Session::with_file(path.clone()).cookies();
+        let found: Vec<_> = reloaded.iter().map(|c| (c.domain.as_str(), c.name.as_str(), c.http_only)).collect();
+        assert_eq!(found, vec![("rongyok.com", "wordpress_logged_in", false), ("thongyok.com", "PHPSESSID", true)]);
+        // Expiry goes through Max-Age, so allow for the clock ticking during the import
+        assert!(reloaded[0].expires.is_some_and(|e| (e - 4102444800).abs() <= 2));
+        assert_eq!(reloaded[1].expires, None);
+
+        session.clear().unwrap();
+        assert!(Session::with_file(path.clone()).cookies().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
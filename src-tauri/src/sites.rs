@@ -0,0 +1,328 @@
+use crate::catalog::{extract_listing_page, extract_series_cards, SeriesPage, SeriesSummary};
+use crate::error::ParserError;
+use crate::parser::{PageFetch, PageValidators, ResolvedEpisodes, RongyokParser};
+use crate::quality::VideoSource;
+use futures_util::future::BoxFuture;
+use reqwest::Url;
+use scraper::Html;
+use std::sync::Arc;
+
+/// A site (or mirror) the parser knows how to scrape.
+///
+/// Each implementation decides which URLs it handles, how series ids are
+/// read from them, which page to fetch for a series and how episode video
+/// URLs are resolved from that page.
+pub trait SiteExtractor: Send + Sync {
+    /// Short identifier, e.g. "rongyok"
+    fn name(&self) -> &str;
+
+    /// Site root (with trailing slash), also sent as the Referer
+    fn base_url(&self) -> &str;
+
+    /// Check whether this extractor handles the given URL
+    fn matches(&self, url: &str) -> bool {
+        match (host_of(url), host_of(self.base_url())) {
+            (Some(host), Some(own)) => host == own || host.ends_with(&format!(".{}", own)),
+            _ => false,
+        }
+    }
+
+    /// Extract series_id from URL
+    fn parse_series_id(&self, url: &str) -> Option<i32> {
+        RongyokParser::parse_series_url(url)
+    }
+
+    /// Extract the episode number from an episode link
+    fn parse_episode(&self, url: &str) -> Option<i32> {
+        RongyokParser::parse_episode_url(url)
+    }
+
+    /// URL of the page that lists the series and its episodes
+    fn series_url(&self, series_id: i32, original_url: Option<&str>) -> String;
+
+    /// Catalog search page for a keyword (WordPress-style `?s=` by default)
+    fn search_url(&self, query: &str) -> String {
+        match Url::parse(self.base_url()) {
+            Ok(mut url) => {
+                url.query_pairs_mut().append_pair("s", query);
+                url.to_string()
+            }
+            Err(_) => format!("{}?s={}", self.base_url(), query),
+        }
+    }
+
+    /// Series listed on a search results or catalog page
+    fn parse_series_list(&self, html: &str, page_url: &str) -> Vec<SeriesSummary> {
+        extract_series_cards(html, page_url, self)
+    }
+
+    /// Series and pagination of a genre, category or "latest updates" page
+    fn parse_listing_page(&self, html: &str, page_url: &str) -> SeriesPage {
+        extract_listing_page(html, page_url, self)
+    }
+
+    /// Series title from the series page, when the site knows better than `<title>`
+    fn extract_title(&self, _document: &Html) -> Option<String> {
+        None
+    }
+
+    /// Episode count from the series page, when the site knows better than the built-in heuristics
+    fn extract_total_episodes(&self, _document: &Html, _html: &str) -> Option<i32> {
+        None
+    }
+
+    /// Video sources this site finds on a series or episode page before the built-in extraction,
+    /// with the episode each belongs to when the page says
+    fn extract_video_sources(&self, _html: &str) -> Vec<(Option<i32>, VideoSource)> {
+        Vec::new()
+    }
+
+    /// Fetch the series page HTML, unless it still matches the cached copy's `validators`
+    fn fetch_series_page<'a>(
+        &'a self,
+        parser: &'a RongyokParser,
+        url: &'a str,
+        validators: &'a PageValidators,
+    ) -> BoxFuture<'a, Result<PageFetch, ParserError>> {
+        Box::pin(parser.fetch_page_if_modified(url, self.base_url(), validators))
+    }
+
+    /// Resolve video URLs for every episode of the series, reporting per-episode outcomes
+    fn resolve_episodes<'a>(
+        &'a self,
+        parser: &'a RongyokParser,
+        series_id: i32,
+        html: &'a str,
+        total_episodes: i32,
+    ) -> BoxFuture<'a, Result<ResolvedEpisodes, ParserError>>;
+}
+
+/// rongyok.com - all episode URLs are embedded in the series page
+pub struct RongyokSite {
+    base_url: String,
+}
+
+impl RongyokSite {
+    pub fn new() -> Self {
+        Self::with_base_url("https://rongyok.com/")
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            base_url: with_trailing_slash(base_url),
+        }
+    }
+}
+
+impl SiteExtractor for RongyokSite {
+    fn name(&self) -> &str {
+        "rongyok"
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn series_url(&self, series_id: i32, _original_url: Option<&str>) -> String {
+        format!("{}watch/?series_id={}", self.base_url, series_id)
+    }
+
+    fn resolve_episodes<'a>(
+        &'a self,
+        parser: &'a RongyokParser,
+        _series_id: i32,
+        html: &'a str,
+        total_episodes: i32,
+    ) -> BoxFuture<'a, Result<ResolvedEpisodes, ParserError>> {
+        Box::pin(async move {
+            let episode_sources = parser.extract_all_episode_sources(html);
+            Ok(ResolvedEpisodes::from_page(episode_sources, total_episodes))
+        })
+    }
+}
+
+/// thongyok.com - video URLs usually live on the individual /watch/{id}/{ep} pages
+pub struct ThongyokSite {
+    base_url: String,
+}
+
+impl ThongyokSite {
+    pub fn new() -> Self {
+        Self::with_base_url("https://thongyok.com/")
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            base_url: with_trailing_slash(base_url),
+        }
+    }
+
+    fn watch_url(&self, series_id: i32, episode: i32) -> String {
+        format!("{}watch/{}/{}", self.base_url, series_id, episode)
+    }
+}
+
+impl SiteExtractor for ThongyokSite {
+    fn name(&self) -> &str {
+        "thongyok"
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn series_url(&self, series_id: i32, original_url: Option<&str>) -> String {
+        match original_url {
+            // Keep the title slug of a series link, but fetch it from the main domain
+            Some(orig) if self.matches(orig) && orig.contains("/series/") => rehost(orig, &self.base_url),
+            _ => format!("{}series/{}", self.base_url, series_id),
+        }
+    }
+
+    fn resolve_episodes<'a>(
+        &'a self,
+        parser: &'a RongyokParser,
+        series_id: i32,
+        html: &'a str,
+        total_episodes: i32,
+    ) -> BoxFuture<'a, Result<ResolvedEpisodes, ParserError>> {
+        Box::pin(async move {
+            let episode_sources = parser.extract_all_episode_sources(html);
+            if !episode_sources.is_empty() {
+                return Ok(ResolvedEpisodes::from_page(episode_sources, total_episodes));
+            }
+
+            let pages = (1..=total_episodes)
+                .map(|ep| (ep, self.watch_url(series_id, ep)))
+                .collect();
+            Ok(parser.fetch_episode_page_urls(self, pages).await)
+        })
+    }
+}
+
+/// Names of the sites built into the app, which rules files can't reuse
+pub const BUILT_IN_SITES: [&str; 2] = ["rongyok", "thongyok"];
+/// Hosts the built-in sites serve (with their subdomains), which rules files can't claim
+pub const BUILT_IN_DOMAINS: [&str; 2] = ["rongyok.com", "thongyok.com"];
+
+/// Registered site extractors, consulted in registration order. Sites from the user's rules
+/// file are kept apart so they can be swapped on reload; they are consulted first.
+pub struct ExtractorRegistry {
+    sites: Vec<Arc<dyn SiteExtractor>>,
+    rule_sites: Vec<Arc<dyn SiteExtractor>>,
+}
+
+impl ExtractorRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self { sites: Vec::new(), rule_sites: Vec::new() }
+    }
+
+    /// Add a site extractor. The first registered site is the fallback for unknown URLs.
+    pub fn register(&mut self, site: Box<dyn SiteExtractor>) {
+        self.sites.push(Arc::from(site));
+    }
+
+    /// Replace the sites loaded from the rules file
+    pub fn set_rule_sites(&mut self, sites: Vec<Arc<dyn SiteExtractor>>) {
+        self.rule_sites = sites;
+    }
+
+    /// Look up an extractor by name, e.g. "thongyok"
+    pub fn get(&self, name: &str) -> Option<Arc<dyn SiteExtractor>> {
+        self.iter().find(|s| s.name() == name)
+    }
+
+    /// All extractors, rule sites first, then in registration order
+    pub fn iter(&self) -> impl Iterator<Item = Arc<dyn SiteExtractor>> + '_ {
+        self.rule_sites.iter().chain(&self.sites).cloned()
+    }
+
+    /// Find the extractor that handles the given URL
+    pub fn find(&self, url: &str) -> Option<Arc<dyn SiteExtractor>> {
+        self.iter().find(|s| s.matches(url))
+    }
+
+    /// Find the extractor for a URL, falling back to the first registered site
+    pub fn resolve(&self, url: Option<&str>) -> Option<Arc<dyn SiteExtractor>> {
+        url.and_then(|u| self.find(u))
+            .or_else(|| self.sites.first().or(self.rule_sites.first()).cloned())
+    }
+}
+
+impl Default for ExtractorRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(RongyokSite::new()));
+        registry.register(Box::new(ThongyokSite::new()));
+        registry
+    }
+}
+
+/// Lowercased host of a URL, without port
+pub(crate) fn host_of(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, r)| r)?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_lowercase())
+    }
+}
+
+/// `url` moved onto the scheme, host and port of `base`, e.g. from a mobile subdomain
+fn rehost(url: &str, base: &str) -> String {
+    if host_of(url) == host_of(base) {
+        return url.to_string();
+    }
+    match (Url::parse(url), Url::parse(base)) {
+        (Ok(mut moved), Ok(base)) => {
+            let _ = moved.set_scheme(base.scheme());
+            let _ = moved.set_host(base.host_str());
+            let _ = moved.set_port(base.port());
+            moved.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
+
+pub(crate) fn with_trailing_slash(url: &str) -> String {
+    if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{}/", url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_find() {
+        let registry = ExtractorRegistry::default();
+
+        assert_eq!(registry.find("https://rongyok.com/watch/?series_id=1004").as_deref().map(|s| s.name()), Some("rongyok"));
+        assert_eq!(registry.find("https://www.thongyok.com/series/1004/title").as_deref().map(|s| s.name()), Some("thongyok"));
+        assert!(registry.find("https://other.com/series/1004").is_none());
+
+        // Unknown sites fall back to the first registered extractor
+        assert_eq!(registry.resolve(Some("https://other.com/1004")).as_deref().map(|s| s.name()), Some("rongyok"));
+        assert_eq!(registry.resolve(None).as_deref().map(|s| s.name()), Some("rongyok"));
+    }
+
+    #[test]
+    fn test_custom_base_url() {
+        let site = ThongyokSite::with_base_url("http://127.0.0.1:8080");
+
+        assert!(site.matches("http://127.0.0.1:8080/series/7"));
+        assert_eq!(site.series_url(7, None), "http://127.0.0.1:8080/series/7");
+        assert_eq!(site.watch_url(7, 3), "http://127.0.0.1:8080/watch/7/3");
+
+        // Episode and mobile links map back to the series page on the configured host
+        assert_eq!(site.series_url(7, Some("http://127.0.0.1:8080/watch/7/3")), "http://127.0.0.1:8080/series/7");
+        let site = ThongyokSite::new();
+        assert_eq!(site.series_url(7, Some("https://m.thongyok.com/series/7/title")), "https://thongyok.com/series/7/title");
+    }
+}
@@ -0,0 +1,234 @@
+//! Video sources read from structured data rather than matched in raw HTML: schema.org JSON-LD
+//! blocks and JSON embedded in inline `<script>` tags (player configs, episode lists).
+//!
+//! Values are deserialised with serde, so JSON escaping is handled properly. The parser merges
+//! these with its regex matches, keeping each URL's `Provenance`.
+
+use crate::quality::{describe_source, Provenance, VideoSource};
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::sync::LazyLock;
+
+/// Keys that hold an episode number
+const EPISODE_KEYS: [&str; 5] = ["ep", "episode", "episode_number", "episodeNumber", "number"];
+/// Keys that hold a video URL in player configs and episode lists
+const URL_KEYS: [&str; 8] = ["video_url", "videoUrl", "file", "src", "url", "contentUrl", "hls", "dash"];
+/// Absolute URL of a progressive video, HLS playlist or DASH manifest
+static MEDIA_URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^https?://[^\s]+?\.(?:mp4|m3u8|mpd)(?:[?#]|$)").unwrap());
+
+/// A video source found in structured data, with the episode it belongs to when known
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuredSource {
+    pub episode: Option<i32>,
+    pub source: VideoSource,
+}
+
+/// schema.org node; only the parts that lead to a video are read
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct LdNode {
+    #[serde(rename = "@graph")]
+    graph: Vec<LdNode>,
+    episode_number: Option<NumberOrText>,
+    content_url: Option<String>,
+    height: Option<NumberOrText>,
+    bitrate: Option<NumberOrText>,
+    content_size: Option<NumberOrText>,
+    #[serde(alias = "episodes")]
+    episode: OneOrMany<LdNode>,
+    video: OneOrMany<LdNode>,
+    has_part: OneOrMany<LdNode>,
+    contains_season: OneOrMany<LdNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(Box<T>),
+    Many(Vec<T>),
+}
+
+impl<T> Default for OneOrMany<T> {
+    fn default() -> Self {
+        OneOrMany::Many(Vec::new())
+    }
+}
+
+impl<T> OneOrMany<T> {
+    fn iter(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        match self {
+            OneOrMany::One(item) => Box::new(std::iter::once(item.as_ref())),
+            OneOrMany::Many(items) => Box::new(items.iter()),
+        }
+    }
+}
+
+/// schema.org numbers are often written as strings, e.g. "height": "720"
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NumberOrText {
+    Number(f64),
+    Text(String),
+}
+
+impl NumberOrText {
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            NumberOrText::Number(n) if *n >= 0.0 => Some(*n as u64),
+            NumberOrText::Number(_) => None,
+            NumberOrText::Text(s) => s.trim().trim_end_matches(|c: char| c.is_alphabetic()).trim().parse().ok(),
+        }
+    }
+}
+
+/// Every video source in the page's JSON-LD and inline script JSON
+pub fn extract_structured_sources(html: &str) -> Vec<StructuredSource> {
+    let document = Html::parse_document(html);
+    let mut found = Vec::new();
+
+    let ld_selector = Selector::parse("script[type='application/ld+json']").unwrap();
+    for script in document.select(&ld_selector) {
+        if let Ok(node) = serde_json::from_str::<LdNode>(&script.text().collect::<String>()) {
+            collect_json_ld(&node, None, &mut found);
+        }
+    }
+
+    let script_selector = Selector::parse("script:not([type='application/ld+json']):not([src])").unwrap();
+    for script in document.select(&script_selector) {
+        for value in embedded_json(&script.text().collect::<String>()) {
+            collect_script_json(&value, None, &mut found);
+        }
+    }
+
+    found
+}
+
+fn collect_json_ld(node: &LdNode, episode: Option<i32>, found: &mut Vec<StructuredSource>) {
+    let episode = node.episode_number.as_ref().and_then(|n| n.as_u64()).map(|n| n as i32).or(episode);
+
+    if let Some(url) = node.content_url.as_deref().filter(|u| is_media_url(u)) {
+        let mut source = describe_source(url.to_string(), "");
+        source.resolution = node.height.as_ref().and_then(|h| h.as_u64()).map(|h| h as u32).or(source.resolution);
+        source.bitrate = node.bitrate.as_ref().and_then(|b| b.as_u64()).or(source.bitrate);
+        source.size = node.content_size.as_ref().and_then(|s| s.as_u64()).or(source.size);
+        source.provenance = Provenance::JsonLd;
+        found.push(StructuredSource { episode, source });
+    }
+
+    let children = node.graph.iter()
+        .chain(node.episode.iter())
+        .chain(node.video.iter())
+        .chain(node.has_part.iter())
+        .chain(node.contains_season.iter());
+    for child in children {
+        collect_json_ld(child, episode, found);
+    }
+}
+
+/// Walk a script JSON value; objects pass their episode number down to nested sources
+fn collect_script_json(value: &Value, episode: Option<i32>, found: &mut Vec<StructuredSource>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_script_json(item, episode, found);
+            }
+        }
+        Value::Object(object) => {
+            let episode = EPISODE_KEYS.iter().find_map(|key| json_episode(object.get(*key)?)).or(episode);
+
+            for key in URL_KEYS {
+                let Some(url) = object.get(key).and_then(Value::as_str).filter(|u| is_media_url(u)) else { continue };
+                // Quality hints come from the object's own scalar fields, read like markup attributes
+                let mut source = describe_source(url.to_string(), &Value::Object(scalar_fields(object)).to_string());
+                source.provenance = Provenance::ScriptJson;
+                found.push(StructuredSource { episode, source });
+            }
+
+            for child in object.values().filter(|v| v.is_array() || v.is_object()) {
+                collect_script_json(child, episode, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// JSON objects and arrays embedded in a script, e.g. `var config = {...};` or `init([...])`.
+/// Text that isn't valid JSON (JavaScript object literals, code) is skipped.
+fn embedded_json(script: &str) -> Vec<Value> {
+    let mut values = Vec::new();
+    let mut rest = script;
+
+    while let Some(start) = rest.find(['{', '[']) {
+        let candidate = &rest[start..];
+        let mut stream = serde_json::Deserializer::from_str(candidate).into_iter::<Value>();
+        match stream.next() {
+            Some(Ok(value @ (Value::Object(_) | Value::Array(_)))) => {
+                rest = &candidate[stream.byte_offset()..];
+                values.push(value);
+            }
+            _ => rest = &candidate[1..],
+        }
+    }
+    values
+}
+
+fn scalar_fields(object: &Map<String, Value>) -> Map<String, Value> {
+    object
+        .iter()
+        .filter(|(_, v)| !v.is_array() && !v.is_object())
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+fn json_episode(value: &Value) -> Option<i32> {
+    match value {
+        Value::Number(n) => n.as_i64().map(|n| n as i32),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Absolute URL of a progressive video, HLS playlist or DASH manifest
+fn is_media_url(url: &str) -> bool {
+    MEDIA_URL_RE.is_match(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixture;
+
+    #[test]
+    fn test_extract_structured_sources() {
+        let html = r#"<script type="application/ld+json">
+            {"@context": "https://schema.org", "@type": "TVSeries", "episode": [
+              {"@type": "TVEpisode", "episodeNumber": "2", "video": {"@type": "VideoObject",
+                "contentUrl": "https://cdn.example.com/v/2.mp4", "height": "720", "encodingFormat": "video/mp4"}}
+            ]}
+          </script>
+          <script>
+            jwplayer("player").setup({sources: [{"file": "https://cdn.example.com/v/1_480p.mp4"}]});
+            var playerConfig = {"ep": 1, "sources": [{"file": "https:\/\/cdn.example.com\/v\/1.m3u8", "label": "1080p"}]};
+          </script>"#;
+
+        let found = extract_structured_sources(html);
+        let summary: Vec<_> = found
+            .iter()
+            .map(|f| (f.episode, f.source.url.as_str(), f.source.resolution, f.source.provenance))
+            .collect();
+        assert_eq!(summary, vec![
+            (Some(2), "https://cdn.example.com/v/2.mp4", Some(720), Provenance::JsonLd),
+            // The JavaScript object literal around the first array still yields the array itself
+            (None, "https://cdn.example.com/v/1_480p.mp4", Some(480), Provenance::ScriptJson),
+            (Some(1), "https://cdn.example.com/v/1.m3u8", Some(1080), Provenance::ScriptJson),
+        ]);
+
+        // Subtitle files next to the episode URLs are not video sources
+        let found = extract_structured_sources(&fixture("rongyok_series.html"));
+        let episodes: Vec<_> = found.iter().map(|f| f.episode).collect();
+        assert_eq!(episodes, vec![Some(1), Some(2), Some(4)]);
+    }
+}
@@ -0,0 +1,235 @@
+//! Subscriptions to series that are still airing.
+//!
+//! Each subscription is re-checked on its own interval. Episodes that show up on the site and
+//! haven't been downloaded or reported yet are announced with a `new-episodes` event, and
+//! downloaded straight away when the subscription has download settings.
+
+use crate::error::StorageError;
+use crate::quality::QualityPreference;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Shortest allowed polling interval, so a typo doesn't hammer the site
+pub const MIN_INTERVAL_MINS: u64 = 15;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    pub site: String,
+    pub series_id: i32,
+    pub title: String,
+    pub source_url: String,
+    /// Minutes between checks
+    pub interval_mins: u64,
+    /// Download new episodes with these settings; `None` only reports them
+    #[serde(default)]
+    pub download: Option<SubscriptionDownload>,
+    /// Episodes already available or announced, which don't count as new again
+    #[serde(default)]
+    pub reported: BTreeSet<i32>,
+    /// Unix seconds of the last check
+    #[serde(default)]
+    pub last_checked: Option<u64>,
+}
+
+/// Download settings saved with a subscription, in the same shape as a batch's options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionDownload {
+    pub output_dir: String,
+    pub auto_merge: bool,
+    pub concurrent_downloads: i32,
+    /// KB/s, 0 = unlimited
+    pub speed_limit: i32,
+    pub file_naming: String,
+    #[serde(default)]
+    pub quality: QualityPreference,
+    #[serde(default)]
+    pub download_subtitles: bool,
+    #[serde(default)]
+    pub embed_subtitles: bool,
+}
+
+impl Subscription {
+    pub fn is_due(&self, now: u64) -> bool {
+        self.last_checked.is_none_or(|t| now >= t + self.interval_mins * 60)
+    }
+
+    /// Available episodes that are neither downloaded nor reported yet; they are marked as reported
+    pub fn take_new_episodes(&mut self, available: &[i32], downloaded: &[i32]) -> Vec<i32> {
+        let new: Vec<i32> = available
+            .iter()
+            .copied()
+            .filter(|ep| !downloaded.contains(ep) && !self.reported.contains(ep))
+            .collect();
+        self.reported.extend(available.iter().copied());
+        new
+    }
+}
+
+/// Payload of the `new-episodes` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewEpisodes {
+    pub site: String,
+    pub series_id: i32,
+    pub title: String,
+    pub episodes: Vec<i32>,
+    /// The episodes were queued for download with the subscription's settings
+    pub enqueued: bool,
+}
+
+/// The subscription list, persisted in the app data directory
+pub struct Subscriptions {
+    path: PathBuf,
+    list: Mutex<Vec<Subscription>>,
+}
+
+impl Subscriptions {
+    pub fn new() -> (Self, Option<StorageError>) {
+        let path = dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("com.rongyok.downloader")
+            .join("subscriptions.json");
+        Self::with_path(path)
+    }
+
+    /// Load the list, starting empty when there is no file. A file that can't be read or
+    /// parsed is reported; one that doesn't parse is first moved aside so the next save
+    /// doesn't overwrite it.
+    pub fn with_path(path: PathBuf) -> (Self, Option<StorageError>) {
+        let (list, error) = match std::fs::read_to_string(&path) {
+            Ok(json) => match serde_json::from_str(&json) {
+                Ok(list) => (list, None),
+                Err(e) => (Vec::new(), Some(back_up_corrupt(&path, e))),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (Vec::new(), None),
+            Err(e) => (Vec::new(), Some(StorageError::read(&path, e))),
+        };
+        (Self { path, list: Mutex::new(list) }, error)
+    }
+
+    pub fn list(&self) -> Vec<Subscription> {
+        self.list.lock().unwrap().clone()
+    }
+
+    /// Add a subscription, or replace the one for the same series
+    pub fn upsert(&self, subscription: Subscription) -> Result<(), StorageError> {
+        let mut list = self.list.lock().unwrap();
+        list.retain(|s| !(s.site == subscription.site && s.series_id == subscription.series_id));
+        list.push(subscription);
+        self.save(&list)
+    }
+
+    /// Remove a subscription; returns whether there was one
+    pub fn remove(&self, site: &str, series_id: i32) -> Result<bool, StorageError> {
+        let mut list = self.list.lock().unwrap();
+        let before = list.len();
+        list.retain(|s| !(s.site == site && s.series_id == series_id));
+        let removed = list.len() != before;
+        if removed {
+            self.save(&list)?;
+        }
+        Ok(removed)
+    }
+
+    /// Subscriptions whose interval has passed
+    pub fn due(&self, now: u64) -> Vec<Subscription> {
+        self.list.lock().unwrap().iter().filter(|s| s.is_due(now)).cloned().collect()
+    }
+
+    /// Store the outcome of a check, unless the subscription was removed in the meantime
+    pub fn update(&self, checked: &Subscription) -> Result<(), StorageError> {
+        let mut list = self.list.lock().unwrap();
+        let Some(existing) = list.iter_mut().find(|s| s.site == checked.site && s.series_id == checked.series_id) else {
+            return Ok(());
+        };
+        existing.title = checked.title.clone();
+        existing.reported = checked.reported.clone();
+        existing.last_checked = checked.last_checked;
+        self.save(&list)
+    }
+
+    fn save(&self, list: &[Subscription]) -> Result<(), StorageError> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| StorageError::write(dir, e))?;
+        }
+        let json = serde_json::to_string_pretty(list).map_err(|e| StorageError::write(&self.path, e.into()))?;
+        std::fs::write(&self.path, json).map_err(|e| StorageError::write(&self.path, e))
+    }
+}
+
+/// Move an unparseable file to `<name>.corrupt`
+fn back_up_corrupt(path: &Path, error: serde_json::Error) -> StorageError {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".corrupt");
+    let backup = PathBuf::from(backup);
+    if let Err(e) = std::fs::rename(path, &backup) {
+        return StorageError::write(&backup, e);
+    }
+    StorageError::Corrupt {
+        path: path.to_string_lossy().to_string(),
+        backup: backup.to_string_lossy().to_string(),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriptions_report_each_episode_once() {
+        let path = std::env::temp_dir().join(format!("subscriptions-test-{}.json", std::process::id()));
+        let (subscriptions, error) = Subscriptions::with_path(path.clone());
+        assert_eq!(error, None);
+        subscriptions
+            .upsert(Subscription {
+                site: "rongyok".to_string(),
+                series_id: 1004,
+                title: "Weekly".to_string(),
+                source_url: "https://rongyok.com/watch/?series_id=1004".to_string(),
+                interval_mins: 60,
+                download: None,
+                // Episodes on the site when subscribing aren't new
+                reported: BTreeSet::from([1, 2, 3]),
+                last_checked: Some(1_000),
+            })
+            .unwrap();
+
+        assert!(subscriptions.due(1_000 + 59 * 60).is_empty());
+        let mut due = subscriptions.due(1_000 + 60 * 60).pop().unwrap();
+
+        // Episode 4 landed; 2 was downloaded in the meantime and 3 is still pending
+        assert_eq!(due.take_new_episodes(&[1, 2, 3, 4], &[1, 2]), vec![4]);
+        assert!(due.take_new_episodes(&[1, 2, 3, 4], &[1, 2]).is_empty());
+        due.last_checked = Some(5_000);
+        subscriptions.update(&due).unwrap();
+
+        // The list survives a restart
+        let (reloaded, _) = Subscriptions::with_path(path.clone());
+        assert_eq!(reloaded.list()[0].reported, BTreeSet::from([1, 2, 3, 4]));
+        assert_eq!(reloaded.list()[0].last_checked, Some(5_000));
+        assert!(reloaded.remove("rongyok", 1004).unwrap());
+        assert!(reloaded.list().is_empty());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_backed_up() {
+        let path = std::env::temp_dir().join(format!("subscriptions-corrupt-{}.json", std::process::id()));
+        std::fs::write(&path, "[{\"site\": ").unwrap();
+
+        let (subscriptions, error) = Subscriptions::with_path(path.clone());
+        assert!(subscriptions.list().is_empty());
+        let backup = format!("{}.corrupt", path.to_string_lossy());
+        assert!(matches!(error, Some(StorageError::Corrupt { backup: ref b, .. }) if *b == backup));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "[{\"site\": ");
+        assert!(!path.exists());
+
+        std::fs::remove_file(backup).ok();
+    }
+}
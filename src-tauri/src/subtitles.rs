@@ -0,0 +1,267 @@
+//! Subtitle tracks: discovery on series/episode pages, WebVTT to SRT conversion,
+//! sidecar files next to downloaded episodes and joining them for merged videos.
+
+use crate::metadata::join_url;
+use crate::parser::unescape_url;
+use crate::quality::source_context;
+use regex::Regex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A subtitle file referenced by a page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleTrack {
+    pub url: String,
+    /// Language code as given by the page, e.g. "th" or "en"
+    pub language: Option<String>,
+    /// Human readable label, e.g. "ไทย"
+    pub label: Option<String>,
+}
+
+/// Find subtitle tracks in `<track>` tags and inline JSON. Each track comes with the
+/// episode number found next to it, if any.
+pub fn extract_subtitle_tracks(html: &str, page_url: &str) -> Vec<(Option<i32>, SubtitleTrack)> {
+    let base = Url::parse(page_url).ok();
+    let mut tracks: Vec<(Option<i32>, SubtitleTrack)> = Vec::new();
+
+    // <track kind="subtitles" src="..." srclang="th" label="ไทย">
+    let track_re = Regex::new(r"(?is)<track\b[^>]*>").unwrap();
+    let attr_re = Regex::new(r#"(?i)([a-z-]+)\s*=\s*["']([^"']*)["']"#).unwrap();
+
+    for tag in track_re.find_iter(html) {
+        let attrs: HashMap<String, String> = attr_re
+            .captures_iter(tag.as_str())
+            .map(|c| (c[1].to_lowercase(), c[2].to_string()))
+            .collect();
+        let kind = attrs.get("kind").map(|k| k.to_lowercase());
+        if kind.is_some_and(|k| k != "subtitles" && k != "captions") {
+            continue;
+        }
+        let Some(url) = attrs.get("src").and_then(|src| join_url(base.as_ref(), &unescape_url(src))) else { continue };
+
+        push_track(&mut tracks, None, SubtitleTrack {
+            url,
+            language: attrs.get("srclang").cloned().filter(|l| !l.is_empty()),
+            label: attrs.get("label").cloned().filter(|l| !l.is_empty()),
+        });
+    }
+
+    // {"ep": 3, "subtitles": [{"lang": "th", "label": "ไทย", "file": "https://.../3.th.vtt"}]}
+    let url_re = Regex::new(r#"(?i)https?:(?:\\/\\/|//)[^"'<>\s]+?\.(?:vtt|srt)(?:\?[^"'<>\s\\]*)?"#).unwrap();
+    let lang_re = Regex::new(r#""(?:srclang|lang|language)"\s*:\s*"([^"]+)""#).unwrap();
+    let label_re = Regex::new(r#""label"\s*:\s*"([^"]+)""#).unwrap();
+    let ep_re = Regex::new(r#""(?:ep|episode|episode_number)"\s*:\s*"?(\d+)"#).unwrap();
+
+    for m in url_re.find_iter(html) {
+        let context = source_context(html, m.start(), m.end());
+        // Tracks nested inside an episode object: look back for the closest episode number
+        let preceding = &html[floor_char_boundary(html, m.start().saturating_sub(400))..m.start()];
+        let episode = ep_re
+            .captures(context)
+            .or_else(|| ep_re.captures_iter(preceding).last())
+            .and_then(|c| c[1].parse().ok());
+
+        push_track(&mut tracks, episode, SubtitleTrack {
+            url: unescape_url(m.as_str()),
+            language: lang_re.captures(context).map(|c| c[1].to_string()),
+            label: label_re.captures(context).map(|c| c[1].to_string()),
+        });
+    }
+
+    tracks
+}
+
+/// Convert WebVTT to SRT: numbered cues, comma decimal separators, no cue settings or
+/// WebVTT-only markup. SRT input is returned unchanged.
+pub fn to_srt(text: &str) -> String {
+    let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    if !text.trim_start().starts_with("WEBVTT") {
+        return text;
+    }
+
+    let markup_re = Regex::new(r"<(/?)([a-zA-Z0-9.:]+)[^>]*>").unwrap();
+    let mut srt = String::new();
+    let mut index = 0;
+
+    for block in text.split("\n\n") {
+        let lines: Vec<&str> = block.lines().collect();
+        // Header, NOTE and STYLE blocks have no timing line
+        let Some(pos) = lines.iter().position(|l| l.contains("-->")) else { continue };
+        let Some((start, end)) = parse_timing(lines[pos]) else { continue };
+
+        let body = lines[pos + 1..].join("\n");
+        let body = markup_re.replace_all(&body, |c: &regex::Captures| match &c[2] {
+            "i" | "b" | "u" => format!("<{}{}>", &c[1], &c[2]),
+            _ => String::new(),
+        });
+
+        index += 1;
+        srt.push_str(&format!("{}\n{} --> {}\n{}\n\n", index, format_time(start), format_time(end), body.trim()));
+    }
+
+    srt
+}
+
+/// Join several SRT files into one, shifting each by its offset in seconds
+pub fn combine_srt(parts: &[(String, f64)]) -> String {
+    let mut combined = String::new();
+    let mut index = 0;
+
+    for (text, offset) in parts {
+        let text = text.replace("\r\n", "\n");
+        for block in text.split("\n\n") {
+            let lines: Vec<&str> = block.lines().collect();
+            let Some(pos) = lines.iter().position(|l| l.contains("-->")) else { continue };
+            let Some((start, end)) = parse_timing(lines[pos]) else { continue };
+
+            index += 1;
+            combined.push_str(&format!(
+                "{}\n{} --> {}\n{}\n\n",
+                index,
+                format_time(start + offset),
+                format_time(end + offset),
+                lines[pos + 1..].join("\n")
+            ));
+        }
+    }
+
+    combined
+}
+
+/// Sidecar path for a track next to `video`: `ep_001.th.srt`, or `ep_001.srt` without a language.
+/// Paths already in `taken` get a numeric suffix on the language part.
+pub fn subtitle_path(video: &Path, language: Option<&str>, taken: &[PathBuf]) -> PathBuf {
+    let stem = video.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let language: String = language
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+
+    let name = |n: usize| match (language.is_empty(), n) {
+        (true, 0) => format!("{}.srt", stem),
+        (true, n) => format!("{}.{}.srt", stem, n + 1),
+        (false, 0) => format!("{}.{}.srt", stem, language),
+        (false, n) => format!("{}.{}-{}.srt", stem, language, n + 1),
+    };
+
+    (0..)
+        .map(|n| video.with_file_name(name(n)))
+        .find(|path| !taken.contains(path))
+        .unwrap()
+}
+
+/// Sidecar SRT files written for `video`, with the language part of their names
+pub fn sidecar_subtitles(video: &Path) -> Vec<(Option<String>, PathBuf)> {
+    let (Some(dir), Some(stem)) = (video.parent(), video.file_stem()) else { return Vec::new() };
+    let stem = stem.to_string_lossy().to_string();
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+
+    let mut sidecars: Vec<(Option<String>, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let base = name.strip_suffix(".srt")?;
+            if base == stem {
+                return Some((None, path));
+            }
+            let language = base.strip_prefix(&stem)?.strip_prefix('.')?;
+            Some((Some(language.to_string()), path))
+        })
+        .collect();
+
+    sidecars.sort();
+    sidecars
+}
+
+fn push_track(tracks: &mut Vec<(Option<i32>, SubtitleTrack)>, episode: Option<i32>, track: SubtitleTrack) {
+    if let Some(existing) = tracks.iter_mut().find(|(_, t)| t.url == track.url) {
+        // The same URL in a <track> tag and in JSON: keep whatever each one knew
+        existing.0 = existing.0.or(episode);
+        existing.1.language = existing.1.language.take().or(track.language);
+        existing.1.label = existing.1.label.take().or(track.label);
+    } else {
+        tracks.push((episode, track));
+    }
+}
+
+/// Parse a cue timing line like `00:01.000 --> 00:04.500 align:start`
+fn parse_timing(line: &str) -> Option<(f64, f64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_time(start.trim())?, parse_time(end)?))
+}
+
+/// `hh:mm:ss.ttt`, `mm:ss.ttt` or `hh:mm:ss,ttt` to seconds
+fn parse_time(value: &str) -> Option<f64> {
+    let value = value.replace(',', ".");
+    value
+        .split(':')
+        .try_fold(0.0, |total, part| Some(total * 60.0 + part.parse::<f64>().ok()?))
+}
+
+fn format_time(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixture;
+
+    #[test]
+    fn test_vtt_to_srt() {
+        let vtt = "WEBVTT\n\nNOTE translated by fans\n\n1\n00:01.000 --> 00:04.500 align:start\n<v Mei>สวัสดี</v> <i>hello</i>\n\n01:02:03.250 --> 01:02:05.000\nbye\n";
+        assert_eq!(
+            to_srt(vtt),
+            "1\n00:00:01,000 --> 00:00:04,500\nสวัสดี <i>hello</i>\n\n2\n01:02:03,250 --> 01:02:05,000\nbye\n\n"
+        );
+    }
+
+    #[test]
+    fn test_combine_srt_shifts_offsets() {
+        let first = "1\n00:00:01,000 --> 00:00:02,000\nA\n\n".to_string();
+        let second = "1\n00:00:00,500 --> 00:00:01,000\nB\n".to_string();
+        assert_eq!(
+            combine_srt(&[(first, 0.0), (second, 2700.0)]),
+            "1\n00:00:01,000 --> 00:00:02,000\nA\n\n2\n00:45:00,500 --> 00:45:01,000\nB\n\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_subtitle_tracks_fixture() {
+        let tracks = extract_subtitle_tracks(&fixture("rongyok_series.html"), "https://rongyok.com/watch/?series_id=1004");
+        let found: Vec<_> = tracks.iter().map(|(ep, t)| (*ep, t.language.as_deref(), t.url.as_str())).collect();
+        assert_eq!(found, vec![
+            (Some(1), Some("th"), "https://subs.example.com/1004/1.th.vtt"),
+            (Some(1), Some("en"), "https://subs.example.com/1004/1.en.srt"),
+        ]);
+
+        let tracks = extract_subtitle_tracks(&fixture("thongyok_watch_2.html"), "https://thongyok.com/watch/7/2");
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].1.url, "https://thongyok.com/subs/7/2.vtt");
+        assert_eq!(tracks[0].1.label.as_deref(), Some("ไทย"));
+
+        let video = Path::new("/downloads/ep_001.mp4");
+        let taken = vec![PathBuf::from("/downloads/ep_001.th.srt")];
+        assert_eq!(subtitle_path(video, Some("th"), &taken), PathBuf::from("/downloads/ep_001.th-2.srt"));
+        assert_eq!(subtitle_path(video, None, &[]), PathBuf::from("/downloads/ep_001.srt"));
+    }
+}
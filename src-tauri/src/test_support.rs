@@ -0,0 +1,203 @@
+//! Offline test helpers: saved site pages and a tiny local HTTP server that stands in for
+//! rongyok/thongyok so the parser can be exercised end to end without network access.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Placeholder in fixture files that is replaced with the local server's base URL
+const BASE_URL_PLACEHOLDER: &str = "{{BASE_URL}}";
+
+/// Load a saved page from tests/fixtures
+pub fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Missing fixture {:?}: {}", path, e))
+}
+
+struct Route {
+    content_type: String,
+    body: Vec<u8>,
+    etag: Option<String>,
+    /// Answer with a 302 to this location instead of the body
+    redirect: Option<String>,
+    /// Answer 403 unless the request carries this Referer
+    referer: Option<String>,
+    /// Answer `Range: bytes=a-b` requests with 206 and that slice of the body
+    ranges: bool,
+}
+
+/// Local HTTP/1.1 server serving fixed responses by path (including query string).
+/// Unknown paths get a 404.
+pub struct FixtureServer {
+    listener: std::net::TcpListener,
+    base_url: String,
+    routes: HashMap<String, Route>,
+}
+
+impl FixtureServer {
+    /// Bind to a free local port. Binding happens before routes are added so
+    /// fixtures can embed the final base URL.
+    pub fn bind() -> Self {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed to bind test server");
+        let port = listener.local_addr().unwrap().port();
+
+        Self {
+            listener,
+            base_url: format!("http://127.0.0.1:{}/", port),
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Base URL the server will answer on, for building pages that link back to it
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Serve an HTML fixture at `path`, with {{BASE_URL}} filled in
+    pub fn page(mut self, path: &str, fixture_name: &str) -> Self {
+        let html = fixture(fixture_name).replace(BASE_URL_PLACEHOLDER, &self.base_url);
+        self.routes.insert(path.to_string(), Route {
+            content_type: "text/html; charset=utf-8".to_string(),
+            body: html.into_bytes(),
+            etag: None,
+            redirect: None,
+            referer: None,
+            ranges: false,
+        });
+        self
+    }
+
+    /// Serve raw bytes at `path`
+    pub fn bytes(mut self, path: &str, content_type: &str, body: &[u8]) -> Self {
+        self.routes.insert(path.to_string(), Route {
+            content_type: content_type.to_string(),
+            body: body.to_vec(),
+            etag: None,
+            redirect: None,
+            referer: None,
+            ranges: false,
+        });
+        self
+    }
+
+    /// Send an ETag for an existing route and answer matching If-None-Match with 304
+    pub fn with_etag(mut self, path: &str, etag: &str) -> Self {
+        if let Some(route) = self.routes.get_mut(path) {
+            route.etag = Some(etag.to_string());
+        }
+        self
+    }
+
+    /// Only serve an existing route to requests with the given Referer, like hotlink-protected players
+    pub fn with_referer(mut self, path: &str, referer: &str) -> Self {
+        if let Some(route) = self.routes.get_mut(path) {
+            route.referer = Some(referer.to_string());
+        }
+        self
+    }
+
+    /// Advertise byte ranges for an existing route and serve partial content
+    pub fn with_ranges(mut self, path: &str) -> Self {
+        if let Some(route) = self.routes.get_mut(path) {
+            route.ranges = true;
+        }
+        self
+    }
+
+    /// Redirect `path` to `location` (absolute, or relative to the server)
+    pub fn redirect(mut self, path: &str, location: &str) -> Self {
+        self.routes.insert(path.to_string(), Route {
+            content_type: "text/plain".to_string(),
+            body: Vec::new(),
+            etag: None,
+            redirect: Some(location.to_string()),
+            referer: None,
+            ranges: false,
+        });
+        self
+    }
+
+    /// Start serving in the background (needs a Tokio runtime); returns the base URL
+    pub fn start(self) -> String {
+        self.listener.set_nonblocking(true).unwrap();
+        let listener = TcpListener::from_std(self.listener).unwrap();
+        let routes = Arc::new(self.routes);
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let routes = routes.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 8192];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+                    let header = |wanted: &str| {
+                        request.lines().find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case(wanted).then(|| value.trim())
+                        })
+                    };
+                    let if_none_match = header("if-none-match");
+                    let referer = header("referer");
+                    let range = header("range")
+                        .and_then(|r| r.strip_prefix("bytes="))
+                        .and_then(|r| r.split_once('-'))
+                        .and_then(|(start, end)| Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?)));
+
+                    let route = routes.get(path);
+                    let etag = route.and_then(|r| r.etag.as_deref());
+                    let redirect = route.and_then(|r| r.redirect.as_deref());
+                    let ranges = route.is_some_and(|r| r.ranges);
+                    let (status, content_type, body) = match route {
+                        Some(r) if r.referer.is_some() && r.referer.as_deref() != referer => {
+                            ("403 Forbidden", "text/plain", b"forbidden".as_slice())
+                        }
+                        Some(_) if redirect.is_some() => ("302 Found", "text/plain", b"".as_slice()),
+                        Some(_) if etag.is_some() && etag == if_none_match => ("304 Not Modified", "text/plain", b"".as_slice()),
+                        Some(route) if route.ranges && range.is_some_and(|(start, _)| start < route.body.len()) => {
+                            let (start, end) = range.unwrap();
+                            let end = end.min(route.body.len() - 1);
+                            ("206 Partial Content", route.content_type.as_str(), &route.body[start..=end])
+                        }
+                        Some(route) => ("200 OK", route.content_type.as_str(), route.body.as_slice()),
+                        None => ("404 Not Found", "text/plain", b"not found".as_slice()),
+                    };
+
+                    let etag_header = etag.map(|e| format!("ETag: {}\r\n", e)).unwrap_or_default();
+                    let location_header = redirect.map(|l| format!("Location: {}\r\n", l)).unwrap_or_default();
+                    let range_headers = match (ranges, range, route) {
+                        (true, Some((start, _)), Some(route)) if status.starts_with("206") => format!(
+                            "Accept-Ranges: bytes\r\nContent-Range: bytes {}-{}/{}\r\n",
+                            start,
+                            start + body.len() - 1,
+                            route.body.len()
+                        ),
+                        (true, _, _) => "Accept-Ranges: bytes\r\n".to_string(),
+                        _ => String::new(),
+                    };
+                    let header = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}{}{}Connection: close\r\n\r\n",
+                        status,
+                        content_type,
+                        body.len(),
+                        etag_header,
+                        location_header,
+                        range_headers
+                    );
+                    let _ = socket.write_all(header.as_bytes()).await;
+                    let _ = socket.write_all(body).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+
+        self.base_url
+    }
+}
+
@@ -19,6 +19,9 @@ import {
   Keyboard,
   Clock,
   AlertCircle,
+  Gauge,
+  Bell,
+  BellOff,
 } from "lucide-react";
 import {
   Button,
@@ -26,12 +29,20 @@ import {
   ProgressBar,
   EpisodeSelector,
   SeriesCard,
+  SearchResults,
+  RecentSeries,
   LogPanel,
   SettingsPanel,
+  SessionPanel,
+  ProxyPanel,
+  SiteRulesPanel,
   HistoryPanel,
   SpeedGraph,
   FileBrowser,
   DownloadQueue,
+  ProbeSummary,
+  ImportPanel,
+  SubscriptionsPanel,
   Logo,
   UpdateDialog,
   MiniMode,
@@ -44,9 +55,11 @@ import { useSpeedGraph } from "./hooks/useSpeedGraph";
 import { useUpdater } from "./hooks/useUpdater";
 import { useKeyboardShortcuts } from "./hooks/useKeyboardShortcuts";
 import { useDownloadPresets } from "./hooks/useDownloadPresets";
-import { useI18n } from "./hooks/useI18n";
+import { useI18n, translations } from "./hooks/useI18n";
 import { useCustomTheme } from "./hooks/useCustomTheme";
-import { SeriesInfo, DownloadState, DownloadProgress } from "./types";
+import { CachedSeriesInfo, CachedSeriesSummary, SeriesInfo, SeriesSummary, SeriesPage, CookieInfo, ProxySettings, ProxyTestResult, DownloadState, DownloadProgress, AppError, ErrorCode, RulesReport, SeriesProbe, ImportLine, ImportReport, DownloadRequest, DownloadOptions, Subscription, NewEpisodes } from "./types";
+import { Settings as AppSettings } from "./hooks/useSettings";
+import { ImportOptions } from "./components/ImportPanel";
 import { QueueItem } from "./components/DownloadQueue";
 import { PresetSelector } from "./components/PresetSelector";
 
@@ -54,7 +67,16 @@ interface DownloadResult {
   episode: number;
   success: boolean;
   filePath?: string;
-  error?: string;
+  error?: AppError | null;
+}
+
+// Failures that can succeed when simply retried (expired links are re-resolved by the backend)
+const RETRYABLE_ERRORS: ErrorCode[] = ["network", "url_expired", "task_failed", "disk_full"];
+
+function isRetryable(err?: AppError | null): boolean {
+  if (!err) return false;
+  if (err.code === "http_status") return Number(err.details?.status) >= 500;
+  return RETRYABLE_ERRORS.includes(err.code);
 }
 
 interface FileInfo {
@@ -67,14 +89,53 @@ interface FileInfo {
 
 type TabType = "download" | "files" | "history" | "settings" | "logs";
 
+// Episodes to select after loading a series: just the linked episode, or all of them
+function initialSelection(result: CachedSeriesInfo): Set<number> {
+  if (result.targetEpisode != null) return new Set([result.targetEpisode]);
+  return new Set(availableEpisodes(result));
+}
+
+// Episodes that can be downloaded; series cached before availability was tracked have no map
+function availableEpisodes(series: SeriesInfo): number[] {
+  return Array.from({ length: series.totalEpisodes }, (_, i) => i + 1).filter(
+    (ep) => (series.availability?.[ep]?.status ?? "available") === "available",
+  );
+}
+
+// Download options sent to the backend for imports and subscriptions
+function toDownloadOptions(settings: AppSettings, ffmpegAvailable: boolean): DownloadOptions {
+  return {
+    outputDir: settings.outputDir,
+    autoMerge: settings.autoMerge && ffmpegAvailable,
+    concurrentDownloads: settings.concurrentDownloads,
+    speedLimit: settings.speedLimit,
+    fileNaming: settings.fileNaming,
+    quality: settings.quality,
+    downloadSubtitles: settings.downloadSubtitles,
+    embedSubtitles: settings.embedSubtitles,
+  };
+}
+
 function App() {
   // State
   const [url, setUrl] = useState("");
-  const [series, setSeries] = useState<SeriesInfo | null>(null);
+  const [series, setSeries] = useState<CachedSeriesInfo | null>(null);
+  const [recentSeries, setRecentSeries] = useState<CachedSeriesSummary[]>([]);
+  const [searchResults, setSearchResults] = useState<SeriesSummary[]>([]);
+  const [nextListingPage, setNextListingPage] = useState<string | null>(null);
+  const [cookies, setCookies] = useState<CookieInfo[]>([]);
+  const [siteRules, setSiteRules] = useState<RulesReport | null>(null);
+  const [subscriptions, setSubscriptions] = useState<Subscription[]>([]);
+  const [isCheckingSubscriptions, setIsCheckingSubscriptions] = useState(false);
+  const [proxy, setProxy] = useState<ProxySettings>({ enabled: false, url: "", bypass: [] });
   const [selectedEpisodes, setSelectedEpisodes] = useState<Set<number>>(
     new Set(),
   );
   const [isFetching, setIsFetching] = useState(false);
+  const [probe, setProbe] = useState<SeriesProbe | null>(null);
+  const [importReport, setImportReport] = useState<ImportReport | null>(null);
+  const [isImporting, setIsImporting] = useState(false);
+  const [isProbing, setIsProbing] = useState(false);
   const [activeTab, setActiveTab] = useState<TabType>("download");
   const [ffmpegAvailable, setFfmpegAvailable] = useState(false);
   const [files, setFiles] = useState<FileInfo[]>([]);
@@ -148,8 +209,32 @@ function App() {
     dismissUpdate,
   } = useUpdater();
   const { language, setLanguage, t } = useI18n();
+
+  // Localised text for a backend error, falling back to its English message
+  const describeError = useCallback(
+    (e: unknown): string => {
+      if (typeof e !== "object" || e === null || !("code" in e)) return String(e);
+      const { code, message, details } = e as AppError;
+      if (!translations[`error.${code}`]) return message;
+      const params: Record<string, string | number> = {};
+      Object.entries(details ?? {}).forEach(([key, value]) => {
+        if (typeof value === "string" || typeof value === "number") params[key] = value;
+      });
+      return t(`error.${code}`, params);
+    },
+    [t],
+  );
   const { themes, activeThemeId, setActiveTheme } = useCustomTheme();
 
+  const cacheMaxAgeSecs = settings.seriesCacheHours * 3600;
+  const refreshRecent = useCallback(async () => {
+    try {
+      setRecentSeries(await invoke<CachedSeriesSummary[]>("list_cached_series", { maxAgeSecs: cacheMaxAgeSecs }));
+    } catch (e) {
+      console.error("Failed to list cached series:", e);
+    }
+  }, [cacheMaxAgeSecs]);
+
   const { presets, activePresetId, applyPreset } = useDownloadPresets(
     (newSettings) => {
       Object.entries(newSettings).forEach(([key, value]) => {
@@ -189,18 +274,16 @@ function App() {
       if (text.includes("rongyok.com") || text.includes("thongyok.com")) {
         setIsFetching(true);
         try {
-          const result = await invoke<SeriesInfo>("fetch_series", {
+          const result = await invoke<CachedSeriesInfo>("fetch_series", {
             url: text,
+            maxAgeSecs: cacheMaxAgeSecs,
           });
           setSeries(result);
-          const allEpisodes = new Set(
-            Array.from({ length: result.totalEpisodes }, (_, i) => i + 1),
-          );
-          setSelectedEpisodes(allEpisodes);
+          setSelectedEpisodes(initialSelection(result));
           success(`Loaded: ${result.title} (${result.totalEpisodes} episodes)`);
           log(`Cached ${Object.keys(result.episodeUrls).length} video URLs`);
         } catch (e) {
-          error(`Failed to fetch: ${e}`);
+          error(`Failed to fetch: ${describeError(e)}`);
         } finally {
           setIsFetching(false);
         }
@@ -208,7 +291,7 @@ function App() {
     } catch (e) {
       error("Failed to read clipboard");
     }
-  }, [log, error, success]);
+  }, [log, error, success, describeError, cacheMaxAgeSecs]);
 
   const handleStartDownload = useCallback(async () => {
     if (!series || selectedEpisodes.size === 0) {
@@ -245,6 +328,8 @@ function App() {
     setDownloadState({
       isDownloading: true,
       isPaused: false,
+      seriesId: series.seriesId,
+      site: series.site,
       currentEpisode: 0,
       completedEpisodes: [],
       failedEpisodes: [],
@@ -257,12 +342,16 @@ function App() {
       const results = await invoke<DownloadResult[]>("start_download", {
         request: {
           seriesId: series.seriesId,
+          site: series.site,
           episodes,
           outputDir: settings.outputDir,
           autoMerge: settings.autoMerge && ffmpegAvailable,
           concurrentDownloads: settings.concurrentDownloads,
           speedLimit: settings.speedLimit,
           fileNaming: settings.fileNaming,
+          quality: settings.quality,
+          downloadSubtitles: settings.downloadSubtitles,
+          embedSubtitles: settings.embedSubtitles,
           seriesTitle: series.title,
         },
       });
@@ -297,11 +386,32 @@ function App() {
         warning(
           `Downloaded ${successCount}/${episodes.length} episodes (${failCount} failed)`,
         );
+        // Leave the retryable failures selected so one click retries them
+        const retryable = results.filter((r) => !r.success && isRetryable(r.error)).map((r) => r.episode);
+        if (retryable.length > 0) {
+          setSelectedEpisodes(new Set(retryable));
+          log(`Selected ${retryable.length} episodes that can be retried`);
+        }
       }
 
+      // Mark what is now on disk; the backend records the files in the series cache
+      const downloaded = results.filter((r) => r.success).map((r) => r.episode);
+      setSeries((prev) =>
+        prev && prev.seriesId === series.seriesId
+          ? { ...prev, downloadedEpisodes: [...new Set([...prev.downloadedEpisodes, ...downloaded])] }
+          : prev,
+      );
+      refreshRecent();
       refreshFiles();
     } catch (e) {
-      error(`Download failed: ${e}`);
+      error(`Download failed: ${describeError(e)}`);
+      // Nothing was downloaded; drop the episodes that can't be so a retry goes through
+      const unavailable = (e as AppError)?.code === "episodes_unavailable"
+        ? Object.keys(((e as AppError).details?.availability ?? {}) as object).map(Number)
+        : [];
+      if (unavailable.length > 0) {
+        setSelectedEpisodes((prev) => new Set([...prev].filter((ep) => !unavailable.includes(ep))));
+      }
     } finally {
       setDownloadState((prev) => ({ ...prev, isDownloading: false }));
       setQueue([]);
@@ -317,9 +427,43 @@ function App() {
     success,
     warning,
     error,
+    describeError,
     resetSpeedGraph,
+    refreshRecent,
   ]);
 
+  const handleProbe = useCallback(async () => {
+    if (!series || selectedEpisodes.size === 0) return;
+    setIsProbing(true);
+    try {
+      const result = await invoke<SeriesProbe>("probe_download", {
+        request: {
+          seriesId: series.seriesId,
+          site: series.site,
+          episodes: Array.from(selectedEpisodes).sort((a, b) => a - b),
+          quality: settings.quality,
+          speedLimit: settings.speedLimit,
+          concurrentDownloads: settings.concurrentDownloads,
+        },
+      });
+      setProbe(result);
+      if (result.dead.length > 0) {
+        warning(`Dead links for episodes ${result.dead.join(", ")}`);
+      } else {
+        log(`All ${result.episodes.length} episode links are reachable`);
+      }
+    } catch (e) {
+      error(`Failed to check episodes: ${describeError(e)}`);
+    } finally {
+      setIsProbing(false);
+    }
+  }, [series, selectedEpisodes, settings, log, warning, error, describeError]);
+
+  // A probe only describes the selection it was run for
+  useEffect(() => {
+    setProbe(null);
+  }, [series?.seriesId, series?.site, selectedEpisodes]);
+
   const handlePause = useCallback(async () => {
     if (downloadState.currentEpisode === 0) {
       warning("No episode currently downloading");
@@ -327,13 +471,17 @@ function App() {
     }
     setDownloadState((prev) => ({ ...prev, isPaused: true }));
     try {
-      await invoke("pause_download", { episode: downloadState.currentEpisode });
+      await invoke("pause_download", {
+        episode: downloadState.currentEpisode,
+        seriesId: downloadState.seriesId,
+        site: downloadState.site,
+      });
       log("Paused download");
     } catch (e) {
       // Download may have completed, don't show error
       log("Pause completed (download may have finished)");
     }
-  }, [downloadState.currentEpisode, log, warning]);
+  }, [downloadState.currentEpisode, downloadState.seriesId, downloadState.site, log, warning]);
 
   const handleResume = useCallback(async () => {
     if (downloadState.currentEpisode === 0) {
@@ -344,13 +492,15 @@ function App() {
     try {
       await invoke("resume_download", {
         episode: downloadState.currentEpisode,
+        seriesId: downloadState.seriesId,
+        site: downloadState.site,
       });
       log("Resumed download");
     } catch (e) {
       // Download may have completed, don't show error
       log("Resume completed (download may have finished)");
     }
-  }, [downloadState.currentEpisode, log, warning]);
+  }, [downloadState.currentEpisode, downloadState.seriesId, downloadState.site, log, warning]);
 
   const handleCancel = useCallback(async () => {
     if (downloadState.currentEpisode === 0) return;
@@ -362,12 +512,14 @@ function App() {
     try {
       await invoke("cancel_download", {
         episode: downloadState.currentEpisode,
+        seriesId: downloadState.seriesId,
+        site: downloadState.site,
       });
       warning("Cancelled download");
     } catch (e) {
-      error(`Failed to cancel: ${e}`);
+      error(`Failed to cancel: ${describeError(e)}`);
     }
-  }, [downloadState.currentEpisode, warning, error]);
+  }, [downloadState.currentEpisode, downloadState.seriesId, downloadState.site, warning, error, describeError]);
 
   const handlePauseResume = useCallback(() => {
     if (downloadState.isPaused) {
@@ -407,20 +559,18 @@ function App() {
         // Auto-fetch the series info
         setIsFetching(true);
         try {
-          const result = await invoke<SeriesInfo>("fetch_series", {
+          const result = await invoke<CachedSeriesInfo>("fetch_series", {
             url: text,
+            maxAgeSecs: cacheMaxAgeSecs,
           });
           setSeries(result);
-          const allEpisodes = new Set(
-            Array.from({ length: result.totalEpisodes }, (_, i) => i + 1),
-          );
-          setSelectedEpisodes(allEpisodes);
+          setSelectedEpisodes(initialSelection(result));
           success(
             `Auto-loaded: ${result.title} (${result.totalEpisodes} episodes)`,
           );
           log(`Cached ${Object.keys(result.episodeUrls).length} video URLs`);
         } catch (e) {
-          error(`Failed to fetch: ${e}`);
+          error(`Failed to fetch: ${describeError(e)}`);
         } finally {
           setIsFetching(false);
         }
@@ -428,7 +578,7 @@ function App() {
     } catch (e) {
       // Clipboard access denied or empty - silently ignore
     }
-  }, [url, isValidSeriesUrl, log, success, error]);
+  }, [url, isValidSeriesUrl, log, success, error, describeError, cacheMaxAgeSecs]);
 
   // Initialize
   const initialized = React.useRef(false);
@@ -439,6 +589,8 @@ function App() {
     log("Application started");
     checkFFmpeg();
     setupEventListeners();
+    refreshRecent();
+    refreshSubscriptions();
 
     // Auto-paste from clipboard on startup
     autoFetchFromClipboard();
@@ -488,6 +640,28 @@ function App() {
   }, [activeTab]);
 
   const setupEventListeners = async () => {
+    await listen<NewEpisodes>("new-episodes", (event) => {
+      const { title, episodes, enqueued } = event.payload;
+      const message = `${title}: new episode${episodes.length > 1 ? "s" : ""} ${episodes.join(", ")}`;
+      success(enqueued ? `${message} (downloading)` : message);
+      showNotification("New Episodes", message);
+      refreshSubscriptions();
+    });
+
+    await listen<ImportLine>("import-progress", (event) => {
+      setImportReport((prev) => (prev ? { ...prev, lines: [...prev.lines, event.payload] } : prev));
+    });
+
+    await listen<RulesReport>("site-rules-reloaded", (event) => {
+      const report = event.payload;
+      setSiteRules(report);
+      if (report.issues.length > 0) {
+        warning(`Site rules reloaded with ${report.issues.length} problem(s)`);
+      } else {
+        log(`Site rules reloaded: ${report.sites.length} site(s)`);
+      }
+    });
+
     await listen<DownloadProgress>("download-progress", (event) => {
       setProgress(event.payload);
       addDataPoint(event.payload.speed);
@@ -510,7 +684,12 @@ function App() {
           ...prev,
           failedEpisodes: [...prev.failedEpisodes, result.episode],
         }));
-        error(`Episode ${result.episode} failed: ${result.error}`);
+        const reason = describeError(result.error);
+        if (result.error?.code === "cancelled") {
+          warning(`Episode ${result.episode}: ${reason}`);
+        } else {
+          error(`Episode ${result.episode} failed: ${reason}`);
+        }
       }
 
       setQueue((prev) =>
@@ -566,12 +745,13 @@ function App() {
       refreshFiles();
     });
 
-    await listen<string>("merge-error", (event) => {
-      error(`Merge failed: ${event.payload}`);
+    await listen<AppError>("merge-error", (event) => {
+      const reason = describeError(event.payload);
+      error(`Merge failed: ${reason}`);
       setMergeState({
         isMerging: false,
         mergedFile: null,
-        mergeError: event.payload,
+        mergeError: reason,
         progress: 0,
         currentTime: 0,
         totalDuration: 0,
@@ -618,33 +798,210 @@ function App() {
     }
   };
 
-  const handleFetch = async () => {
-    if (!url.trim()) {
-      error("Please enter a URL");
+  const handleFetch = async (target: string = url) => {
+    const input = target.trim();
+    if (!input) {
+      error("Please enter a URL or search keyword");
+      return;
+    }
+
+    // Anything that isn't a URL is a catalog search
+    if (!/^https?:\/\//i.test(input)) {
+      setIsFetching(true);
+      log(`Searching: ${input}`);
+      try {
+        const results = await invoke<SeriesSummary[]>("search_series", { query: input });
+        setSearchResults(results);
+        setNextListingPage(null);
+        if (results.length === 0) {
+          error(`No series found for "${input}"`);
+        } else {
+          success(`Found ${results.length} series`);
+        }
+      } catch (e) {
+        error(`Search failed: ${describeError(e)}`);
+      } finally {
+        setIsFetching(false);
+      }
+      return;
+    }
+
+    // Genre, category and "latest updates" pages list many series; links on other
+    // hosts may be short links, which the backend follows
+    if (isValidSeriesUrl(input) && !/series_id=\d+|\/series\/\d+|\/watch\/\d+/.test(input)) {
+      await handleLoadListing(input, false);
       return;
     }
 
     setIsFetching(true);
-    log(`Fetching: ${url}`);
+    setSearchResults([]);
+    setNextListingPage(null);
+    log(`Fetching: ${input}`);
 
     try {
-      const result = await invoke<SeriesInfo>("fetch_series", { url });
+      const result = await invoke<CachedSeriesInfo>("fetch_series", {
+        url: input,
+        maxAgeSecs: cacheMaxAgeSecs,
+      });
       setSeries(result);
 
-      const allEpisodes = new Set(
-        Array.from({ length: result.totalEpisodes }, (_, i) => i + 1),
-      );
-      setSelectedEpisodes(allEpisodes);
+      setSelectedEpisodes(initialSelection(result));
 
       success(`Loaded: ${result.title} (${result.totalEpisodes} episodes)`);
+      if (result.targetEpisode != null) {
+        log(`Selected episode ${result.targetEpisode} from the link`);
+      }
+      if (result.offline) {
+        warning(`Site unreachable - showing cached copy from ${new Date(result.fetchedAt * 1000).toLocaleString()}`);
+      }
       log(`Cached ${Object.keys(result.episodeUrls).length} video URLs`);
+      refreshRecent();
+    } catch (e) {
+      error(`Failed to fetch: ${describeError(e)}`);
+    } finally {
+      setIsFetching(false);
+    }
+  };
+
+  // Download imported series one after another
+  const runImportedDownloads = async (downloads: DownloadRequest[]) => {
+    for (const [i, request] of downloads.entries()) {
+      log(`Downloading ${request.seriesTitle} (${i + 1}/${downloads.length})`);
+      setDownloadState({
+        isDownloading: true,
+        isPaused: false,
+        seriesId: request.seriesId,
+        site: request.site,
+        currentEpisode: 0,
+        completedEpisodes: [],
+        failedEpisodes: [],
+        totalSelected: request.episodes.length,
+      });
+      resetSpeedGraph();
+      try {
+        const results = await invoke<DownloadResult[]>("start_download", { request });
+        const failCount = results.filter((r) => !r.success).length;
+        if (failCount === 0) {
+          success(`${request.seriesTitle}: all ${results.length} episodes downloaded`);
+        } else {
+          warning(`${request.seriesTitle}: ${failCount} of ${results.length} episodes failed`);
+        }
+      } catch (e) {
+        error(`${request.seriesTitle}: download failed: ${describeError(e)}`);
+      }
+    }
+    setDownloadState((prev) => ({ ...prev, isDownloading: false }));
+    refreshRecent();
+    refreshFiles();
+  };
+
+  const handleImport = async (source: { text?: string; path?: string }, options: ImportOptions) => {
+    const preset = presets.find((p) => p.id === options.presetId);
+    const effective = { ...settings, ...preset?.settings };
+    setIsImporting(true);
+    setImportReport({ lines: [], downloads: [] });
+    try {
+      const report = await invoke<ImportReport>("import_series_list", {
+        request: {
+          ...source,
+          enqueue: options.download ? toDownloadOptions(effective, ffmpegAvailable) : null,
+        },
+      });
+      setImportReport(report);
+      const imported = report.lines.filter((l) => l.status === "imported").length;
+      const problems = report.lines.filter((l) => l.status === "invalid" || l.status === "failed").length;
+      if (problems > 0) {
+        warning(`Imported ${imported} series, ${problems} line(s) could not be imported`);
+      } else {
+        success(`Imported ${imported} series`);
+      }
+      refreshRecent();
+      if (report.downloads.length > 0) {
+        await runImportedDownloads(report.downloads);
+      }
+    } catch (e) {
+      error(`Import failed: ${describeError(e)}`);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
+  const handleImportFile = async (options: ImportOptions) => {
+    try {
+      const selected = await open({
+        multiple: false,
+        title: "Import Series List",
+        filters: [{ name: "Lists", extensions: ["txt", "csv", "tsv"] }],
+      });
+      if (selected && typeof selected === "string") {
+        await handleImport({ path: selected }, options);
+      }
+    } catch (e) {
+      warning("Could not open list file");
+    }
+  };
+
+  const handleOpenRecent = (item: CachedSeriesSummary) => {
+    setUrl(item.sourceUrl);
+    handleFetch(item.sourceUrl);
+  };
+
+  const handleRefreshRecent = async (item: CachedSeriesSummary) => {
+    setIsFetching(true);
+    log(`Refreshing: ${item.title}`);
+    try {
+      const result = await invoke<CachedSeriesInfo>("refresh_cached_series", { url: item.sourceUrl });
+      setUrl(result.sourceUrl);
+      setSeries(result);
+      setSelectedEpisodes(
+        new Set(Array.from({ length: result.totalEpisodes }, (_, i) => i + 1)),
+      );
+      success(`Refreshed: ${result.title} (${result.totalEpisodes} episodes)`);
+      refreshRecent();
+    } catch (e) {
+      error(`Failed to refresh: ${describeError(e)}`);
+    } finally {
+      setIsFetching(false);
+    }
+  };
+
+  const handleEvictRecent = async (item: CachedSeriesSummary) => {
+    try {
+      await invoke<boolean>("evict_cached_series", { site: item.site, seriesId: item.seriesId });
+      await invoke<boolean>("unload_series", { site: item.site, seriesId: item.seriesId });
+      setRecentSeries((prev) => prev.filter((s) => !(s.site === item.site && s.seriesId === item.seriesId)));
+      log(`Removed ${item.title} from cache`);
+    } catch (e) {
+      error(`Failed to remove from cache: ${describeError(e)}`);
+    }
+  };
+
+  const handleLoadListing = async (pageUrl: string, append: boolean) => {
+    setIsFetching(true);
+    log(`Loading listing: ${pageUrl}`);
+    try {
+      const listing = await invoke<SeriesPage>("fetch_listing", { url: pageUrl });
+      setSearchResults((prev) => {
+        if (!append) return listing.series;
+        const seen = new Set(prev.map((s) => `${s.site}-${s.seriesId}`));
+        return [...prev, ...listing.series.filter((s) => !seen.has(`${s.site}-${s.seriesId}`))];
+      });
+      setNextListingPage(listing.nextPageUrl ?? null);
+      success(
+        `Page ${listing.page}${listing.totalPages ? ` of ${listing.totalPages}` : ""}: ${listing.series.length} series`,
+      );
     } catch (e) {
-      error(`Failed to fetch: ${e}`);
+      error(`Failed to load listing: ${describeError(e)}`);
     } finally {
       setIsFetching(false);
     }
   };
 
+  const handleSelectResult = (result: SeriesSummary) => {
+    setUrl(result.url);
+    handleFetch(result.url);
+  };
+
   const toggleEpisode = (ep: number) => {
     setSelectedEpisodes((prev) => {
       const next = new Set(prev);
@@ -659,9 +1016,7 @@ function App() {
 
   const selectAllEpisodes = () => {
     if (series) {
-      setSelectedEpisodes(
-        new Set(Array.from({ length: series.totalEpisodes }, (_, i) => i + 1)),
-      );
+      setSelectedEpisodes(new Set(availableEpisodes(series)));
     }
   };
 
@@ -716,6 +1071,151 @@ function App() {
     }
   };
 
+  const refreshCookies = async () => {
+    try {
+      setCookies(await invoke<CookieInfo[]>("get_cookies"));
+    } catch (e) {
+      warning("Could not read stored cookies");
+    }
+  };
+
+  const refreshProxy = async () => {
+    try {
+      setProxy(await invoke<ProxySettings>("get_proxy_settings"));
+    } catch (e) {
+      warning("Could not read proxy settings");
+    }
+  };
+
+  const handleValidateSiteRules = async () => {
+    try {
+      const report = await invoke<RulesReport>("validate_site_rules");
+      setSiteRules(report);
+      if (report.issues.length > 0) {
+        warning(`Site rules have ${report.issues.length} problem(s)`);
+      }
+    } catch (e) {
+      warning("Could not read site rules");
+    }
+  };
+
+  const handleReloadSiteRules = async () => {
+    try {
+      const report = await invoke<RulesReport>("reload_site_rules");
+      setSiteRules(report);
+      success(`Loaded ${report.sites.length} site(s) from rules`);
+    } catch (e) {
+      error(`Failed to reload site rules: ${describeError(e)}`);
+    }
+  };
+
+  const refreshSubscriptions = async () => {
+    try {
+      setSubscriptions(await invoke<Subscription[]>("list_subscriptions"));
+    } catch (e) {
+      console.error("Failed to list subscriptions:", e);
+    }
+  };
+
+  const handleSubscribe = async (site: string, seriesId: number, intervalMins?: number, autoDownload = false) => {
+    try {
+      const subscription = await invoke<Subscription>("subscribe_series", {
+        site,
+        seriesId,
+        intervalMins,
+        download: autoDownload ? toDownloadOptions(settings, ffmpegAvailable) : null,
+      });
+      setSubscriptions((prev) => [
+        ...prev.filter((s) => !(s.site === site && s.seriesId === seriesId)),
+        subscription,
+      ]);
+      log(`Subscribed to ${subscription.title}`);
+    } catch (e) {
+      error(`Failed to subscribe: ${describeError(e)}`);
+    }
+  };
+
+  const handleUnsubscribe = async (site: string, seriesId: number) => {
+    try {
+      await invoke<boolean>("unsubscribe_series", { site, seriesId });
+      setSubscriptions((prev) => prev.filter((s) => !(s.site === site && s.seriesId === seriesId)));
+      log("Unsubscribed");
+    } catch (e) {
+      error(`Failed to unsubscribe: ${describeError(e)}`);
+    }
+  };
+
+  const handleCheckSubscriptions = async () => {
+    setIsCheckingSubscriptions(true);
+    try {
+      const found = await invoke<NewEpisodes[]>("check_subscriptions_now");
+      if (found.length === 0) log("No new episodes");
+      refreshSubscriptions();
+    } catch (e) {
+      error(`Failed to check subscriptions: ${describeError(e)}`);
+    } finally {
+      setIsCheckingSubscriptions(false);
+    }
+  };
+
+  const isSubscribed = series != null && subscriptions.some((s) => s.site === series.site && s.seriesId === series.seriesId);
+
+  // Show the current session, proxy, site rules and subscriptions whenever settings are opened
+  useEffect(() => {
+    if (activeTab === "settings") {
+      refreshCookies();
+      refreshProxy();
+      handleValidateSiteRules();
+      refreshSubscriptions();
+    }
+  }, [activeTab]);
+
+  const handleSaveProxy = async (settings: ProxySettings) => {
+    try {
+      await invoke("set_proxy_settings", { settings });
+      setProxy(settings);
+      success(settings.enabled ? `Using proxy ${settings.url}` : "Proxy disabled");
+    } catch (e) {
+      error(`Invalid proxy settings: ${describeError(e)}`);
+    }
+  };
+
+  const handleTestProxy = async (settings: ProxySettings) => {
+    try {
+      return await invoke<ProxyTestResult>("test_proxy", { settings });
+    } catch (e) {
+      error(`Invalid proxy settings: ${describeError(e)}`);
+      return null;
+    }
+  };
+
+  const handleImportCookies = async () => {
+    try {
+      const selected = await open({
+        multiple: false,
+        title: "Select cookies.txt",
+        filters: [{ name: "Cookies", extensions: ["txt"] }],
+      });
+      if (selected && typeof selected === "string") {
+        const count = await invoke<number>("import_cookies", { path: selected });
+        success(`Imported ${count} cookies`);
+        await refreshCookies();
+      }
+    } catch (e) {
+      error(`Failed to import cookies: ${describeError(e)}`);
+    }
+  };
+
+  const handleClearCookies = async () => {
+    try {
+      await invoke("clear_cookies");
+      setCookies([]);
+      success("Session cleared");
+    } catch (e) {
+      error(`Failed to clear cookies: ${describeError(e)}`);
+    }
+  };
+
   const handleOpenOutputFolder = async () => {
     try {
       await invoke("open_folder", { path: settings.outputDir });
@@ -751,16 +1251,16 @@ function App() {
       // Auto-fetch
       setIsFetching(true);
       try {
-        const result = await invoke<SeriesInfo>("fetch_series", { url: text });
+        const result = await invoke<CachedSeriesInfo>("fetch_series", {
+          url: text,
+          maxAgeSecs: cacheMaxAgeSecs,
+        });
         setSeries(result);
-        const allEpisodes = new Set(
-          Array.from({ length: result.totalEpisodes }, (_, i) => i + 1),
-        );
-        setSelectedEpisodes(allEpisodes);
+        setSelectedEpisodes(initialSelection(result));
         success(`Loaded: ${result.title} (${result.totalEpisodes} episodes)`);
         log(`Cached ${Object.keys(result.episodeUrls).length} video URLs`);
       } catch (err) {
-        error(`Failed to fetch: ${err}`);
+        error(`Failed to fetch: ${describeError(err)}`);
       } finally {
         setIsFetching(false);
       }
@@ -950,7 +1450,7 @@ function App() {
             {/* URL Input - Compact */}
             <div className="space-y-2">
               <Input
-                placeholder="https://rongyok.com/watch/?series_id=XXX"
+                placeholder="https://rongyok.com/watch/?series_id=XXX or search"
                 value={url}
                 onChange={(e) => setUrl(e.target.value)}
                 leftIcon={<Link size={14} />}
@@ -963,6 +1463,8 @@ function App() {
                       onClick={() => {
                         setUrl("");
                         setSeries(null);
+                        setSearchResults([]);
+                        setNextListingPage(null);
                         setSelectedEpisodes(new Set());
                       }}
                       disabled={!url}
@@ -981,7 +1483,7 @@ function App() {
                     </Button>
                     <Button
                       size="sm"
-                      onClick={handleFetch}
+                      onClick={() => handleFetch()}
                       isLoading={isFetching}
                       className="px-2"
                     >
@@ -1020,9 +1522,63 @@ function App() {
               />
             </div>
 
+            {/* Catalog search results */}
+            {searchResults.length > 0 && (
+              <SearchResults
+                results={searchResults}
+                onSelect={handleSelectResult}
+                onLoadMore={
+                  nextListingPage
+                    ? () => handleLoadListing(nextListingPage, true)
+                    : undefined
+                }
+                isLoading={isFetching}
+              />
+            )}
+
+            {/* Recently viewed series from the on-disk cache */}
+            {searchResults.length === 0 && !series && recentSeries.length > 0 && (
+              <RecentSeries
+                series={recentSeries}
+                onOpen={handleOpenRecent}
+                onRefresh={handleRefreshRecent}
+                onEvict={handleEvictRecent}
+                isLoading={isFetching}
+              />
+            )}
+
+            {/* Bulk import of series links */}
+            {searchResults.length === 0 && !series && (
+              <ImportPanel
+                presets={presets}
+                report={importReport}
+                isImporting={isImporting}
+                onImportText={(text, options) => handleImport({ text }, options)}
+                onImportFile={handleImportFile}
+                onReadClipboard={async () => (await readText()) ?? ""}
+              />
+            )}
+
             {/* Series Info - Compact */}
             <SeriesCard series={series} isLoading={isFetching} />
 
+            {series && !isFetching && (
+              <div className="flex justify-end -mt-1">
+                <Button
+                  size="sm"
+                  variant="ghost"
+                  onClick={() =>
+                    isSubscribed
+                      ? handleUnsubscribe(series.site, series.seriesId)
+                      : handleSubscribe(series.site, series.seriesId)
+                  }
+                  leftIcon={isSubscribed ? <BellOff size={12} /> : <Bell size={12} />}
+                >
+                  {isSubscribed ? "Unsubscribe" : "Notify me of new episodes"}
+                </Button>
+              </div>
+            )}
+
             {/* Episode Selector */}
             {series && (
               <EpisodeSelector
@@ -1031,6 +1587,8 @@ function App() {
                 onToggle={toggleEpisode}
                 onSelectAll={selectAllEpisodes}
                 onDeselectAll={deselectAllEpisodes}
+                downloadedEpisodes={series.downloadedEpisodes}
+                availability={series.availability}
                 disabled={downloadState.isDownloading}
               />
             )}
@@ -1102,6 +1660,8 @@ function App() {
               />
             )}
 
+            {probe && !downloadState.isDownloading && <ProbeSummary probe={probe} />}
+
             {/* Options & Actions - Compact inline */}
             <div className="flex flex-wrap items-center justify-between gap-2">
               <label className="flex items-center gap-1.5 cursor-pointer text-xs text-slate-300">
@@ -1121,14 +1681,25 @@ function App() {
 
               <div className="flex gap-2">
                 {!downloadState.isDownloading ? (
-                  <Button
-                    onClick={handleStartDownload}
-                    disabled={!series || selectedEpisodes.size === 0}
-                    leftIcon={<Download size={14} />}
-                    variant="success"
-                  >
-                    Download ({selectedEpisodes.size})
-                  </Button>
+                  <>
+                    <Button
+                      variant="ghost"
+                      onClick={handleProbe}
+                      isLoading={isProbing}
+                      disabled={!series || selectedEpisodes.size === 0}
+                      leftIcon={<Gauge size={14} />}
+                    >
+                      Check
+                    </Button>
+                    <Button
+                      onClick={handleStartDownload}
+                      disabled={!series || selectedEpisodes.size === 0}
+                      leftIcon={<Download size={14} />}
+                      variant="success"
+                    >
+                      Download ({selectedEpisodes.size})
+                    </Button>
+                  </>
                 ) : (
                   <>
                     {!downloadState.isPaused ? (
@@ -1211,6 +1782,30 @@ function App() {
               activeThemeId={activeThemeId}
               onThemeSelect={setActiveTheme}
             />
+
+            <ProxyPanel proxy={proxy} onSave={handleSaveProxy} onTest={handleTestProxy} />
+
+            <SessionPanel
+              cookies={cookies}
+              onImport={handleImportCookies}
+              onClear={handleClearCookies}
+            />
+
+            <SiteRulesPanel
+              report={siteRules}
+              onValidate={handleValidateSiteRules}
+              onReload={handleReloadSiteRules}
+            />
+
+            <SubscriptionsPanel
+              subscriptions={subscriptions}
+              onChange={(sub, intervalMins, autoDownload) =>
+                handleSubscribe(sub.site, sub.seriesId, intervalMins, autoDownload)
+              }
+              onUnsubscribe={(sub) => handleUnsubscribe(sub.site, sub.seriesId)}
+              onCheckNow={handleCheckSubscriptions}
+              isChecking={isCheckingSubscriptions}
+            />
           </div>
         )}
 
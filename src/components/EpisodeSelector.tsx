@@ -1,9 +1,15 @@
+import { EpisodeAvailability } from "../types";
+
 interface EpisodeSelectorProps {
   totalEpisodes: number;
   selectedEpisodes: Set<number>;
   onToggle: (episode: number) => void;
   onSelectAll: () => void;
   onDeselectAll: () => void;
+  /** Episodes already on disk */
+  downloadedEpisodes?: number[];
+  /** Gaps and duplicates found while resolving the series */
+  availability?: Record<number, EpisodeAvailability>;
   disabled?: boolean;
 }
 
@@ -13,9 +19,13 @@ export function EpisodeSelector({
   onToggle,
   onSelectAll,
   onDeselectAll,
+  downloadedEpisodes = [],
+  availability = {},
   disabled,
 }: EpisodeSelectorProps) {
   const episodes = Array.from({ length: totalEpisodes }, (_, i) => i + 1);
+  const downloaded = new Set(downloadedEpisodes);
+  const unavailable = episodes.filter((ep) => (availability[ep]?.status ?? "available") !== "available");
 
   return (
     <div className="glass rounded-lg p-2 border border-slate-700/50">
@@ -25,6 +35,12 @@ export function EpisodeSelector({
             {selectedEpisodes.size}/{totalEpisodes}
           </span>
           Episodes
+          {downloaded.size > 0 && (
+            <span className="ml-1 text-[10px] text-cyan-400">({downloaded.size} downloaded)</span>
+          )}
+          {unavailable.length > 0 && (
+            <span className="ml-1 text-[10px] text-red-400">({unavailable.length} unavailable)</span>
+          )}
         </span>
         <div className="flex gap-1">
           <button
@@ -51,12 +67,17 @@ export function EpisodeSelector({
               key={ep}
               onClick={() => onToggle(ep)}
               disabled={disabled}
+              title={downloaded.has(ep) ? "Downloaded" : describeAvailability(availability[ep])}
               className={`
                 w-7 h-6 text-[10px] font-medium rounded transition-all border
                 ${
                   selectedEpisodes.has(ep)
                     ? "bg-emerald-500/20 text-emerald-300 border-emerald-500/50 shadow-[0_0_8px_rgba(16,185,129,0.3)]"
-                    : "bg-slate-700/30 text-slate-400 border-slate-600/50 hover:bg-slate-700/50 hover:text-white hover:border-slate-500/50"
+                    : downloaded.has(ep)
+                      ? "bg-cyan-500/10 text-cyan-400 border-cyan-500/40 hover:bg-cyan-500/20"
+                      : unavailable.includes(ep)
+                        ? "bg-red-500/10 text-red-400/70 border-red-500/30 border-dashed hover:bg-red-500/20"
+                        : "bg-slate-700/30 text-slate-400 border-slate-600/50 hover:bg-slate-700/50 hover:text-white hover:border-slate-500/50"
                 }
                 disabled:opacity-50 disabled:cursor-not-allowed
               `}
@@ -69,3 +90,16 @@ export function EpisodeSelector({
     </div>
   );
 }
+
+function describeAvailability(availability?: EpisodeAvailability): string | undefined {
+  switch (availability?.status) {
+    case "missing":
+      return "No video on the site";
+    case "unresolved":
+      return `Could not resolve: ${availability.reason}`;
+    case "duplicate":
+      return `Same video as episode ${availability.of}`;
+    default:
+      return undefined;
+  }
+}
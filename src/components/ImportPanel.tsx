@@ -0,0 +1,134 @@
+import { useState } from "react";
+import { ListPlus, Clipboard, FileText, ChevronDown, ChevronRight } from "lucide-react";
+import { DownloadPreset } from "../hooks/useDownloadPresets";
+import { ImportLine, ImportReport } from "../types";
+import { Button } from "./Button";
+
+export interface ImportOptions {
+  /** Queue every available episode of each series */
+  download: boolean;
+  /** Preset applied on top of the current settings; null keeps the settings as they are */
+  presetId: string | null;
+}
+
+interface ImportPanelProps {
+  presets: DownloadPreset[];
+  report: ImportReport | null;
+  isImporting?: boolean;
+  onImportText: (text: string, options: ImportOptions) => void;
+  onImportFile: (options: ImportOptions) => void;
+  onReadClipboard: () => Promise<string>;
+}
+
+function describeLine(line: ImportLine): string {
+  switch (line.status) {
+    case "imported":
+      return line.series ? `${line.series.title} (${line.series.totalEpisodes} EP)` : "Imported";
+    case "duplicate":
+      return `Same series as line ${line.of}`;
+    case "skipped":
+      return "No link";
+    case "invalid":
+      return line.error?.message ?? "Not a series link";
+    case "failed":
+      return line.error?.message ?? "Could not load";
+  }
+}
+
+const STATUS_STYLES: Record<ImportLine["status"], string> = {
+  imported: "text-emerald-400",
+  duplicate: "text-slate-500",
+  skipped: "text-slate-500",
+  invalid: "text-amber-400",
+  failed: "text-red-400",
+};
+
+export function ImportPanel({ presets, report, isImporting, onImportText, onImportFile, onReadClipboard }: ImportPanelProps) {
+  const [expanded, setExpanded] = useState(false);
+  const [text, setText] = useState("");
+  const [download, setDownload] = useState(false);
+  const [presetId, setPresetId] = useState<string | null>(null);
+  const options = { download, presetId };
+
+  return (
+    <div className="glass rounded-lg p-2 border border-slate-700/50">
+      <button
+        onClick={() => setExpanded((prev) => !prev)}
+        className="w-full flex items-center gap-1 text-xs text-slate-400 hover:text-slate-200"
+      >
+        {expanded ? <ChevronDown size={12} /> : <ChevronRight size={12} />}
+        <ListPlus size={12} className="text-cyan-400" />
+        Import a list of series links
+      </button>
+
+      {expanded && (
+        <div className="mt-2 space-y-2">
+          <textarea
+            value={text}
+            onChange={(e) => setText(e.target.value)}
+            placeholder="One link per line, or paste CSV / spreadsheet rows"
+            rows={4}
+            className="w-full bg-slate-900/60 border border-slate-700 rounded-md p-2 text-xs text-slate-200 font-mono resize-y"
+          />
+
+          <div className="flex flex-wrap items-center gap-2 text-xs text-slate-300">
+            <label className="flex items-center gap-1.5 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={download}
+                onChange={(e) => setDownload(e.target.checked)}
+                className="w-3.5 h-3.5 rounded bg-slate-700 border-slate-600 text-violet-600"
+              />
+              Download all episodes
+            </label>
+            {download && (
+              <select
+                value={presetId ?? ""}
+                onChange={(e) => setPresetId(e.target.value || null)}
+                className="bg-slate-900/60 border border-slate-700 rounded px-1 py-0.5 text-xs"
+              >
+                <option value="">Current settings</option>
+                {presets.map((preset) => (
+                  <option key={preset.id} value={preset.id}>
+                    {preset.icon} {preset.name}
+                  </option>
+                ))}
+              </select>
+            )}
+          </div>
+
+          <div className="flex gap-2">
+            <Button size="sm" variant="ghost" onClick={async () => setText(await onReadClipboard())}>
+              <Clipboard size={14} /> Paste
+            </Button>
+            <Button size="sm" variant="ghost" onClick={() => onImportFile(options)} disabled={isImporting}>
+              <FileText size={14} /> From file
+            </Button>
+            <Button
+              size="sm"
+              variant="cyan"
+              onClick={() => onImportText(text, options)}
+              isLoading={isImporting}
+              disabled={!text.trim()}
+            >
+              Import
+            </Button>
+          </div>
+
+          {report && report.lines.length > 0 && (
+            <ul className="space-y-0.5 max-h-40 overflow-y-auto">
+              {report.lines.map((line) => (
+                <li key={line.line} className="text-xs flex gap-2">
+                  <span className="text-slate-500 w-8 shrink-0">#{line.line}</span>
+                  <span className={`${STATUS_STYLES[line.status]} truncate`} title={line.text}>
+                    {describeLine(line)}
+                  </span>
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
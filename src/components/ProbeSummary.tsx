@@ -0,0 +1,54 @@
+import { Gauge, AlertCircle } from "lucide-react";
+import { SeriesProbe } from "../types";
+
+interface ProbeSummaryProps {
+  probe: SeriesProbe;
+}
+
+function formatBytes(bytes: number): string {
+  if (bytes === 0) return "0 B";
+  const k = 1024;
+  const sizes = ["B", "KB", "MB", "GB", "TB"];
+  const i = Math.floor(Math.log(bytes) / Math.log(k));
+  return parseFloat((bytes / Math.pow(k, i)).toFixed(1)) + " " + sizes[i];
+}
+
+function formatDuration(secs: number): string {
+  const h = Math.floor(secs / 3600);
+  const m = Math.ceil((secs % 3600) / 60);
+  if (h > 0) return `${h}h ${m}m`;
+  return m > 0 ? `${m}m` : "<1m";
+}
+
+export function ProbeSummary({ probe }: ProbeSummaryProps) {
+  const noRanges = probe.episodes.filter((p) => p.alive && p.kind === "progressive" && !p.acceptsRanges);
+
+  return (
+    <div className="p-2 glass rounded-lg border border-slate-700/50 text-xs space-y-1">
+      <div className="flex items-center gap-2 text-slate-300">
+        <Gauge size={12} className="text-cyan-400" />
+        <span>{formatBytes(probe.totalSize)}</span>
+        {probe.unknownSize.length > 0 && (
+          <span className="text-slate-500">+ {probe.unknownSize.length} of unknown size</span>
+        )}
+        {probe.estimatedSecs != null && (
+          <span className="text-slate-400">• about {formatDuration(probe.estimatedSecs)}</span>
+        )}
+        {probe.bytesPerSec != null && (
+          <span className="text-slate-500">at {formatBytes(probe.bytesPerSec)}/s</span>
+        )}
+      </div>
+      {probe.dead.length > 0 && (
+        <div className="flex items-center gap-1 text-red-400">
+          <AlertCircle size={12} />
+          Dead links: EP {probe.dead.join(", ")}
+        </div>
+      )}
+      {noRanges.length > 0 && (
+        <div className="text-amber-400/80">
+          {noRanges.length} episodes can't resume if interrupted (no range support)
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,109 @@
+import { useEffect, useState } from "react";
+import { Globe, Save, Activity } from "lucide-react";
+import { ProxySettings, ProxyTestResult } from "../types";
+import { Button } from "./Button";
+import { Input } from "./Input";
+
+interface ProxyPanelProps {
+  proxy: ProxySettings;
+  onSave: (proxy: ProxySettings) => void;
+  onTest: (proxy: ProxySettings) => Promise<ProxyTestResult | null>;
+}
+
+export function ProxyPanel({ proxy, onSave, onTest }: ProxyPanelProps) {
+  const [draft, setDraft] = useState<ProxySettings>(proxy);
+  const [bypassText, setBypassText] = useState(proxy.bypass.join(", "));
+  const [testResult, setTestResult] = useState<ProxyTestResult | null>(null);
+  const [isTesting, setIsTesting] = useState(false);
+
+  useEffect(() => {
+    setDraft(proxy);
+    setBypassText(proxy.bypass.join(", "));
+  }, [proxy]);
+
+  const current = (): ProxySettings => ({
+    ...draft,
+    bypass: bypassText
+      .split(",")
+      .map((h) => h.trim())
+      .filter(Boolean),
+  });
+
+  const handleTest = async () => {
+    setIsTesting(true);
+    setTestResult(await onTest(current()));
+    setIsTesting(false);
+  };
+
+  return (
+    <section className="bg-slate-800/50 rounded-xl p-4 border border-slate-700">
+      <h3 className="text-sm font-medium text-slate-300 mb-4 flex items-center gap-2">
+        <span className="icon-glow icon-glow-sm icon-glow-cyan">
+          <Globe size={16} />
+        </span>
+        Proxy
+      </h3>
+
+      <div className="space-y-3">
+        <div className="flex items-center justify-between">
+          <div>
+            <label className="text-sm text-white">Use Proxy</label>
+            <p className="text-xs text-slate-500">Route page fetches, downloads and posters through a proxy</p>
+          </div>
+          <label className="relative inline-flex items-center cursor-pointer">
+            <input
+              type="checkbox"
+              checked={draft.enabled}
+              onChange={(e) => setDraft({ ...draft, enabled: e.target.checked })}
+              className="sr-only peer"
+            />
+            <div className="w-11 h-6 bg-slate-700 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-violet-600"></div>
+          </label>
+        </div>
+
+        <Input
+          label="Proxy URL"
+          placeholder="http://proxy.example.com:8080 or socks5://127.0.0.1:1080"
+          value={draft.url}
+          onChange={(e) => setDraft({ ...draft, url: e.target.value })}
+        />
+        <div className="grid grid-cols-2 gap-2">
+          <Input
+            label="Username"
+            value={draft.username ?? ""}
+            onChange={(e) => setDraft({ ...draft, username: e.target.value || undefined })}
+          />
+          <Input
+            label="Password"
+            type="password"
+            value={draft.password ?? ""}
+            onChange={(e) => setDraft({ ...draft, password: e.target.value || undefined })}
+          />
+        </div>
+        <Input
+          label="Bypass (comma separated)"
+          placeholder="localhost, 192.168.0.0/16, .intranet.example.com"
+          value={bypassText}
+          onChange={(e) => setBypassText(e.target.value)}
+        />
+
+        {testResult && (
+          <p className={`text-xs ${testResult.reachable ? "text-emerald-400" : "text-red-400"}`}>
+            {testResult.reachable
+              ? `Reachable (HTTP ${testResult.status}, ${testResult.latencyMs} ms)`
+              : `Unreachable: ${testResult.error ?? "unknown error"}`}
+          </p>
+        )}
+
+        <div className="flex gap-2">
+          <Button size="sm" variant="ghost" onClick={handleTest} isLoading={isTesting}>
+            <Activity size={14} /> Test
+          </Button>
+          <Button size="sm" onClick={() => onSave(current())}>
+            <Save size={14} /> Save
+          </Button>
+        </div>
+      </div>
+    </section>
+  );
+}
@@ -0,0 +1,78 @@
+import { CachedSeriesSummary } from "../types";
+import { Film, RefreshCw, X } from "lucide-react";
+
+interface RecentSeriesProps {
+  series: CachedSeriesSummary[];
+  onOpen: (series: CachedSeriesSummary) => void;
+  onRefresh: (series: CachedSeriesSummary) => void;
+  onEvict: (series: CachedSeriesSummary) => void;
+  isLoading?: boolean;
+}
+
+function formatAge(unixSeconds: number): string {
+  const minutes = Math.floor((Date.now() / 1000 - unixSeconds) / 60);
+  if (minutes < 60) return `${Math.max(minutes, 0)}m ago`;
+  if (minutes < 60 * 24) return `${Math.floor(minutes / 60)}h ago`;
+  return `${Math.floor(minutes / 60 / 24)}d ago`;
+}
+
+export function RecentSeries({ series, onOpen, onRefresh, onEvict, isLoading }: RecentSeriesProps) {
+  return (
+    <div className="glass rounded-lg p-1 border border-slate-700/50 max-h-64 overflow-y-auto">
+      <p className="px-1 pb-1 text-[10px] text-slate-500">Recently viewed</p>
+      {series.map((item) => (
+        <div
+          key={`${item.site}-${item.seriesId}`}
+          className="flex items-center gap-2 p-1 rounded hover:bg-slate-700/40 transition-colors"
+        >
+          <button
+            onClick={() => onOpen(item)}
+            disabled={isLoading}
+            className="flex-1 min-w-0 flex gap-2 text-left disabled:opacity-50"
+          >
+            {item.posterUrl ? (
+              <img
+                src={item.posterUrl}
+                alt={item.title}
+                className="w-8 h-11 object-cover rounded shadow"
+              />
+            ) : (
+              <div className="w-8 h-11 bg-slate-700/50 rounded flex items-center justify-center">
+                <Film size={12} className="text-violet-400" />
+              </div>
+            )}
+            <div className="flex-1 min-w-0">
+              <p className="text-xs font-semibold text-white truncate">{item.title}</p>
+              <div className="flex items-center gap-2 text-[10px] text-slate-400 mt-0.5">
+                <span className="px-1 bg-violet-500/20 text-violet-300 rounded border border-violet-500/30">
+                  {item.site} #{item.seriesId}
+                </span>
+                <span className="text-emerald-400">
+                  {item.downloadedEpisodes.length}/{item.totalEpisodes} downloaded
+                </span>
+                <span className={item.expired ? "text-amber-400" : undefined}>
+                  {formatAge(item.fetchedAt)}
+                </span>
+              </div>
+            </div>
+          </button>
+          <button
+            onClick={() => onRefresh(item)}
+            disabled={isLoading}
+            title="Refresh from site"
+            className="p-1 text-slate-400 hover:text-cyan-400 disabled:opacity-50"
+          >
+            <RefreshCw size={12} />
+          </button>
+          <button
+            onClick={() => onEvict(item)}
+            title="Remove from cache"
+            className="p-1 text-slate-400 hover:text-red-400"
+          >
+            <X size={12} />
+          </button>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,56 @@
+import { SeriesSummary } from "../types";
+import { Film } from "lucide-react";
+
+interface SearchResultsProps {
+  results: SeriesSummary[];
+  onSelect: (result: SeriesSummary) => void;
+  /** Shown when a listing has more pages */
+  onLoadMore?: () => void;
+  isLoading?: boolean;
+}
+
+export function SearchResults({ results, onSelect, onLoadMore, isLoading }: SearchResultsProps) {
+  return (
+    <div className="glass rounded-lg p-1 border border-slate-700/50 max-h-64 overflow-y-auto">
+      {results.map((result) => (
+        <button
+          key={`${result.site}-${result.seriesId}`}
+          onClick={() => onSelect(result)}
+          className="w-full flex gap-2 p-1 rounded text-left hover:bg-slate-700/40 transition-colors"
+        >
+          {result.posterUrl ? (
+            <img
+              src={result.posterUrl}
+              alt={result.title}
+              className="w-8 h-11 object-cover rounded shadow"
+            />
+          ) : (
+            <div className="w-8 h-11 bg-slate-700/50 rounded flex items-center justify-center">
+              <Film size={12} className="text-violet-400" />
+            </div>
+          )}
+          <div className="flex-1 min-w-0">
+            <p className="text-xs font-semibold text-white truncate">{result.title}</p>
+            <div className="flex items-center gap-2 text-[10px] text-slate-400 mt-0.5">
+              <span className="px-1 bg-violet-500/20 text-violet-300 rounded border border-violet-500/30">
+                {result.site} #{result.seriesId}
+              </span>
+              {result.totalEpisodes != null && (
+                <span className="text-cyan-400">{result.totalEpisodes} eps</span>
+              )}
+            </div>
+          </div>
+        </button>
+      ))}
+      {onLoadMore && (
+        <button
+          onClick={onLoadMore}
+          disabled={isLoading}
+          className="w-full p-1 mt-0.5 rounded text-[10px] text-cyan-400 hover:bg-slate-700/40 disabled:opacity-50"
+        >
+          {isLoading ? "Loading..." : "Load more"}
+        </button>
+      )}
+    </div>
+  );
+}
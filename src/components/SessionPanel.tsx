@@ -0,0 +1,56 @@
+import { Cookie, Upload, Trash2 } from "lucide-react";
+import { CookieInfo } from "../types";
+import { Button } from "./Button";
+
+interface SessionPanelProps {
+  cookies: CookieInfo[];
+  onImport: () => void;
+  onClear: () => void;
+}
+
+export function SessionPanel({ cookies, onImport, onClear }: SessionPanelProps) {
+  const domains = Array.from(new Set(cookies.map((c) => c.domain)));
+
+  return (
+    <section className="bg-slate-800/50 rounded-xl p-4 border border-slate-700">
+      <h3 className="text-sm font-medium text-slate-300 mb-4 flex items-center gap-2">
+        <span className="icon-glow icon-glow-sm icon-glow-amber">
+          <Cookie size={16} />
+        </span>
+        Session
+      </h3>
+
+      <p className="text-xs text-slate-500 mb-3">
+        Cookies are shared by series fetching and downloads. Import a cookies.txt export from a
+        logged-in browser for members-only or age-gated series.
+      </p>
+
+      {cookies.length > 0 ? (
+        <ul className="space-y-1 mb-3 max-h-40 overflow-y-auto">
+          {domains.map((domain) => (
+            <li key={domain} className="text-xs text-slate-300 flex justify-between gap-2">
+              <span className="truncate">{domain}</span>
+              <span className="text-slate-500 truncate">
+                {cookies
+                  .filter((c) => c.domain === domain)
+                  .map((c) => c.name)
+                  .join(", ")}
+              </span>
+            </li>
+          ))}
+        </ul>
+      ) : (
+        <p className="text-xs text-slate-400 mb-3">No cookies stored</p>
+      )}
+
+      <div className="flex gap-2">
+        <Button size="sm" variant="ghost" onClick={onImport}>
+          <Upload size={14} /> Import cookies.txt
+        </Button>
+        <Button size="sm" variant="ghost" onClick={onClear} disabled={cookies.length === 0}>
+          <Trash2 size={14} /> Clear
+        </Button>
+      </div>
+    </section>
+  );
+}
@@ -14,6 +14,7 @@ import {
   Languages,
 } from "lucide-react";
 import { Settings as SettingsType } from "../hooks/useSettings";
+import { QualityPreference } from "../types";
 import { CustomTheme } from "../hooks/useCustomTheme";
 import { Language } from "../hooks/useI18n";
 import { Button } from "./Button";
@@ -36,6 +37,17 @@ interface SettingsPanelProps {
   onThemeSelect: (themeId: string) => void;
 }
 
+function qualityValue(quality: QualityPreference): string {
+  return quality.kind === "closest" ? `closest:${quality.height}` : quality.kind;
+}
+
+function parseQuality(value: string): QualityPreference {
+  if (value.startsWith("closest:")) {
+    return { kind: "closest", height: Number(value.slice("closest:".length)) };
+  }
+  return { kind: value as "highest" | "lowest" | "smallest" };
+}
+
 export function SettingsPanel({
   settings,
   onUpdate,
@@ -130,6 +142,47 @@ export function SettingsPanel({
             </select>
           </div>
 
+          {/* Video Quality */}
+          <div className="flex items-center justify-between">
+            <div>
+              <label className="text-sm text-white">Video Quality</label>
+              <p className="text-xs text-slate-500">When an episode has several sources</p>
+            </div>
+            <select
+              value={qualityValue(settings.quality)}
+              onChange={(e) => onUpdate("quality", parseQuality(e.target.value))}
+              className="bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 text-sm text-white"
+            >
+              <option value="highest">Highest</option>
+              <option value="closest:1080">Closest to 1080p</option>
+              <option value="closest:720">Closest to 720p</option>
+              <option value="closest:480">Closest to 480p</option>
+              <option value="lowest">Lowest</option>
+              <option value="smallest">Smallest file</option>
+            </select>
+          </div>
+
+          {/* Series Cache */}
+          <div className="flex items-center justify-between">
+            <div>
+              <label className="text-sm text-white">Series Cache</label>
+              <p className="text-xs text-slate-500">Reuse fetched series without asking the site</p>
+            </div>
+            <div className="flex items-center gap-2">
+              <input
+                type="number"
+                min="0"
+                step="1"
+                value={settings.seriesCacheHours}
+                onChange={(e) =>
+                  onUpdate("seriesCacheHours", Math.max(0, parseInt(e.target.value) || 0))
+                }
+                className="bg-slate-700 border border-slate-600 rounded-lg px-3 py-2 text-sm text-white w-24"
+              />
+              <span className="text-xs text-slate-500">hours</span>
+            </div>
+          </div>
+
           {/* Auto Merge */}
           <div className="flex items-center justify-between">
             <div>
@@ -172,6 +225,40 @@ export function SettingsPanel({
               <div className="w-11 h-6 bg-slate-700 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-violet-600"></div>
             </label>
           </div>
+
+          {/* Download Subtitles */}
+          <div className="flex items-center justify-between">
+            <div>
+              <label className="text-sm text-white">Download Subtitles</label>
+              <p className="text-xs text-slate-500">Save subtitle tracks as .srt next to episodes</p>
+            </div>
+            <label className="relative inline-flex items-center cursor-pointer">
+              <input
+                type="checkbox"
+                checked={settings.downloadSubtitles}
+                onChange={(e) => onUpdate("downloadSubtitles", e.target.checked)}
+                className="sr-only peer"
+              />
+              <div className="w-11 h-6 bg-slate-700 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-violet-600"></div>
+            </label>
+          </div>
+
+          {/* Embed Subtitles */}
+          <div className="flex items-center justify-between">
+            <div>
+              <label className="text-sm text-white">Embed Subtitles</label>
+              <p className="text-xs text-slate-500">Mux subtitles into the merged video</p>
+            </div>
+            <label className="relative inline-flex items-center cursor-pointer">
+              <input
+                type="checkbox"
+                checked={settings.embedSubtitles}
+                onChange={(e) => onUpdate("embedSubtitles", e.target.checked)}
+                className="sr-only peer"
+              />
+              <div className="w-11 h-6 bg-slate-700 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-violet-600"></div>
+            </label>
+          </div>
         </div>
       </section>
 
@@ -0,0 +1,72 @@
+import { Globe, RefreshCw, CheckCircle } from "lucide-react";
+import { RulesReport } from "../types";
+import { Button } from "./Button";
+
+interface SiteRulesPanelProps {
+  report: RulesReport | null;
+  onValidate: () => void;
+  onReload: () => void;
+}
+
+export function SiteRulesPanel({ report, onValidate, onReload }: SiteRulesPanelProps) {
+  return (
+    <section className="bg-slate-800/50 rounded-xl p-4 border border-slate-700">
+      <h3 className="text-sm font-medium text-slate-300 mb-4 flex items-center gap-2">
+        <span className="icon-glow icon-glow-sm icon-glow-violet">
+          <Globe size={16} />
+        </span>
+        Site Rules
+      </h3>
+
+      <p className="text-xs text-slate-500 mb-3">
+        Mirror sites can be added in a JSON rules file. Changes are picked up automatically.
+      </p>
+
+      {report && (
+        <>
+          <p className="text-xs text-slate-400 mb-2 break-all">
+            {report.path}
+            {!report.exists && <span className="text-slate-500"> (not created)</span>}
+          </p>
+
+          {report.sites.length > 0 && (
+            <p className="text-xs text-emerald-400 mb-2">Loaded: {report.sites.join(", ")}</p>
+          )}
+
+          {report.issues.length > 0 ? (
+            <ul className="space-y-1 mb-3 max-h-40 overflow-y-auto">
+              {report.issues.map((issue, i) => (
+                <li key={i} className="text-xs text-red-400">
+                  {issue.site && <span className="text-slate-300">{issue.site}</span>}
+                  {issue.field && <span className="text-slate-500">.{issue.field}</span>}
+                  {issue.line != null && (
+                    <span className="text-slate-500">
+                      {" "}line {issue.line}:{issue.column}
+                    </span>
+                  )}
+                  {(issue.site || issue.line != null) && ": "}
+                  {issue.message}
+                </li>
+              ))}
+            </ul>
+          ) : (
+            report.exists && (
+              <p className="text-xs text-emerald-400 mb-3 flex items-center gap-1">
+                <CheckCircle size={12} /> No problems found
+              </p>
+            )
+          )}
+        </>
+      )}
+
+      <div className="flex gap-2">
+        <Button size="sm" variant="ghost" onClick={onValidate}>
+          <CheckCircle size={14} /> Validate
+        </Button>
+        <Button size="sm" variant="ghost" onClick={onReload}>
+          <RefreshCw size={14} /> Reload
+        </Button>
+      </div>
+    </section>
+  );
+}
@@ -0,0 +1,92 @@
+import { Bell, RefreshCw, X } from "lucide-react";
+import { Subscription } from "../types";
+import { Button } from "./Button";
+
+interface SubscriptionsPanelProps {
+  subscriptions: Subscription[];
+  onChange: (subscription: Subscription, intervalMins: number, autoDownload: boolean) => void;
+  onUnsubscribe: (subscription: Subscription) => void;
+  onCheckNow: () => void;
+  isChecking?: boolean;
+}
+
+const INTERVALS = [
+  { mins: 60, label: "Hourly" },
+  { mins: 6 * 60, label: "Every 6 hours" },
+  { mins: 12 * 60, label: "Every 12 hours" },
+  { mins: 24 * 60, label: "Daily" },
+];
+
+function formatChecked(unixSeconds?: number): string {
+  if (!unixSeconds) return "never checked";
+  const minutes = Math.floor((Date.now() / 1000 - unixSeconds) / 60);
+  if (minutes < 60) return `checked ${Math.max(minutes, 0)}m ago`;
+  if (minutes < 60 * 24) return `checked ${Math.floor(minutes / 60)}h ago`;
+  return `checked ${Math.floor(minutes / 60 / 24)}d ago`;
+}
+
+export function SubscriptionsPanel({ subscriptions, onChange, onUnsubscribe, onCheckNow, isChecking }: SubscriptionsPanelProps) {
+  return (
+    <section className="bg-slate-800/50 rounded-xl p-4 border border-slate-700">
+      <h3 className="text-sm font-medium text-slate-300 mb-4 flex items-center gap-2">
+        <span className="icon-glow icon-glow-sm icon-glow-amber">
+          <Bell size={16} />
+        </span>
+        Subscriptions
+      </h3>
+
+      {subscriptions.length === 0 ? (
+        <p className="text-xs text-slate-500 mb-3">
+          Subscribe to a series from the download tab to be told when new episodes air.
+        </p>
+      ) : (
+        <ul className="space-y-2 mb-3">
+          {subscriptions.map((sub) => (
+            <li key={`${sub.site}-${sub.seriesId}`} className="flex items-center gap-2 text-xs">
+              <div className="flex-1 min-w-0">
+                <p className="text-slate-200 truncate">{sub.title}</p>
+                <p className="text-slate-500">
+                  {sub.site} • {sub.reported.length} EP • {formatChecked(sub.lastChecked)}
+                </p>
+              </div>
+              <select
+                value={sub.intervalMins}
+                onChange={(e) => onChange(sub, Number(e.target.value), sub.download != null)}
+                className="bg-slate-900/60 border border-slate-700 rounded px-1 py-0.5"
+              >
+                {!INTERVALS.some((i) => i.mins === sub.intervalMins) && (
+                  <option value={sub.intervalMins}>Every {sub.intervalMins} min</option>
+                )}
+                {INTERVALS.map((i) => (
+                  <option key={i.mins} value={i.mins}>
+                    {i.label}
+                  </option>
+                ))}
+              </select>
+              <label className="flex items-center gap-1 cursor-pointer text-slate-300" title="Download new episodes with the current settings">
+                <input
+                  type="checkbox"
+                  checked={sub.download != null}
+                  onChange={(e) => onChange(sub, sub.intervalMins, e.target.checked)}
+                  className="w-3.5 h-3.5 rounded bg-slate-700 border-slate-600 text-violet-600"
+                />
+                Auto
+              </label>
+              <button
+                onClick={() => onUnsubscribe(sub)}
+                title="Unsubscribe"
+                className="p-1 text-slate-500 hover:text-red-400"
+              >
+                <X size={12} />
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <Button size="sm" variant="ghost" onClick={onCheckNow} isLoading={isChecking} disabled={subscriptions.length === 0}>
+        <RefreshCw size={14} /> Check now
+      </Button>
+    </section>
+  );
+}
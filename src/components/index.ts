@@ -3,12 +3,20 @@ export { Input } from "./Input";
 export { ProgressBar } from "./ProgressBar";
 export { EpisodeSelector } from "./EpisodeSelector";
 export { SeriesCard } from "./SeriesCard";
+export { SearchResults } from "./SearchResults";
+export { RecentSeries } from "./RecentSeries";
+export { ImportPanel } from "./ImportPanel";
 export { LogPanel } from "./LogPanel";
 export { SettingsPanel } from "./SettingsPanel";
+export { SessionPanel } from "./SessionPanel";
+export { ProxyPanel } from "./ProxyPanel";
+export { SiteRulesPanel } from "./SiteRulesPanel";
+export { SubscriptionsPanel } from "./SubscriptionsPanel";
 export { HistoryPanel } from "./HistoryPanel";
 export { SpeedGraph } from "./SpeedGraph";
 export { FileBrowser } from "./FileBrowser";
 export { DownloadQueue } from "./DownloadQueue";
+export { ProbeSummary } from "./ProbeSummary";
 export { Logo } from "./Logo";
 export { UpdateDialog } from "./UpdateDialog";
 export { MiniMode } from "./MiniMode";
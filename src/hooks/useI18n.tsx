@@ -120,6 +120,38 @@ const translations: Translations = {
   "status.failed": { en: "Failed", th: "ล้มเหลว" },
   "status.merging": { en: "Merging...", th: "กำลังรวม..." },
 
+  // Errors (keyed by backend error code)
+  "error.network": { en: "Network error - check your connection or proxy", th: "เครือข่ายผิดพลาด - ตรวจสอบการเชื่อมต่อหรือพร็อกซี" },
+  "error.http_status": { en: "Server returned HTTP {status}", th: "เซิร์ฟเวอร์ตอบกลับ HTTP {status}" },
+  "error.unsupported_site": { en: "This site is not supported", th: "ไม่รองรับเว็บไซต์นี้" },
+  "error.invalid_url": { en: "Not a valid series URL", th: "URL ซีรีส์ไม่ถูกต้อง" },
+  "error.empty_query": { en: "Enter a search keyword", th: "กรุณาใส่คำค้นหา" },
+  "error.search_failed": { en: "Search failed on every site", th: "ค้นหาไม่สำเร็จทุกเว็บไซต์" },
+  "error.url_expired": { en: "Video link expired - retry to refresh it", th: "ลิงก์วิดีโอหมดอายุ - ลองใหม่เพื่อรีเฟรช" },
+  "error.no_source": { en: "No video found for episode {episode}", th: "ไม่พบวิดีโอของตอนที่ {episode}" },
+  "error.invalid_stream": { en: "The video stream could not be read", th: "อ่านสตรีมวิดีโอไม่ได้" },
+  "error.disk_full": { en: "Disk is full - free up space and retry", th: "พื้นที่ดิสก์เต็ม - ลบไฟล์แล้วลองใหม่" },
+  "error.io": { en: "Could not write {path}", th: "เขียนไฟล์ {path} ไม่ได้" },
+  "error.cancelled": { en: "Cancelled", th: "ยกเลิกแล้ว" },
+  "error.task_failed": { en: "Download task stopped unexpectedly", th: "งานดาวน์โหลดหยุดทำงานกะทันหัน" },
+  "error.ffmpeg_not_found": { en: "FFmpeg not found - cannot merge videos", th: "ไม่พบ FFmpeg - รวมวิดีโอไม่ได้" },
+  "error.no_input": { en: "No videos to merge", th: "ไม่มีวิดีโอให้รวม" },
+  "error.no_valid_input": { en: "All files appear incomplete or corrupted", th: "ไฟล์ทั้งหมดไม่สมบูรณ์หรือเสียหาย" },
+  "error.ffmpeg_failed": { en: "FFmpeg failed: {reason}", th: "FFmpeg ล้มเหลว: {reason}" },
+  "error.no_series_loaded": { en: "Load a series first", th: "กรุณาโหลดซีรีส์ก่อน" },
+  "error.episodes_unavailable": { en: "Episodes {episodes} can't be downloaded", th: "ดาวน์โหลดตอนที่ {episodes} ไม่ได้" },
+  "error.read_failed": { en: "Could not read {path}", th: "อ่านไฟล์ {path} ไม่ได้" },
+  "error.corrupt_file": { en: "{path} was damaged and moved to {backup}", th: "ไฟล์ {path} เสียหาย ย้ายไปไว้ที่ {backup}" },
+  "error.invalid_proxy_url": { en: "Not a valid proxy URL", th: "URL พร็อกซีไม่ถูกต้อง" },
+  "error.unsupported_proxy_scheme": { en: "Unsupported proxy type: {scheme}", th: "ไม่รองรับพร็อกซีประเภท {scheme}" },
+  "error.proxy_without_host": { en: "Proxy URL has no host", th: "URL พร็อกซีไม่มีโฮสต์" },
+  "error.proxy_credentials": { en: "Invalid proxy username or password", th: "ชื่อผู้ใช้หรือรหัสผ่านพร็อกซีไม่ถูกต้อง" },
+  "error.client_failed": { en: "Could not set up the network connection", th: "ตั้งค่าการเชื่อมต่อเครือข่ายไม่ได้" },
+  "error.invalid_cookie_line": { en: "Invalid cookies.txt line {line}: {reason}", th: "บรรทัด {line} ของ cookies.txt ไม่ถูกต้อง: {reason}" },
+  "error.no_active_download": { en: "Episode {episode} is not downloading", th: "ตอนที่ {episode} ไม่ได้กำลังดาวน์โหลด" },
+  "error.nothing_to_import": { en: "Paste links or choose a file to import", th: "วางลิงก์หรือเลือกไฟล์เพื่อนำเข้า" },
+  "error.open_failed": { en: "Could not open {path}", th: "เปิด {path} ไม่ได้" },
+
   // Common
   "common.save": { en: "Save", th: "บันทึก" },
   "common.cancel": { en: "Cancel", th: "ยกเลิก" },
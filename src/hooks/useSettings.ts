@@ -1,4 +1,5 @@
 import { useState, useEffect } from "react";
+import { QualityPreference } from "../types";
 
 export interface Settings {
   concurrentDownloads: number;
@@ -9,6 +10,10 @@ export interface Settings {
   soundEnabled: boolean;
   theme: "dark" | "light" | "system";
   fileNaming: "ep_001" | "episode_1" | "title_ep1";
+  quality: QualityPreference;
+  downloadSubtitles: boolean;
+  embedSubtitles: boolean;
+  seriesCacheHours: number; // how long fetched series are reused before asking the site again
   outputDir: string;
 }
 
@@ -21,6 +26,10 @@ const DEFAULT_SETTINGS: Settings = {
   soundEnabled: true,
   theme: "dark",
   fileNaming: "ep_001",
+  quality: { kind: "highest" },
+  downloadSubtitles: true,
+  embedSubtitles: false,
+  seriesCacheHours: 24,
   outputDir: "~/Downloads/rongyok",
 };
 
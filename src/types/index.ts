@@ -1,15 +1,235 @@
+export interface ProxySettings {
+  enabled: boolean;
+  url: string;
+  username?: string;
+  password?: string;
+  bypass: string[];
+}
+
+export interface ProxyTestResult {
+  reachable: boolean;
+  status?: number;
+  latencyMs: number;
+  error?: string;
+}
+
+export interface CookieInfo {
+  domain: string;
+  path: string;
+  name: string;
+  expires?: number;
+  secure: boolean;
+  httpOnly: boolean;
+}
+
+// A problem in the user's site rules file (sites.json)
+export interface RuleIssue {
+  site?: string; // rule name, or "sites[N]" when unnamed
+  field?: string;
+  message: string;
+  line?: number; // JSON syntax errors only
+  column?: number;
+}
+
+export interface RulesReport {
+  path: string;
+  exists: boolean;
+  sites: string[]; // sites that loaded
+  issues: RuleIssue[];
+}
+
+export interface SeriesSummary {
+  seriesId: number;
+  site: string;
+  url: string;
+  title: string;
+  posterUrl?: string;
+  totalEpisodes?: number;
+}
+
+export interface SeriesPage {
+  url: string;
+  page: number;
+  totalPages?: number;
+  nextPageUrl?: string;
+  series: SeriesSummary[];
+}
+
 export interface SeriesInfo {
   seriesId: number;
+  site: string;
+  sourceUrl: string;
   title: string;
+  description?: string;
+  genres: string[];
+  alternateTitles: string[];
   totalEpisodes: number;
   posterUrl?: string;
   episodeUrls: Record<number, string>;
+  episodes: EpisodeInfo[];
+  resolutionReport: EpisodeResolution[];
+  availability?: Record<number, EpisodeAvailability>; // absent for series cached by older versions
+}
+
+export type EpisodeAvailability =
+  | { status: "available" }
+  | { status: "missing" } // the site lists no video
+  | { status: "unresolved"; reason: string } // episode page failed; a refresh may fix it
+  | { status: "duplicate"; of: number }; // same video as an earlier episode
+
+// Series info as served from the on-disk cache
+export interface CachedSeriesInfo extends SeriesInfo {
+  fetchedAt: number; // unix seconds
+  offline: boolean; // site unreachable, showing an expired copy
+  downloadedEpisodes: number[];
+  targetEpisode?: number; // episode the fetched link pointed at
+}
+
+// A series loaded in the backend this run; downloads resolve against their own series
+export interface LoadedSeriesSummary {
+  seriesId: number;
+  site: string;
+  sourceUrl: string;
+  title: string;
+  posterUrl?: string;
+  totalEpisodes: number;
+}
+
+// A recently viewed series kept in the cache
+export interface CachedSeriesSummary {
+  seriesId: number;
+  site: string;
+  sourceUrl: string;
+  title: string;
+  posterUrl?: string;
+  totalEpisodes: number;
+  fetchedAt: number; // unix seconds
+  viewedAt: number; // unix seconds
+  expired: boolean;
+  downloadedEpisodes: number[];
+}
+
+export interface EpisodeResolution {
+  episode: number;
+  status: "resolved" | "failed";
+  reason?: string;
+  attempts: number;
 }
 
 export interface EpisodeInfo {
   episodeNumber: number;
+  title?: string;
+  thumbnail?: string;
+  duration?: number; // seconds
+  sourcePageUrl?: string;
+  videoUrl?: string;
+  sources: VideoSource[];
+  subtitles: SubtitleTrack[];
+}
+
+export interface SubtitleTrack {
+  url: string;
+  language?: string;
+  label?: string;
+}
+
+export interface VideoSource {
+  url: string;
+  resolution?: number; // vertical pixels, e.g. 720
+  bitrate?: number; // bits per second
+  size?: number; // bytes
+  foundOn?: string; // host of the page (or embedded player) it was found on
+  provenance: "jsonLd" | "scriptJson" | "pattern" | "rule"; // how the URL was found
+}
+
+export type QualityPreference =
+  | { kind: "highest" }
+  | { kind: "lowest" }
+  | { kind: "closest"; height: number }
+  | { kind: "smallest" };
+
+// How a batch is downloaded: the current settings, a preset, or a subscription's
+export interface DownloadOptions {
+  outputDir: string;
+  autoMerge: boolean;
+  concurrentDownloads: number;
+  speedLimit: number; // KB/s, 0 = unlimited
+  fileNaming: string;
+  quality: QualityPreference;
+  downloadSubtitles: boolean;
+  embedSubtitles: boolean;
+}
+
+// One batch, as sent to start_download
+export interface DownloadRequest extends DownloadOptions {
+  seriesId: number;
+  site?: string;
+  episodes: number[];
+  seriesTitle: string;
+}
+
+// A series checked periodically for new episodes
+export interface Subscription {
+  site: string;
+  seriesId: number;
+  title: string;
+  sourceUrl: string;
+  intervalMins: number;
+  download?: DownloadOptions; // set when new episodes are downloaded automatically
+  reported: number[]; // episodes that no longer count as new
+  lastChecked?: number; // unix seconds
+}
+
+// Payload of the new-episodes event
+export interface NewEpisodes {
+  site: string;
+  seriesId: number;
   title: string;
-  videoUrl: string;
+  episodes: number[];
+  enqueued: boolean; // already downloading with the subscription's settings
+}
+
+// Outcome of one line of an imported list of series links
+export type ImportLine = {
+  line: number;
+  text: string;
+  url?: string;
+  series?: LoadedSeriesSummary;
+  error?: AppError;
+} & (
+  | { status: "imported" }
+  | { status: "duplicate"; of: number } // line the series first appeared on
+  | { status: "skipped" } // no link on the line
+  | { status: "invalid" }
+  | { status: "failed" }
+);
+
+export interface ImportReport {
+  lines: ImportLine[];
+  downloads: DownloadRequest[]; // one per imported series when downloading was requested
+}
+
+// Result of probing a selection's video URLs before downloading
+export interface EpisodeProbe {
+  episode: number;
+  url?: string;
+  kind: "progressive" | "hls" | "dash";
+  alive: boolean;
+  status?: number;
+  contentLength?: number; // for HLS/DASH, the playlist's length
+  contentType?: string;
+  acceptsRanges: boolean;
+  size?: number; // bytes; estimated for HLS/DASH
+  error?: string;
+}
+
+export interface SeriesProbe {
+  episodes: EpisodeProbe[];
+  totalSize: number; // bytes, known sizes only
+  unknownSize: number[];
+  dead: number[];
+  bytesPerSec?: number;
+  estimatedSecs?: number;
 }
 
 export interface DownloadProgress {
@@ -20,9 +240,50 @@ export interface DownloadProgress {
   percentage: number;
 }
 
+export type ErrorCode =
+  | "network"
+  | "http_status"
+  | "unsupported_site"
+  | "invalid_url"
+  | "empty_query"
+  | "search_failed"
+  | "url_expired"
+  | "no_source"
+  | "invalid_stream"
+  | "disk_full"
+  | "io"
+  | "cancelled"
+  | "task_failed"
+  | "ffmpeg_not_found"
+  | "no_input"
+  | "no_valid_input"
+  | "ffmpeg_failed"
+  | "no_series_loaded"
+  | "episodes_unavailable"
+  | "read_failed"
+  | "corrupt_file"
+  | "invalid_proxy_url"
+  | "unsupported_proxy_scheme"
+  | "proxy_without_host"
+  | "proxy_credentials"
+  | "client_failed"
+  | "invalid_cookie_line"
+  | "no_active_download"
+  | "nothing_to_import"
+  | "open_failed";
+
+// Errors from commands, download results and merge events
+export interface AppError {
+  code: ErrorCode;
+  message: string; // English fallback
+  details: Record<string, unknown> | null;
+}
+
 export interface DownloadState {
   isDownloading: boolean;
   isPaused: boolean;
+  seriesId?: number; // series the running batch belongs to
+  site?: string;
   currentEpisode: number;
   completedEpisodes: number[];
   failedEpisodes: number[];